@@ -88,7 +88,13 @@ pub struct GetNewBoardStateResponse {
     /// a client gets check/checkmate/stalemate with every move without a
     /// follow-up `/board/status` round-trip. Adjacently tagged (see
     /// `GameStatus`): `{"status":"Checkmate","data":{"winner":"White"}}`
-    /// means White just gave mate and won.
+    /// means White just gave mate and won. `BrainrotWin { winner }`
+    /// reads the same way: the side now to move is frozen by the
+    /// winner's Skibidi. `Won { winner, reason }` covers the game's
+    /// other win conditions (king capture, King of the Hill, …), e.g.
+    /// `{"status":"Won","data":{"winner":"White","reason":"KingOfTheHill"}}`.
+    /// After a Duck Chess piece move the side to move is still the
+    /// mover, who now owes the duck.
     pub status: GameStatus,
 }
 
@@ -102,6 +108,25 @@ pub struct GetStatusResponse {
     pub status: GameStatus,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GetViewRequest {
+    pub board_fen: String,
+    /// Whose view to render.
+    pub seat: Color,
+}
+
+/// A dark-chess seat's view of the position (see `engine::board::fog`).
+/// The server keeps the real FEN; each player is only ever sent this.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetViewResponse {
+    /// The position with every square `seat` can't see blanked and
+    /// enemy passengers / hostages hidden.
+    pub view_fen: String,
+    /// The squares `seat` can see, so a client can draw the fog over
+    /// the rest rather than read blank squares as empty ones.
+    pub visible: Vec<Coord>,
+}
+
 /// JSON error body returned on 4xx. Designed to be self-contained: a
 /// client can log/display this without keeping track of what it sent.
 #[derive(Debug, Serialize)]
@@ -131,6 +156,10 @@ fn move_error_code(err: &MoveError) -> &'static str {
         MoveError::PieceCannotMakeMove { .. } => "piece_cannot_make_move",
         MoveError::WouldLeaveKingInCheck { .. } => "would_leave_king_in_check",
         MoveError::CompelledByTornado { .. } => "compelled_by_tornado",
+        MoveError::MustCapture { .. } => "must_capture",
+        MoveError::MustMoveDuck { .. } => "must_move_duck",
+        MoveError::IllegalDuckMove { .. } => "illegal_duck_move",
+        MoveError::IllegalDrop { .. } => "illegal_drop",
         MoveError::ApplyFailed { .. } => "apply_failed",
     }
 }
@@ -147,7 +176,7 @@ async fn get_new_board_state_handler(
     let game_move = req.game_move.clone();
 
     match board.make_move(game_move) {
-        Ok(()) => {
+        Ok(_) => {
             let new_board_fen = board_to_fen(&board);
             let status = board.status();
             Json(GetNewBoardStateResponse { new_board_fen, status })
@@ -178,6 +207,21 @@ async fn get_status_handler(Json(req): Json<GetStatusRequest>) -> Response {
     Json(GetStatusResponse { status: board.status() }).into_response()
 }
 
+/// Fog-of-war view of a position for one seat. Same structured
+/// 400-on-bad-FEN contract as the other endpoints.
+#[axum::debug_handler]
+async fn get_view_handler(Json(req): Json<GetViewRequest>) -> Response {
+    let board = match fen_to_board(&req.board_fen) {
+        Ok(b) => b,
+        Err(e) => return fen_error_response(e, req.board_fen),
+    };
+    Json(GetViewResponse {
+        view_fen: board_to_fen(&board.visible_to(req.seat)),
+        visible: board.visible_squares(req.seat),
+    })
+    .into_response()
+}
+
 pub async fn serve_api() {
     let cors = CorsLayer::new()
         .allow_origin("*".parse::<http::HeaderValue>().unwrap()) // allow all — dev only
@@ -191,6 +235,7 @@ pub async fn serve_api() {
         .route("/board/moves", post(get_moves_handler))
         .route("/board/new_state", post(get_new_board_state_handler))
         .route("/board/status", post(get_status_handler))
+        .route("/board/view", post(get_view_handler))
         .layer(cors);
 
     let listener = tokio::net::TcpListener::bind(&binding_address)
@@ -318,4 +363,152 @@ mod tests {
             body.status
         );
     }
+
+    /// A position where the side to move is frozen by an enemy
+    /// Skibidi's Brainrot reports `BrainrotWin` over `/board/status`,
+    /// with the same adjacent tagging as the other outcomes.
+    #[tokio::test]
+    async fn status_reports_brainrot_win() {
+        let req = GetStatusRequest {
+            board_fen: "(P=k,C=BRAINROT)7/(P=S(PHASE=2),C=BRAINROT)7/8/8/8/8/8/7K b - - 0 1"
+                .to_string(),
+        };
+        let resp = get_status_handler(Json(req)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("read response body");
+        let body: serde_json::Value =
+            serde_json::from_slice(&bytes).expect("parse response JSON");
+        assert_eq!(
+            body["status"],
+            serde_json::json!({"status": "BrainrotWin", "data": {"winner": "White"}})
+        );
+    }
+
+    /// Any other win condition comes back as `Won`, naming the reason
+    /// alongside the winner.
+    #[tokio::test]
+    async fn status_reports_won_with_reason() {
+        let req = GetStatusRequest {
+            board_fen: "4k3/8/8/8/4K3/8/8/8 b - - 0 1 tr=full p=0 win=checkmate,koth".to_string(),
+        };
+        let resp = get_status_handler(Json(req)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("read response body");
+        let body: serde_json::Value =
+            serde_json::from_slice(&bytes).expect("parse response JSON");
+        assert_eq!(
+            body["status"],
+            serde_json::json!({
+                "status": "Won",
+                "data": {"winner": "White", "reason": "KingOfTheHill"}
+            })
+        );
+    }
+
+    /// Duck Chess over `/board/new_state`: during the duck half-move a
+    /// piece move is a 400 with `must_move_duck`, and placing the duck
+    /// comes back with it in the FEN.
+    #[tokio::test]
+    async fn new_state_enforces_duck_phase() {
+        use engine::board::MoveType;
+
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1 tr=full p=0 \
+                   variants=duck_chess duck_phase=placing";
+        let at = |f, r| Coord { file: f, rank: r };
+
+        let req = GetNewBoardStateRequest {
+            board_fen: fen.to_string(),
+            game_move: GameMove {
+                from: at(3, 6), // d2
+                move_type: MoveType::MoveTo(at(3, 4)), // d4
+            },
+        };
+        let resp = get_new_board_state_handler(Json(req)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("read response body");
+        let body: serde_json::Value =
+            serde_json::from_slice(&bytes).expect("parse response JSON");
+        assert_eq!(body["code"], "must_move_duck");
+
+        let req = GetNewBoardStateRequest {
+            board_fen: fen.to_string(),
+            game_move: GameMove {
+                from: at(4, 3),
+                move_type: MoveType::PlaceDuck { to: at(4, 3) }, // e5
+            },
+        };
+        let resp = get_new_board_state_handler(Json(req)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("read response body");
+        let body: GetNewBoardStateResponse =
+            serde_json::from_slice(&bytes).expect("parse response JSON");
+        assert!(
+            body.new_board_fen.starts_with("rnbqkbnr/pppppppp/8/4(DUCK)3/4P3/8/PPPP1PPP/RNBQKBNR b "),
+            "duck missing from {}",
+            body.new_board_fen
+        );
+        assert_eq!(body.status, GameStatus::Ongoing);
+    }
+
+    /// `/board/view` sends a seat only what it can see, plus the
+    /// visible squares to draw the fog around.
+    #[tokio::test]
+    async fn view_redacts_the_other_side() {
+        let req = GetViewRequest {
+            board_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            seat: Color::White,
+        };
+        let resp = get_view_handler(Json(req)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("read response body");
+        let body: GetViewResponse =
+            serde_json::from_slice(&bytes).expect("parse response JSON");
+        assert!(
+            body.view_fen.starts_with("8/8/8/8/8/8/PPPPPPPP/RNBQKBNR w "),
+            "black pieces leaked into {}",
+            body.view_fen
+        );
+        assert_eq!(body.visible.len(), 32);
+    }
+
+    /// A promotion into a fairy piece comes over the wire like any
+    /// other, and is accepted when the game's `promo=` list has it.
+    #[tokio::test]
+    async fn new_state_accepts_fairy_promotion() {
+        let req: GetNewBoardStateRequest = serde_json::from_value(serde_json::json!({
+            "board_fen": "4k3/P7/8/8/8/8/8/4K3 w - - 0 1 promo=queen,goblin",
+            "game_move": {
+                "from": { "file": 0, "rank": 1 },
+                "move_type": {
+                    "kind": "Promotion",
+                    "target": { "target": { "file": 0, "rank": 0 }, "into": "Goblin" }
+                }
+            }
+        }))
+        .expect("a Goblin promotion deserialises");
+        let resp = get_new_board_state_handler(Json(req)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("read response body");
+        let body: GetNewBoardStateResponse =
+            serde_json::from_slice(&bytes).expect("parse response JSON");
+        assert!(
+            body.new_board_fen.starts_with("(P=G(H=0-0))3k3/"),
+            "{}",
+            body.new_board_fen
+        );
+    }
 }
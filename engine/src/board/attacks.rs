@@ -0,0 +1,354 @@
+//! Cached attack maps.
+//!
+//! `Board::is_attacked_by` used to run the movement stack's threat
+//! path on every call, and `is_in_check`, castling's "passes through
+//! check" test and every king-safety probe call it again for squares
+//! whose answer hasn't changed since the last move. The grid now
+//! carries, per colour, a known/attacked bit pair for every square:
+//! a query is a lookup, and a miss runs the stack once and records the
+//! answer.
+//!
+//! The maps can never go stale. Every mutable accessor on `Grid`
+//! forgets them, so a hand edit of `board.grid` — or any phase of a
+//! move writing a square — leaves a map that is simply empty again.
+//! `make_move_unchecked` then does better than empty: it carries the
+//! pre-move maps forward and forgets only what the move could have
+//! changed. A standard piece's threat on a square depends on its own
+//! square and the squares along the way, so every square sharing a
+//! line with — or a knight's jump from — a square the move wrote is
+//! forgotten. That covers a slider unblocked or blocked, a capture,
+//! castling, en passant, a gate flipping or a duck landing in a
+//! slider's path, brainrot spreading (conditions never change a
+//! threat), and under Atomic a king moving next to a square it now
+//! shields. Non-standard pieces reach further: a carriage threatens the
+//! car ahead wherever it is, a locomotive the next tile of its track, a
+//! carrier whatever its passengers do. The maps remember where each one
+//! reached; after the move a piece whose square was written forgets
+//! everything it reached before and after, and one left standing
+//! forgets only the squares it started or stopped reaching — a train
+//! advancing past it, a junction thrown ahead of it. `unmake_move` puts
+//! the pre-move maps back.
+//!
+//! Answers depend on the ruleset only through Atomic, the one variant
+//! that registers a threat modifier (`AtomicCaptureFilter`: kings
+//! can't capture, touching kings shield each other). The maps note
+//! which of the two they were worked out under, so flipping
+//! `flags.variants` by hand reads as a miss; anything else adding a
+//! `THREAT` modifier must show up in `threat_rules` too.
+
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+use smallvec::{SmallVec, smallvec};
+
+use crate::board::square::Square;
+use crate::board::{Board, Coord, VariantId};
+use crate::movement::rules::RuleSet;
+use crate::movement::stack::king_safety::is_standard_piece;
+use crate::pieces::Color;
+use crate::pieces::piecetype::PieceType;
+
+/// Squares packed into one word: a known bit and an attacked bit each.
+const SQUARES_PER_WORD: usize = 32;
+
+/// A set of squares, one bit each. An 8×8 board's fits inline.
+type Reach = SmallVec<[u64; 1]>;
+
+/// Per-colour "is this square attacked?" answers for one grid. Lives
+/// inside `Grid`; see the module docs for who fills and forgets it.
+///
+/// Atomic words so a shared `&Board` can record what it computes, and
+/// so a pair is written in one store: a reader sees both bits or
+/// neither. An 8×8 board's maps fit inline.
+#[derive(Debug, Default)]
+pub struct AttackMaps {
+    /// White's words, then Black's.
+    words: SmallVec<[AtomicU64; 4]>,
+    /// The `threat_rules` the answers hold under; 0 until a board
+    /// claims them.
+    rules: AtomicU8,
+    /// Where each non-standard piece reached, by square index in grid
+    /// order, when that's been worked out.
+    fairy: Option<Vec<(usize, Reach)>>,
+}
+
+impl AttackMaps {
+    /// Empty maps for a grid of `squares` squares.
+    pub(crate) fn new(squares: usize) -> Self {
+        let per_colour = squares.div_ceil(SQUARES_PER_WORD);
+        AttackMaps {
+            words: (0..2 * per_colour).map(|_| AtomicU64::new(0)).collect(),
+            rules: AtomicU8::new(0),
+            fairy: None,
+        }
+    }
+
+    /// Word index and bit offset of `square`'s pair in `color`'s map.
+    fn slot(&self, square: usize, color: Color) -> Option<(usize, u32)> {
+        let per_colour = self.words.len() / 2;
+        let offset = match color {
+            Color::White => 0,
+            Color::Black => per_colour,
+            Color::Neutral => return None,
+        };
+        let word = square / SQUARES_PER_WORD;
+        (word < per_colour).then(|| (offset + word, 2 * (square % SQUARES_PER_WORD) as u32))
+    }
+
+    /// Whether `square` is attacked by `color`, if that's known.
+    pub(crate) fn get(&self, square: usize, color: Color) -> Option<bool> {
+        let (word, shift) = self.slot(square, color)?;
+        let pair = self.words[word].load(Ordering::Relaxed) >> shift;
+        (pair & 1 != 0).then_some(pair & 2 != 0)
+    }
+
+    /// Record whether `square` is attacked by `color`.
+    pub(crate) fn set(&self, square: usize, color: Color, attacked: bool) {
+        if let Some((word, shift)) = self.slot(square, color) {
+            let pair = 1 | (u64::from(attacked) << 1);
+            self.words[word].fetch_or(pair << shift, Ordering::Relaxed);
+        }
+    }
+
+    /// Do these answers hold under `rules`? Maps no board has claimed
+    /// yet take them on.
+    fn claim(&self, rules: u8) -> bool {
+        match self
+            .rules
+            .compare_exchange(0, rules, Ordering::Relaxed, Ordering::Relaxed)
+        {
+            Ok(_) => true,
+            Err(held) => held == rules,
+        }
+    }
+
+    /// Forget everything.
+    pub(crate) fn clear(&mut self) {
+        for word in &mut self.words {
+            *word.get_mut() = 0;
+        }
+        *self.rules.get_mut() = 0;
+        self.fairy = None;
+    }
+
+    /// Is anything known at all?
+    fn knows_anything(&self) -> bool {
+        self.words.iter().any(|w| w.load(Ordering::Relaxed) != 0)
+    }
+
+    /// Forget `square`, for both colours.
+    fn forget(&mut self, square: usize) {
+        let per_colour = self.words.len() / 2;
+        let (word, shift) = (square / SQUARES_PER_WORD, 2 * (square % SQUARES_PER_WORD));
+        if word < per_colour {
+            *self.words[word].get_mut() &= !(3 << shift);
+            *self.words[per_colour + word].get_mut() &= !(3 << shift);
+        }
+    }
+
+    /// Take on whatever `other` knows. Both must describe the same
+    /// position, so where both know a square they agree.
+    fn absorb(&mut self, other: &AttackMaps) {
+        let theirs = other.rules.load(Ordering::Relaxed);
+        if self.words.len() != other.words.len() || theirs == 0 || !self.claim(theirs) {
+            return;
+        }
+        for (word, theirs) in self.words.iter_mut().zip(&other.words) {
+            *word.get_mut() |= theirs.load(Ordering::Relaxed);
+        }
+    }
+
+    /// The answers alone, without the fairy reach.
+    fn answers(&self) -> Self {
+        AttackMaps {
+            words: self
+                .words
+                .iter()
+                .map(|w| AtomicU64::new(w.load(Ordering::Relaxed)))
+                .collect(),
+            rules: AtomicU8::new(self.rules.load(Ordering::Relaxed)),
+            fairy: None,
+        }
+    }
+}
+
+impl Clone for AttackMaps {
+    fn clone(&self) -> Self {
+        AttackMaps {
+            fairy: self.fairy.clone(),
+            ..self.answers()
+        }
+    }
+}
+
+/// Which threat rules `rules` plays by, as the maps record it. See the
+/// module docs.
+fn threat_rules(rules: RuleSet) -> u8 {
+    1 + u8::from(rules.has(VariantId::Atomic))
+}
+
+/// Does `square` hold a king, standing or riding?
+fn holds_king(square: &Square) -> bool {
+    square.piece.as_ref().is_some_and(|piece| {
+        std::iter::once(piece)
+            .chain(piece.passengers().unwrap_or_default())
+            .any(|p| matches!(p, PieceType::King(_)))
+    })
+}
+
+/// Add `bits` to `into`.
+fn union(into: &mut Reach, bits: impl Iterator<Item = u64>) {
+    for (word, bits) in into.iter_mut().zip(bits) {
+        *word |= bits;
+    }
+}
+
+/// The squares whose answers the fairy pieces could have changed
+/// between `before` and `after`: everything a piece on a `changed`
+/// square reached on either side, and what any other piece started
+/// or stopped reaching. Both lists are in grid order.
+fn fairy_changes(
+    before: &[(usize, Reach)],
+    after: &[(usize, Reach)],
+    changed: impl Fn(usize) -> bool,
+    into: &mut Reach,
+) {
+    let find = |list: &[(usize, Reach)], square: usize| {
+        list.binary_search_by_key(&square, |(sq, _)| *sq)
+            .ok()
+            .map(|at| list[at].1.clone())
+    };
+    for (square, reach) in before {
+        match find(after, *square).filter(|_| !changed(*square)) {
+            Some(now) => union(into, reach.iter().zip(&now).map(|(a, b)| a ^ b)),
+            None => union(into, reach.iter().copied()),
+        }
+    }
+    for (square, reach) in after {
+        if changed(*square) || find(before, *square).is_none() {
+            union(into, reach.iter().copied());
+        }
+    }
+}
+
+impl Board {
+    /// `is_attacked_by` past its Neutral and duck short-circuits: the
+    /// cached answer, or the threat path's, recorded for next time.
+    pub(crate) fn attacked_cached(&self, target: &Coord, attacker: Color) -> bool {
+        let maps = self.grid.attacks();
+        let square = self
+            .grid
+            .index_of(target.file as usize, target.rank as usize);
+        let cache = square.filter(|_| maps.claim(threat_rules(self.ruleset())));
+        if let Some(hit) = cache.and_then(|sq| maps.get(sq, attacker)) {
+            return hit;
+        }
+        let hit = !self
+            .rules()
+            .movement
+            .resolve_threats(self, target, attacker)
+            .is_empty();
+        if let Some(sq) = cache {
+            maps.set(sq, attacker, hit);
+        }
+        hit
+    }
+
+    /// Every square `piece` standing on `from` could threaten: its own
+    /// attacks and, for a carrier, its passengers' from its square.
+    fn reach(&self, from: &Coord, piece: &PieceType) -> Reach {
+        let mut reach: Reach = smallvec![0; self.grid.squares().len().div_ceil(64)];
+        let riders = piece.passengers().unwrap_or_default();
+        for to in std::iter::once(piece)
+            .chain(riders)
+            .flat_map(|p| p.attacks(self, from))
+        {
+            if let Some(sq) = self.grid.index_of(to.file as usize, to.rank as usize) {
+                reach[sq / 64] |= 1 << (sq % 64);
+            }
+        }
+        reach
+    }
+
+    /// Where each non-standard piece on the board reaches, in grid
+    /// order.
+    fn fairy_reach(&self) -> Vec<(usize, Reach)> {
+        self.iter_pieces()
+            .filter(|(_, piece)| !is_standard_piece(piece))
+            .filter_map(|(at, piece)| {
+                let sq = self.grid.index_of(at.file as usize, at.rank as usize)?;
+                Some((sq, self.reach(&at, piece)))
+            })
+            .collect()
+    }
+
+    /// Take the attack maps out ahead of a move, first working out
+    /// where the non-standard pieces reach if the maps know anything
+    /// and that isn't known yet: `carry_attack_maps` needs it to see
+    /// what the move changed.
+    pub(crate) fn take_attack_maps(&mut self) -> AttackMaps {
+        let mut maps = self.grid.take_attacks();
+        if maps.fairy.is_none() && maps.knows_anything() {
+            maps.fairy = Some(self.fairy_reach());
+        }
+        maps
+    }
+
+    /// Bring the pre-move maps `before` across a move whose changed
+    /// squares the grid journaled as `journal`, forgetting the squares
+    /// it could have affected (see the module docs), and keep anything
+    /// the apply phases already learned about the new position.
+    pub(crate) fn carry_attack_maps(&mut self, before: &AttackMaps, journal: &[(usize, Square)]) {
+        let fairy = self.fairy_reach();
+        let mut maps = before.answers();
+        if let Some(was) = &before.fairy {
+            let words = self.grid.squares().len().div_ceil(64);
+            let mut lost: Reach = smallvec![0; words];
+            let changed = |sq: usize| journal.iter().any(|(at, _)| *at == sq);
+            fairy_changes(was, &fairy, changed, &mut lost);
+            let mut around: Vec<usize> = journal.iter().map(|(sq, _)| *sq).collect();
+            // Under Atomic a threat also hangs on where the attacker's
+            // king is, and `find_king` takes the first one it meets: a
+            // king arriving or leaving anywhere can change which one
+            // that is.
+            let kings_moved = journal.iter().any(|(sq, saved)| {
+                holds_king(saved) || self.grid.squares().get(*sq).is_some_and(holds_king)
+            });
+            if kings_moved && self.ruleset().has(VariantId::Atomic) {
+                around.extend(
+                    self.grid
+                        .squares()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, square)| holds_king(square))
+                        .map(|(sq, _)| sq),
+                );
+            }
+            let (width, height) = (self.grid.width() as i32, self.grid.len() as i32);
+            for index in around {
+                let (file, rank) = self.grid.file_rank(index);
+                let (file, rank) = (file as i32, rank as i32);
+                for f in 0..width {
+                    for r in 0..height {
+                        let (df, dr) = ((f - file).abs(), (r - rank).abs());
+                        if df == 0 || dr == 0 || df == dr || df * dr == 2 {
+                            let sq = (r * width + f) as usize;
+                            lost[sq / 64] |= 1 << (sq % 64);
+                        }
+                    }
+                }
+            }
+            for (word, bits) in lost.iter().enumerate() {
+                let mut bits = *bits;
+                while bits != 0 {
+                    maps.forget(word * 64 + bits.trailing_zeros() as usize);
+                    bits &= bits - 1;
+                }
+            }
+        } else {
+            maps.clear();
+        }
+        maps.absorb(self.grid.attacks());
+        maps.fairy = Some(fairy);
+        self.grid.set_attacks(maps);
+    }
+}
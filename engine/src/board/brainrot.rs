@@ -2,7 +2,7 @@ use tracing::trace;
 
 use crate::{
     board::{Board, Coord, square::SquareCondition},
-    pieces::piecetype::PieceType,
+    pieces::{Color, piecetype::PieceType},
 };
 
 /// Manhattan-disk radius for each Skibidi phase per the doc on `Skibidi`.
@@ -32,13 +32,16 @@ impl Board {
     /// The function is idempotent under repeated calls when no
     /// neutralization is pending.
     pub fn recalc_brainrot(&mut self) {
-        // Step 1: clear all existing Brainrot conditions.
-        for row in &mut self.grid {
-            for sq in row {
+        // Step 1: clear all existing Brainrot conditions. Only the
+        // rotted squares are written, so a board without Brainrot
+        // leaves no trace in the move's journal.
+        self.grid.edit_matching(
+            |sq| sq.conditions.contains(&SquareCondition::Brainrot),
+            |sq| {
                 sq.conditions
                     .retain(|c| !matches!(c, SquareCondition::Brainrot));
-            }
-        }
+            },
+        );
 
         // Step 2: collect every Skibidi's coord + current phase.
         let mut skibidis: Vec<(Coord, u8)> = self
@@ -91,6 +94,26 @@ impl Board {
         }
     }
 
+    /// `at` carries Brainrot and lies inside the aura of a `caster`
+    /// Skibidi. The condition itself doesn't record who cast it, so
+    /// this re-derives it from the Skibidis on the board; Brainrot
+    /// authored in a FEN with no Skibidi in range has no caster.
+    pub fn is_brainrotted_by(&self, at: &Coord, caster: Color) -> bool {
+        let rotted = self
+            .get_square_at(at)
+            .is_some_and(|sq| sq.conditions.contains(&SquareCondition::Brainrot));
+        rotted
+            && self.iter_pieces().any(|(coord, piece)| match piece {
+                PieceType::Skibidi(sk) if sk.color == caster => {
+                    let dx = (coord.file as isize - at.file as isize).abs();
+                    let dy = (coord.rank as isize - at.rank as isize).abs();
+                    let radius = phase_to_radius(sk.phase);
+                    radius > 0 && dx + dy > 0 && dx + dy <= radius
+                }
+                _ => false,
+            })
+    }
+
     fn apply_skibidi_brainrot(&mut self, center: &Coord, phase: u8) {
         let radius = phase_to_radius(phase);
         if radius == 0 {
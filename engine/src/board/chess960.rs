@@ -0,0 +1,330 @@
+//! Castling from any back rank: Chess960 rules, Shredder/X-FEN castling
+//! rights, and shuffled starting setups.
+//!
+//! A castling right belongs to one rook. The four `*_can_castle_*`
+//! flags say which rights are held, and `BoardFlags::castle_rook_files`
+//! says where each right's rook stands — `None` for the corner, so
+//! standard chess, on a board of any width, never sets it. The FEN
+//! reads `K`/`Q`/`k`/`q` the X-FEN way, as the outermost rook on that
+//! side of the king, and a file letter the Shredder way (`HAha` is the
+//! standard start). It writes a letter only where `K`/`Q` would name a
+//! different rook.
+//!
+//! Wherever the king and rook start, they end where they would in
+//! standard chess: kingside, the king on the next-to-last file
+//! (`width - 2`) with the rook just inside it; queenside, the king on
+//! the c-file and the rook on the d-file. The king and rook may start
+//! on or pass over each other's squares, but every other square
+//! between either piece and its destination must be empty and
+//! walkable, and the king may not castle out of, through or into check
+//! (see `King::castle_moves`). A castle the king makes in under two
+//! steps is written in UCI as the king taking its own rook.
+//!
+//! `shuffled_back_rank` deals out a Chess960-style starting rank for
+//! any army, fairy pieces included, and `shuffled_start` builds the
+//! position around it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::fen::fen_to_board;
+use crate::board::square::Square;
+use crate::board::{Board, BoardFlags, CastleSide, Coord};
+use crate::pieces::{Color, piecetype::PieceType};
+
+/// Where each castling right's rook stands, for the rights whose rook
+/// isn't on the corner. See `board::chess960`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CastleRookFiles {
+    pub white_kingside: Option<u8>,
+    pub white_queenside: Option<u8>,
+    pub black_kingside: Option<u8>,
+    pub black_queenside: Option<u8>,
+}
+
+impl CastleRookFiles {
+    fn slot_mut(&mut self, color: Color, side: CastleSide) -> Option<&mut Option<u8>> {
+        match (color, side) {
+            (Color::White, CastleSide::Kingside) => Some(&mut self.white_kingside),
+            (Color::White, CastleSide::Queenside) => Some(&mut self.white_queenside),
+            (Color::Black, CastleSide::Kingside) => Some(&mut self.black_kingside),
+            (Color::Black, CastleSide::Queenside) => Some(&mut self.black_queenside),
+            (Color::Neutral, _) => None,
+        }
+    }
+
+    /// The rook file recorded for `color`'s right on `side`; `None`
+    /// for the corner.
+    pub fn get(&self, color: Color, side: CastleSide) -> Option<u8> {
+        match (color, side) {
+            (Color::White, CastleSide::Kingside) => self.white_kingside,
+            (Color::White, CastleSide::Queenside) => self.white_queenside,
+            (Color::Black, CastleSide::Kingside) => self.black_kingside,
+            (Color::Black, CastleSide::Queenside) => self.black_queenside,
+            (Color::Neutral, _) => None,
+        }
+    }
+}
+
+/// Castle geometry: `(king_target_file, rook_target_file)` for `side`
+/// on a board `width` files wide — the king on g and the rook on f, or
+/// the king on c and the rook on d, counting kingside files from the
+/// right edge.
+pub(crate) fn castle_target_files(side: CastleSide, width: u8) -> (u8, u8) {
+    match side {
+        CastleSide::Kingside => (width.saturating_sub(2), width.saturating_sub(3)),
+        CastleSide::Queenside => (2, 3),
+    }
+}
+
+/// The corner file on `side`: where a right's rook stands when
+/// `CastleRookFiles` records none.
+pub fn corner_file(side: CastleSide, width: u8) -> u8 {
+    match side {
+        CastleSide::Kingside => width.saturating_sub(1),
+        CastleSide::Queenside => 0,
+    }
+}
+
+impl BoardFlags {
+    /// Does `color` hold the castling right on `side`? Never for
+    /// Neutral.
+    pub fn can_castle(&self, color: Color, side: CastleSide) -> bool {
+        match (color, side) {
+            (Color::White, CastleSide::Kingside) => self.white_can_castle_kingside,
+            (Color::White, CastleSide::Queenside) => self.white_can_castle_queenside,
+            (Color::Black, CastleSide::Kingside) => self.black_can_castle_kingside,
+            (Color::Black, CastleSide::Queenside) => self.black_can_castle_queenside,
+            (Color::Neutral, _) => false,
+        }
+    }
+
+    /// Give `color` the right to castle on `side` with the rook on
+    /// `rook_file` (`None` for the corner) of a board `width` wide.
+    pub(crate) fn grant_castle(
+        &mut self,
+        color: Color,
+        side: CastleSide,
+        rook_file: Option<u8>,
+        width: u8,
+    ) {
+        let held = match (color, side) {
+            (Color::White, CastleSide::Kingside) => &mut self.white_can_castle_kingside,
+            (Color::White, CastleSide::Queenside) => &mut self.white_can_castle_queenside,
+            (Color::Black, CastleSide::Kingside) => &mut self.black_can_castle_kingside,
+            (Color::Black, CastleSide::Queenside) => &mut self.black_can_castle_queenside,
+            (Color::Neutral, _) => return,
+        };
+        *held = true;
+        // The corner is always stored as `None`, so the same rights
+        // compare and hash the same however they were written.
+        if let Some(slot) = self.castle_rook_files.slot_mut(color, side) {
+            *slot = rook_file.filter(|&f| f != corner_file(side, width));
+        }
+    }
+
+    /// Take away `color`'s castling right on `side`.
+    pub(crate) fn clear_castle(&mut self, color: Color, side: CastleSide) {
+        match (color, side) {
+            (Color::White, CastleSide::Kingside) => self.white_can_castle_kingside = false,
+            (Color::White, CastleSide::Queenside) => self.white_can_castle_queenside = false,
+            (Color::Black, CastleSide::Kingside) => self.black_can_castle_kingside = false,
+            (Color::Black, CastleSide::Queenside) => self.black_can_castle_queenside = false,
+            (Color::Neutral, _) => return,
+        }
+        if let Some(slot) = self.castle_rook_files.slot_mut(color, side) {
+            *slot = None;
+        }
+    }
+}
+
+impl Board {
+    /// `color`'s back rank: the bottom row for White, rank 0 for Black.
+    pub(crate) fn castle_rank(&self, color: Color) -> u8 {
+        match color {
+            Color::Black => 0,
+            Color::White | Color::Neutral => self.height().saturating_sub(1),
+        }
+    }
+
+    /// The square of the rook `color` castles with on `side`, while it
+    /// holds that right. Says nothing about what stands there.
+    pub fn castle_rook_square(&self, color: Color, side: CastleSide) -> Option<Coord> {
+        if !self.flags.can_castle(color, side) {
+            return None;
+        }
+        let file = self
+            .flags
+            .castle_rook_files
+            .get(color, side)
+            .unwrap_or_else(|| corner_file(side, self.width()));
+        Some(Coord {
+            file,
+            rank: self.castle_rank(color),
+        })
+    }
+
+    /// The file of `color`'s king on its back rank, if it's there.
+    pub(crate) fn back_rank_king_file(&self, color: Color) -> Option<u8> {
+        let rank = self.castle_rank(color);
+        (0..self.width()).find(|&file| {
+            matches!(
+                self.get_square_at(&Coord { file, rank }).and_then(|s| s.piece.as_ref()),
+                Some(PieceType::King(k)) if k.color == color
+            )
+        })
+    }
+
+    /// The outermost of `color`'s rooks on `side` of its king, on the
+    /// back rank: the rook an X-FEN `K` or `Q` names. `None` without a
+    /// king on the back rank or a rook beyond it.
+    pub(crate) fn outermost_castle_rook(&self, color: Color, side: CastleSide) -> Option<u8> {
+        let king = self.back_rank_king_file(color)?;
+        let rank = self.castle_rank(color);
+        let is_rook = |file: &u8| {
+            matches!(
+                self.get_square_at(&Coord { file: *file, rank }).and_then(|s| s.piece.as_ref()),
+                Some(PieceType::Rook(r)) if r.color == color
+            )
+        };
+        match side {
+            CastleSide::Kingside => (king + 1..self.width()).rev().find(is_rook),
+            CastleSide::Queenside => (0..king).find(is_rook),
+        }
+    }
+}
+
+/// splitmix64: a small, seedable generator, so the same seed always
+/// deals the same setup.
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A random index below `n`.
+fn random_below(state: &mut u64, n: usize) -> usize {
+    (next_random(state) % n as u64) as usize
+}
+
+/// `army`, one side's back-rank pieces, shuffled Chess960-style by
+/// `seed`. A pair of bishops goes on squares of opposite colours, and
+/// the king between the first two rooks; everything else — fairy
+/// pieces included — goes anywhere. An army without a king and two
+/// rooks is shuffled freely.
+pub fn shuffled_back_rank(army: &[PieceType], seed: u64) -> Vec<PieceType> {
+    let mut state = seed;
+    let mut rank: Vec<Option<PieceType>> = vec![None; army.len()];
+    let mut rest: Vec<PieceType> = army.to_vec();
+
+    let take = |rest: &mut Vec<PieceType>, pick: fn(&PieceType) -> bool| {
+        rest.iter().position(pick).map(|i| rest.remove(i))
+    };
+    let rooks = rest
+        .iter()
+        .filter(|p| matches!(p, PieceType::Rook(_)))
+        .count();
+    let has_king = rest.iter().any(|p| matches!(p, PieceType::King(_)));
+    let castling_trio = if has_king && rooks >= 2 {
+        let king = take(&mut rest, |p| matches!(p, PieceType::King(_)));
+        let left = take(&mut rest, |p| matches!(p, PieceType::Rook(_)));
+        let right = take(&mut rest, |p| matches!(p, PieceType::Rook(_)));
+        [left, king, right].into_iter().collect::<Option<Vec<_>>>()
+    } else {
+        None
+    };
+
+    let bishops = rest
+        .iter()
+        .filter(|p| matches!(p, PieceType::Bishop(_)))
+        .count();
+    if bishops == 2 {
+        for parity in 0..2 {
+            let squares: Vec<usize> = (0..rank.len())
+                .filter(|&f| f % 2 == parity && rank[f].is_none())
+                .collect();
+            if squares.is_empty() {
+                continue;
+            }
+            let file = squares[random_below(&mut state, squares.len())];
+            rank[file] = take(&mut rest, |p| matches!(p, PieceType::Bishop(_)));
+        }
+    }
+
+    let mut free: Vec<usize> = (0..rank.len()).filter(|&f| rank[f].is_none()).collect();
+    // Fisher–Yates over the free squares; the first ones take the
+    // rest of the army, and the last three, left to right, the rook,
+    // king and rook.
+    for i in (1..free.len()).rev() {
+        let j = random_below(&mut state, i + 1);
+        free.swap(i, j);
+    }
+    let (scattered, trio_squares) = free.split_at(rest.len());
+    for (&file, piece) in scattered.iter().zip(rest) {
+        rank[file] = Some(piece);
+    }
+    let mut trio_squares = trio_squares.to_vec();
+    trio_squares.sort_unstable();
+    for (&file, piece) in trio_squares.iter().zip(castling_trio.into_iter().flatten()) {
+        rank[file] = Some(piece);
+    }
+    rank.into_iter().flatten().collect()
+}
+
+/// A starting position for `army` (White's pieces, as
+/// `shuffled_back_rank` takes them): the shuffled back rank with a pawn
+/// in front of every piece, Black mirroring White across the board,
+/// and both sides free to castle with the rooks either side of the
+/// king. `height` must be at least 4.
+pub fn shuffled_start(army: &[PieceType], height: u8, seed: u64) -> Board {
+    let width = army.len() as u8;
+    let empty_row = width.to_string();
+    let grid = vec![empty_row.as_str(); height as usize].join("/");
+    let mut board = fen_to_board(&format!("{grid} w - -")).expect("an empty grid always parses");
+
+    let back = shuffled_back_rank(army, seed);
+    let bottom = height as usize - 1;
+    for (file, piece) in back.into_iter().enumerate() {
+        for (rank, color) in [(0, Color::Black), (bottom, Color::White)] {
+            let mut placed = piece.clone();
+            placed.set_color(color);
+            // A Goblin's home is wherever it starts.
+            if let PieceType::Goblin(goblin) = &mut placed {
+                goblin.home_square = Coord {
+                    file: file as u8,
+                    rank: rank as u8,
+                };
+            }
+            board.grid[rank][file] = Square::new().set_piece(placed);
+        }
+        board.grid[1][file] = Square::new().set_piece(PieceType::new_pawn(Color::Black));
+        board.grid[bottom - 1][file] = Square::new().set_piece(PieceType::new_pawn(Color::White));
+    }
+
+    for color in [Color::White, Color::Black] {
+        let Some(king) = board.back_rank_king_file(color) else {
+            continue;
+        };
+        let rank = board.castle_rank(color);
+        let rook_at = |file: u8| {
+            matches!(
+                board.get_square_at(&Coord { file, rank }).and_then(|s| s.piece.as_ref()),
+                Some(PieceType::Rook(_))
+            )
+        };
+        let kingside = (king + 1..width).find(|&f| rook_at(f));
+        let queenside = (0..king).rev().find(|&f| rook_at(f));
+        if let Some(file) = kingside {
+            board
+                .flags
+                .grant_castle(color, CastleSide::Kingside, Some(file), width);
+        }
+        if let Some(file) = queenside {
+            board
+                .flags
+                .grant_castle(color, CastleSide::Queenside, Some(file), width);
+        }
+    }
+    board
+}
@@ -0,0 +1,186 @@
+//! Crazyhouse (`variants=crazyhouse`).
+//!
+//! A captured piece changes sides: it goes into the taker's pocket
+//! (`BoardFlags::white_pocket` / `black_pocket`), and instead of moving
+//! a piece the side to move may drop one from its pocket onto any empty
+//! square — a `MoveType::Drop`. Pawns can't be dropped on the first or
+//! last rank. Drops come out of the movement stack like any other move
+//! (`DropModifier`, see `movement::stack::crazyhouse`), so king safety
+//! filters them and a drop that blocks a check keeps `status()` from
+//! calling mate.
+//!
+//! Pieces go into the pocket fresh (`pocket_form`): a Bus empty, a
+//! Goblin `Free`, a Skibidi back at phase 1. A dropped Goblin's home is
+//! the square it was dropped on. Kings and train carts are never
+//! pocketed.
+
+use tracing::debug;
+
+use crate::board::{Board, Coord, GameMove, MoveError, MoveType, VariantId};
+use crate::pieces::fairy::{bus::Bus, goblin::Goblin, skibidi::Skibidi};
+use crate::pieces::{Color, piecetype::PieceType};
+
+/// `piece` as it goes into `owner`'s pocket after being captured, or
+/// `None` for the pieces that can't be pocketed (kings, train carts)
+/// and for a Neutral owner.
+pub fn pocket_form(piece: &PieceType, owner: Color) -> Option<PieceType> {
+    if owner == Color::Neutral {
+        return None;
+    }
+    let fresh = match piece {
+        PieceType::King(_) | PieceType::Locomotive(_) | PieceType::Carriage(_) => return None,
+        PieceType::Bus(_) => PieceType::Bus(Bus::new(owner)),
+        // The home square is set when it's dropped.
+        PieceType::Goblin(_) => PieceType::Goblin(Goblin::new(owner, Coord { file: 0, rank: 0 })),
+        PieceType::Skibidi(_) => PieceType::Skibidi(Skibidi::new(owner)),
+        other => {
+            let mut p = other.clone();
+            p.set_color(owner);
+            p
+        }
+    };
+    Some(fresh)
+}
+
+/// Put `piece` into `owner`'s pocket.
+pub(crate) fn add_to_pocket(board: &mut Board, owner: Color, piece: PieceType) {
+    if let Some(pocket) = board.flags.pocket_mut(owner) {
+        pocket.push(piece);
+        sort_pocket(pocket);
+    }
+}
+
+/// Pockets are kept in symbol order, so the same holdings always
+/// compare, hash and serialise the same whatever order they were
+/// captured in.
+pub(crate) fn sort_pocket(pocket: &mut [PieceType]) {
+    pocket.sort_by_key(|p| p.symbol());
+}
+
+impl Board {
+    /// Squares the side to move could drop a pocketed piece on, when
+    /// `color` is that side and the game is Crazyhouse. Feeds
+    /// `move_sources`.
+    pub(crate) fn drop_sources(&self, color: Color) -> Vec<Coord> {
+        if !self.flags.has_variant(VariantId::Crazyhouse)
+            || color != self.flags.side_to_move
+            || self.flags.pocket(color).is_empty()
+        {
+            return Vec::new();
+        }
+        let mut out = Vec::new();
+        for rank in 0..self.height() {
+            for file in 0..self.width() {
+                let at = Coord { file, rank };
+                if self.square_is_empty(&at) {
+                    out.push(at);
+                }
+            }
+        }
+        out
+    }
+
+    /// One `Drop` onto `target` per distinct piece in the side to
+    /// move's pocket, if `target` is empty. No gating on the variant:
+    /// only the Crazyhouse stack asks (`DropModifier`).
+    pub(crate) fn drop_moves_at(&self, target: &Coord) -> Vec<GameMove> {
+        if !self.square_is_empty(target) {
+            return Vec::new();
+        }
+        let back_rank = target.rank == 0 || target.rank + 1 == self.height();
+        let mut pieces: Vec<&PieceType> = Vec::new();
+        for piece in self.flags.pocket(self.flags.side_to_move) {
+            if back_rank && matches!(piece, PieceType::Pawn(_)) {
+                continue;
+            }
+            if !pieces.contains(&piece) {
+                pieces.push(piece);
+            }
+        }
+        pieces
+            .into_iter()
+            .map(|piece| GameMove {
+                from: target.clone(),
+                move_type: MoveType::Drop {
+                    piece: piece.clone(),
+                    target: target.clone(),
+                },
+            })
+            .collect()
+    }
+
+    /// `validate_move` for a drop: `IllegalDrop` unless the drop is
+    /// among the generated moves for its target (which covers the
+    /// variant, the pocket, the side to move and the square), then the
+    /// usual king-safety probe. Like `PlaceDuck`, `from` is ignored.
+    pub(crate) fn validate_drop(&self, game_move: &GameMove) -> Result<(), MoveError> {
+        let MoveType::Drop { piece, target } = &game_move.move_type else {
+            return Err(MoveError::IllegalDrop {
+                from: game_move.from.clone(),
+                attempted: game_move.move_type.clone(),
+            });
+        };
+        let canonical = GameMove {
+            from: target.clone(),
+            move_type: game_move.move_type.clone(),
+        };
+        if !self.get_moves(target).contains(&canonical) {
+            return Err(MoveError::IllegalDrop {
+                from: target.clone(),
+                attempted: canonical.move_type,
+            });
+        }
+        if self.breaks_capture_compulsion(&canonical) {
+            return Err(MoveError::MustCapture {
+                from: target.clone(),
+                attempted: canonical.move_type,
+            });
+        }
+        if !self.ruleset().enforces_check() {
+            return Ok(());
+        }
+        let color = piece.get_color();
+        match self.probe_move(&canonical, |h| {
+            h.map(|h| self.leaves_king_in_check(h, color))
+        }) {
+            Ok(false) => Ok(()),
+            Ok(true) => Err(MoveError::WouldLeaveKingInCheck {
+                from: target.clone(),
+                piece_symbol: piece.symbol().into_owned(),
+                piece_color: color,
+                attempted: canonical.move_type,
+            }),
+            Err(reason) => Err(MoveError::ApplyFailed {
+                from: target.clone(),
+                attempted: canonical.move_type,
+                reason,
+            }),
+        }
+    }
+
+    /// Phase 1 for a drop: take the piece out of its owner's pocket and
+    /// set it down on the target.
+    pub(crate) fn relocate_drop(&mut self, game_move: &GameMove) -> Result<(), String> {
+        let MoveType::Drop { piece, target } = &game_move.move_type else {
+            return Err(format!("{:?} is not a drop", game_move.move_type));
+        };
+        if !self.square_is_empty(target) {
+            return Err(format!("Drop: {target:?} is not an empty square"));
+        }
+        let pocket = self
+            .flags
+            .pocket_mut(piece.get_color())
+            .ok_or_else(|| "Drop: a Neutral piece has no pocket".to_string())?;
+        let index = pocket
+            .iter()
+            .position(|p| p == piece)
+            .ok_or_else(|| format!("Drop: '{}' is not in the pocket", piece.symbol()))?;
+        let mut placed = pocket.remove(index);
+        if let PieceType::Goblin(goblin) = &mut placed {
+            goblin.home_square = target.clone();
+        }
+        self.set_piece_at(target, placed);
+        debug!(?target, piece = %piece.symbol(), "piece dropped");
+        Ok(())
+    }
+}
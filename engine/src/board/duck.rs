@@ -0,0 +1,141 @@
+use tracing::debug;
+
+use crate::board::{
+    Board, Coord, DuckPhase, GameMove, MoveError, MoveType, VariantId, square::Square,
+};
+
+impl Board {
+    /// Is this the duck half-move of a Duck Chess turn? Every legal
+    /// move is then a duck move, whichever square is asked about.
+    pub fn in_duck_placement(&self) -> bool {
+        self.flags.has_variant(VariantId::DuckChess)
+            && self.flags.duck_phase == DuckPhase::DuckPlacement
+    }
+
+    /// Where the duck stands, or `None` before the game's first
+    /// `PlaceDuck`.
+    pub fn duck_square(&self) -> Option<Coord> {
+        self.grid.iter().enumerate().find_map(|(rank, row)| {
+            row.iter().position(|sq| sq.duck).map(|file| Coord {
+                file: file as u8,
+                rank: rank as u8,
+            })
+        })
+    }
+
+    /// Duck moves originating at `from`, regardless of whose half-move
+    /// it is (the movement stack's `DuckPhaseModifier` does that
+    /// gating). With the duck on the board, only its own square has
+    /// any: a `MoveDuck` to every square that can take the duck. Before
+    /// the first placement each such square is the source of its own
+    /// `PlaceDuck`.
+    pub(crate) fn duck_moves_from(&self, from: &Coord) -> Vec<GameMove> {
+        match self.duck_square() {
+            Some(duck) if &duck == from => self
+                .duck_targets()
+                .into_iter()
+                .map(|to| GameMove {
+                    from: from.clone(),
+                    move_type: MoveType::MoveDuck { to },
+                })
+                .collect(),
+            Some(_) => Vec::new(),
+            None if self.get_square_at(from).is_some_and(Square::can_take_duck) => {
+                vec![GameMove {
+                    from: from.clone(),
+                    move_type: MoveType::PlaceDuck { to: from.clone() },
+                }]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// `move_sources` during the duck half-move: the duck's square, or
+    /// every square it could be placed on while it's still off the
+    /// board.
+    pub(crate) fn duck_sources(&self) -> Vec<Coord> {
+        match self.duck_square() {
+            Some(duck) => vec![duck],
+            None => self.duck_targets(),
+        }
+    }
+
+    /// Every square the duck could land on this half-move.
+    fn duck_targets(&self) -> Vec<Coord> {
+        let mut out = Vec::new();
+        for (rank, row) in self.grid.iter().enumerate() {
+            for (file, sq) in row.iter().enumerate() {
+                if sq.can_take_duck() {
+                    out.push(Coord {
+                        file: file as u8,
+                        rank: rank as u8,
+                    });
+                }
+            }
+        }
+        out
+    }
+
+    /// `validate_move` for a duck move, or for any move during the duck
+    /// half-move. A piece move then is `MustMoveDuck`; a duck move
+    /// outside it, or one the duck can't make, is `IllegalDuckMove`.
+    /// `PlaceDuck` ignores `from`, so it's checked against the
+    /// generated move for its target.
+    pub(crate) fn validate_duck_move(&self, game_move: &GameMove) -> Result<(), MoveError> {
+        let illegal = || MoveError::IllegalDuckMove {
+            from: game_move.from.clone(),
+            attempted: game_move.move_type.clone(),
+        };
+        if !game_move.move_type.is_duck_move() {
+            return Err(MoveError::MustMoveDuck {
+                from: game_move.from.clone(),
+                attempted: game_move.move_type.clone(),
+            });
+        }
+        if !self.in_duck_placement() {
+            return Err(illegal());
+        }
+        let canonical = match &game_move.move_type {
+            MoveType::PlaceDuck { to } => GameMove {
+                from: to.clone(),
+                move_type: game_move.move_type.clone(),
+            },
+            _ => game_move.clone(),
+        };
+        if self.get_moves(&canonical.from).contains(&canonical) {
+            Ok(())
+        } else {
+            Err(illegal())
+        }
+    }
+
+    /// Phase 1 for a duck move: lift the duck off its square, if it has
+    /// one yet, and set it down on the target.
+    pub(crate) fn relocate_duck(&mut self, game_move: &GameMove) -> Result<(), String> {
+        let to = match &game_move.move_type {
+            MoveType::PlaceDuck { to } => {
+                if let Some(duck) = self.duck_square() {
+                    return Err(format!("PlaceDuck: the duck is already on {duck:?}"));
+                }
+                to
+            }
+            MoveType::MoveDuck { to } => {
+                let from = &game_move.from;
+                let sq = self
+                    .get_square_mut(from)
+                    .filter(|sq| sq.duck)
+                    .ok_or_else(|| format!("MoveDuck: no duck at {from:?}"))?;
+                sq.duck = false;
+                to
+            }
+            other => return Err(format!("{other:?} is not a duck move")),
+        };
+        let sq = self
+            .get_square_mut(to)
+            .filter(|sq| sq.can_take_duck())
+            .ok_or_else(|| format!("the duck can't land on {to:?}"))?;
+        sq.duck = true;
+        debug!(?to, "duck moved");
+        Ok(())
+    }
+}
@@ -2,8 +2,12 @@ use tracing::{debug, trace, warn};
 
 use crate::{
     board::{
-        Board, BoardFlags, Coord, LastMove, LastMoveKind, SignalId, TrainTickRate,
-        square::{PressureTrigger, Square, SquareCondition, SquareType, TrackDir},
+        Board, BoardFlags, CastleSide, Coord, DuckPhase, LastMove, LastMoveKind,
+        PromotionTarget, SignalId, TrainTickRate, VariantId,
+        chess960::CastleRookFiles,
+        grid::Grid,
+        square::{Conditions, PressureTrigger, Square, SquareCondition, SquareType, TrackDir},
+        win::WinConditionId,
     },
     pieces::{Color, piecetype::PieceType},
 };
@@ -43,8 +47,10 @@ pub enum FenError {
     /// An extended `(...)` block in row `in_row` opened or closed a
     /// paren that never balanced.
     UnbalancedParen { in_row: usize },
-    /// Reserved: a structurally-broken extended square. Not produced by
-    /// the current parser (extended-field slips stay lenient).
+    /// A structurally-broken extended square. Most extended-field slips
+    /// stay lenient; only a state the board can't hold is rejected (a
+    /// `DUCK` sharing its square with a piece, or on terrain the duck
+    /// can't stand on).
     BadExtendedSquare {
         content: String,
         reason: &'static str,
@@ -196,7 +202,8 @@ fn fen_row_to_squares(row: &str, row_idx: usize) -> Result<Vec<Square>, FenError
                 squares.push(Square {
                     piece: None,
                     square_type: SquareType::Standard,
-                    conditions: vec![],
+                    conditions: Conditions::new(),
+                    duck: false,
                 });
             }
             continue;
@@ -314,13 +321,49 @@ pub fn board_to_fen(board: &Board) -> String {
             "w"
         }
     };
-    let castling = castle_rights_to_fen(&board.flags);
+    let castling = castle_rights_to_fen(board);
     let ep = match &board.flags.en_passant_target {
         Some(c) => board.format_coord(c),
         None => "-".to_string(),
     };
+    let hm = board.flags.halfmove_clock;
+    let fm = board.flags.fullmove_number;
     let tr = format_train_tick_rate(&board.flags.train_tick_rate);
     let p = format!("p={}", board.flags.ply_count);
+    let em = match board.flags.extra_moves {
+        0 => String::new(),
+        n => format!(" em={n}"),
+    };
+    let variants = match board.flags.variants.as_slice() {
+        [] => String::new(),
+        vs => format!(
+            " variants={}",
+            vs.iter().map(|v| v.fen_tag()).collect::<Vec<_>>().join(",")
+        ),
+    };
+    let duck_phase = match board.flags.duck_phase {
+        DuckPhase::PieceMove => "",
+        DuckPhase::DuckPlacement => " duck_phase=placing",
+    };
+    let promo = match board.flags.promotion_targets.as_slice() {
+        [] => String::new(),
+        ps => format!(
+            " promo={}",
+            ps.iter().map(|p| p.fen_tag()).collect::<Vec<_>>().join(",")
+        ),
+    };
+    let win = match board.flags.win_conditions.as_slice() {
+        [] => String::new(),
+        ws => format!(
+            " win={}",
+            ws.iter().map(|w| w.fen_tag()).collect::<Vec<_>>().join(",")
+        ),
+    };
+    let checks = match (board.flags.white_checks, board.flags.black_checks) {
+        (0, 0) => String::new(),
+        (w, b) => format!(" checks={w}+{b}"),
+    };
+    let pockets = format_pockets(&board.flags);
     let lm = board
         .flags
         .last_move
@@ -328,7 +371,9 @@ pub fn board_to_fen(board: &Board) -> String {
         .map(format_last_move)
         .map(|s| format!(" {s}"))
         .unwrap_or_default();
-    format!("{grid} {stm} {castling} {ep} {tr} {p}{lm}")
+    format!(
+        "{grid} {stm} {castling} {ep} {hm} {fm} {tr} {p}{em}{variants}{duck_phase}{promo}{win}{checks}{pockets}{lm}"
+    )
 }
 
 fn format_train_tick_rate(rate: &TrainTickRate) -> String {
@@ -374,10 +419,10 @@ fn parse_train_tick_rate(s: &str) -> Option<TrainTickRate> {
             }),
     };
     // Only warn when the input *looks* like a train-tick-rate token
-    // (uses the engine's `tr=` prefix). Standard 6-token chess FENs
-    // shove the halfmove clock and fullmove number into the flag
-    // tail; those are pure-numeric and shouldn't trip a warn for
-    // every external client (perft databases, opening books, etc.).
+    // (uses the engine's `tr=` prefix). Bare tokens reach this parser
+    // only after the tail classifier has ruled out the numeric
+    // halfmove/fullmove fields, but a legacy bare `full`/`ply` is still
+    // accepted silently.
     if parsed.is_none() && s.starts_with("tr=") {
         warn!(s, "could not parse train-tick-rate field; ignoring");
     }
@@ -387,14 +432,22 @@ fn parse_train_tick_rate(s: &str) -> Option<TrainTickRate> {
 fn parse_ply_count(s: &str) -> Option<u32> {
     let parsed = s.strip_prefix("p=").and_then(|n| n.parse::<u32>().ok());
     // Same rationale as `parse_train_tick_rate`: only warn when the
-    // token is prefixed `p=` (engine convention). A bare integer
-    // halfmove clock from a standard FEN shouldn't trigger.
+    // token is prefixed `p=` (engine convention). Bare integers are
+    // the standard halfmove/fullmove fields and never reach here.
     if parsed.is_none() && s.starts_with("p=") {
         warn!(s, "could not parse ply-count field; defaulting to 0");
     }
     parsed
 }
 
+fn parse_extra_moves(s: &str) -> Option<u8> {
+    let parsed = s.strip_prefix("em=").and_then(|n| n.parse::<u8>().ok());
+    if parsed.is_none() {
+        warn!(s, "could not parse extra-moves field; defaulting to 0");
+    }
+    parsed
+}
+
 /// Plan 10 step 2: serialise `LastMove` as a compact FEN field.
 /// Format: `lm=(C=<W|B|N>,F=<file>-<rank>,K=<kind>[,T=<file>-<rank>][,V=<symbol>],P=<symbol>)`.
 ///
@@ -431,6 +484,7 @@ fn format_last_move_kind(kind: LastMoveKind) -> &'static str {
         LastMoveKind::ThrowSwitch => "TS",
         LastMoveKind::PieceInCarrier => "PIC",
         LastMoveKind::PlaceTornado => "PT",
+        LastMoveKind::Drop => "DROP",
     }
 }
 
@@ -445,6 +499,7 @@ fn parse_last_move_kind(s: &str) -> Option<LastMoveKind> {
         "TS" => Some(LastMoveKind::ThrowSwitch),
         "PIC" => Some(LastMoveKind::PieceInCarrier),
         "PT" => Some(LastMoveKind::PlaceTornado),
+        "DROP" => Some(LastMoveKind::Drop),
         _ => None,
     }
 }
@@ -540,8 +595,8 @@ fn parse_last_move(s: &str) -> Option<LastMove> {
         mover_color: mover_color?,
         from: from?,
         to,
-        captured_symbol,
-        primary_symbol: primary_symbol?,
+        captured_symbol: captured_symbol.map(Into::into),
+        primary_symbol: primary_symbol?.into(),
         kind: kind?,
     })
 }
@@ -572,19 +627,42 @@ fn algebraic_to_coord(s: &str, height: u8, width: u8) -> Option<Coord> {
     })
 }
 
-fn castle_rights_to_fen(flags: &BoardFlags) -> String {
+/// The castling field. A right whose rook is the outermost on its side
+/// of the king is written X-FEN style, `K`/`Q`; any other rook by its
+/// file letter, Shredder style (see `board::chess960`).
+fn castle_rights_to_fen(board: &Board) -> String {
     let mut out = String::new();
-    if flags.white_can_castle_kingside {
-        out.push('K');
-    }
-    if flags.white_can_castle_queenside {
-        out.push('Q');
-    }
-    if flags.black_can_castle_kingside {
-        out.push('k');
-    }
-    if flags.black_can_castle_queenside {
-        out.push('q');
+    for color in [Color::White, Color::Black] {
+        for side in [CastleSide::Kingside, CastleSide::Queenside] {
+            let Some(rook) = board.castle_rook_square(color, side) else {
+                continue;
+            };
+            let outermost = board.outermost_castle_rook(color, side);
+            let named = board.flags.castle_rook_files.get(color, side);
+            let as_outermost =
+                outermost == Some(rook.file) || (outermost.is_none() && named.is_none());
+            let letter = if as_outermost {
+                match side {
+                    CastleSide::Kingside => 'K',
+                    CastleSide::Queenside => 'Q',
+                }
+            } else if rook.file < 26 {
+                (b'A' + rook.file) as char
+            } else {
+                warn!(
+                    file = rook.file,
+                    "castling rook beyond file Z; writing it as the outermost"
+                );
+                match side {
+                    CastleSide::Kingside => 'K',
+                    CastleSide::Queenside => 'Q',
+                }
+            };
+            out.push(match color {
+                Color::Black => letter.to_ascii_lowercase(),
+                _ => letter,
+            });
+        }
     }
     if out.is_empty() {
         out.push('-');
@@ -592,24 +670,52 @@ fn castle_rights_to_fen(flags: &BoardFlags) -> String {
     out
 }
 
-fn parse_castle_rights(s: &str) -> (bool, bool, bool, bool) {
+/// Grant the castling rights in FEN field `s` on `board`, whose grid
+/// is already in place: `K`/`Q` name the outermost rook on that side of
+/// the king, a file letter the rook on that file, on the king's side it
+/// stands on. A `K`/`Q` with no such rook keeps the corner, as before
+/// Chess960 support.
+fn read_castle_rights(board: &mut Board, s: &str) {
     if s == "-" {
-        return (false, false, false, false);
+        return;
     }
-    let mut wk = false;
-    let mut wq = false;
-    let mut bk = false;
-    let mut bq = false;
+    let width = board.width();
     for c in s.chars() {
-        match c {
-            'K' => wk = true,
-            'Q' => wq = true,
-            'k' => bk = true,
-            'q' => bq = true,
-            _ => warn!(?c, "unknown castle-rights char"),
+        if !c.is_ascii_alphabetic() {
+            warn!(?c, "unknown castle-rights char");
+            continue;
         }
+        let color = if c.is_ascii_uppercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let (side, rook_file) = match c.to_ascii_uppercase() {
+            'K' => (
+                CastleSide::Kingside,
+                board.outermost_castle_rook(color, CastleSide::Kingside),
+            ),
+            'Q' => (
+                CastleSide::Queenside,
+                board.outermost_castle_rook(color, CastleSide::Queenside),
+            ),
+            letter => {
+                let file = letter as u8 - b'A';
+                if file >= width {
+                    warn!(?c, width, "castle-rights file is off the board");
+                    continue;
+                }
+                let king = board.back_rank_king_file(color).unwrap_or(width / 2);
+                let side = if file > king {
+                    CastleSide::Kingside
+                } else {
+                    CastleSide::Queenside
+                };
+                (side, Some(file))
+            }
+        };
+        board.flags.grant_castle(color, side, rook_file, width);
     }
-    (wk, wq, bk, bq)
 }
 
 /// The board must be rectangular. A single ragged row used to slide
@@ -642,9 +748,17 @@ pub fn fen_to_board(fen: &str) -> Result<Board, FenError> {
     debug!(%fen, "fen_to_board");
 
     // Split off optional flag fields:
-    //   <grid> <stm> <castling> <ep> <train_tick> <ply>
-    // Plan 09 appends `tr=...` and `p=...`. Both are back-compatible: any
-    // FEN without them parses with the defaults (`EveryFullTurn`, 0).
+    //   <grid> <stm> <castling> <ep> [<halfmove> <fullmove>] [tr=..] [p=..] [em=..]
+    //   [variants=..] [duck_phase=..] [promo=..] [win=..] [checks=..] [pk=..]
+    //   [lm=..]
+    // The first four are positional. The tail is classified by token
+    // shape rather than position: bare integers are the standard FEN
+    // halfmove clock then fullmove number, the engine's own fields carry
+    // their `tr=`/`p=`/`em=`/`variants=`/`duck_phase=`/`promo=`/`win=`/
+    // `checks=`/`pk=`/`lm=` prefixes.
+    // Every tail field is optional, so
+    // a standard 6-token FEN, a pre-clock engine FEN (`... - tr=full
+    // p=0`) and a bare grid all parse with the documented defaults.
     let mut parts = fen.split_whitespace();
     // An absent grid token (empty / whitespace-only input) is a hard
     // error rather than a silent 0×0 board.
@@ -655,12 +769,7 @@ pub fn fen_to_board(fen: &str) -> Result<Board, FenError> {
     let stm_part = parts.next();
     let castle_part = parts.next();
     let ep_part = parts.next();
-    let train_part = parts.next();
-    let ply_part = parts.next();
-    // Plan 10 step 2: `lm=(...)` is the most recent move's snapshot.
-    // Lenient parse — any malformed payload coerces to None, matching
-    // the rest of the trailing-field convention.
-    let lm_part = parts.next();
+    let tail = parse_flag_tail(parts);
 
     let rows: Vec<&str> = grid_part.split('/').collect();
     // Clamp height at 255 — `Coord::rank` is `u8`, so beyond-255 rows
@@ -692,6 +801,7 @@ pub fn fen_to_board(fen: &str) -> Result<Board, FenError> {
     if let Some(e) = detect_ragged(&grid) {
         return Err(e);
     }
+    let grid = Grid::from(grid);
 
     let side_to_move = match stm_part {
         Some("w") | None => Color::White,
@@ -702,13 +812,11 @@ pub fn fen_to_board(fen: &str) -> Result<Board, FenError> {
         }
     };
 
-    let (wk, wq, bk, bq) = match castle_part {
-        Some(s) => parse_castle_rights(s),
-        // No castling field provided — fall back to all rights set so that
-        // a bare grid keeps round-tripping. New games (sent with an explicit
-        // castling field) get exactly what the client says.
-        None => (true, true, true, true),
-    };
+    // No castling field provided — fall back to all rights set so that
+    // a bare grid keeps round-tripping. New games (sent with an explicit
+    // castling field) get exactly what the client says, read off the
+    // grid once the board is built.
+    let all_rights = castle_part.is_none();
 
     // Algebraic EP parsing needs to know the board's dimensions to invert
     // the rank correctly. Read them off the grid built above.
@@ -719,25 +827,322 @@ pub fn fen_to_board(fen: &str) -> Result<Board, FenError> {
         Some(s) => algebraic_to_coord(s, height, width),
     };
 
-    let train_tick_rate = train_part
-        .and_then(parse_train_tick_rate)
-        .unwrap_or(TrainTickRate::EveryFullTurn);
-    let ply_count = ply_part.and_then(parse_ply_count).unwrap_or(0);
-    let last_move = lm_part.and_then(parse_last_move);
+    let FlagTail {
+        halfmove_clock,
+        fullmove_number,
+        train_tick_rate,
+        ply_count,
+        extra_moves,
+        variants,
+        duck_phase,
+        promotion_targets,
+        win_conditions,
+        white_checks,
+        black_checks,
+        white_pocket,
+        black_pocket,
+        last_move,
+    } = tail;
 
     let flags = BoardFlags {
         side_to_move,
-        white_can_castle_kingside: wk,
-        white_can_castle_queenside: wq,
-        black_can_castle_kingside: bk,
-        black_can_castle_queenside: bq,
+        white_can_castle_kingside: all_rights,
+        white_can_castle_queenside: all_rights,
+        black_can_castle_kingside: all_rights,
+        black_can_castle_queenside: all_rights,
+        castle_rook_files: CastleRookFiles::default(),
+        promotion_targets,
         en_passant_target,
         train_tick_rate,
         ply_count,
         last_move,
+        halfmove_clock,
+        fullmove_number,
+        extra_moves,
+        variants,
+        duck_phase,
+        win_conditions,
+        white_checks,
+        black_checks,
+        white_pocket,
+        black_pocket,
     };
 
-    Ok(Board { grid, flags })
+    let mut board = Board::new(grid, flags);
+    if let Some(s) = castle_part {
+        read_castle_rights(&mut board, s);
+    }
+    Ok(board)
+}
+
+/// Flag fields that follow the positional `<ep>` token, with their
+/// documented defaults already applied.
+struct FlagTail {
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    train_tick_rate: TrainTickRate,
+    ply_count: u32,
+    extra_moves: u8,
+    variants: Vec<VariantId>,
+    duck_phase: DuckPhase,
+    promotion_targets: Vec<PromotionTarget>,
+    win_conditions: Vec<WinConditionId>,
+    white_checks: u8,
+    black_checks: u8,
+    white_pocket: Vec<PieceType>,
+    black_pocket: Vec<PieceType>,
+    last_move: Option<LastMove>,
+}
+
+/// Classify the FEN tail by token shape. Lenient like the rest of the
+/// flag parsing: a malformed or unrecognised token is warned and
+/// skipped, never a hard error. First occurrence wins for the tagged
+/// fields; a third bare integer is ignored.
+fn parse_flag_tail<'a>(tokens: impl Iterator<Item = &'a str>) -> FlagTail {
+    let mut counters: Vec<u32> = Vec::with_capacity(2);
+    let mut train_tick_rate = None;
+    let mut ply_count = None;
+    let mut extra_moves = None;
+    let mut variants = None;
+    let mut duck_phase = None;
+    let mut promotion_targets = None;
+    let mut win_conditions = None;
+    let mut checks = None;
+    let mut pockets = None;
+    let mut last_move = None;
+    for tok in tokens {
+        if tok.starts_with("p=") {
+            if ply_count.is_none() {
+                ply_count = parse_ply_count(tok);
+            }
+        } else if tok.starts_with("em=") {
+            if extra_moves.is_none() {
+                extra_moves = parse_extra_moves(tok);
+            }
+        } else if let Some(list) = tok.strip_prefix("variants=") {
+            if variants.is_none() {
+                variants = Some(parse_variants(list));
+            }
+        } else if let Some(phase) = tok.strip_prefix("duck_phase=") {
+            if duck_phase.is_none() {
+                duck_phase = parse_duck_phase(phase);
+            }
+        } else if let Some(list) = tok.strip_prefix("promo=") {
+            if promotion_targets.is_none() {
+                promotion_targets = Some(parse_promotion_targets(list));
+            }
+        } else if let Some(list) = tok.strip_prefix("win=") {
+            if win_conditions.is_none() {
+                win_conditions = Some(parse_win_conditions(list));
+            }
+        } else if let Some(counts) = tok.strip_prefix("checks=") {
+            if checks.is_none() {
+                checks = parse_checks(counts);
+            }
+        } else if let Some(body) = tok.strip_prefix("pk=") {
+            if pockets.is_none() {
+                pockets = parse_pockets(body);
+            }
+        } else if tok.starts_with("lm=") {
+            // Plan 10 step 2: `lm=(...)` is the most recent move's
+            // snapshot. Any malformed payload coerces to None.
+            if last_move.is_none() {
+                last_move = parse_last_move(tok);
+            }
+        } else if !tok.is_empty() && tok.bytes().all(|b| b.is_ascii_digit()) {
+            if counters.len() == 2 {
+                warn!(tok, "extra numeric FEN field; ignoring");
+                continue;
+            }
+            // An out-of-range value still occupies its slot so the
+            // halfmove/fullmove pairing stays aligned.
+            counters.push(tok.parse::<u32>().unwrap_or_else(|_| {
+                warn!(tok, "numeric FEN field out of range; defaulting to 0");
+                0
+            }));
+        } else if let Some(rate) = parse_train_tick_rate(tok) {
+            train_tick_rate.get_or_insert(rate);
+        } else if !tok.starts_with("tr=") {
+            // `parse_train_tick_rate` already warned for a bad `tr=`.
+            warn!(tok, "unknown FEN flag field; ignoring");
+        }
+    }
+    FlagTail {
+        halfmove_clock: counters.first().copied().unwrap_or(0),
+        // Fullmove numbering starts at 1; a `0` from a sloppy exporter
+        // is normalised rather than propagated.
+        fullmove_number: counters.get(1).copied().filter(|n| *n > 0).unwrap_or(1),
+        train_tick_rate: train_tick_rate.unwrap_or(TrainTickRate::EveryFullTurn),
+        ply_count: ply_count.unwrap_or(0),
+        extra_moves: extra_moves.unwrap_or(0),
+        variants: variants.unwrap_or_default(),
+        duck_phase: duck_phase.unwrap_or_default(),
+        promotion_targets: promotion_targets.unwrap_or_default(),
+        win_conditions: win_conditions.unwrap_or_default(),
+        white_checks: checks.map_or(0, |(w, _)| w),
+        black_checks: checks.map_or(0, |(_, b)| b),
+        white_pocket: pockets.as_ref().map_or_else(Vec::new, |(w, _)| w.clone()),
+        black_pocket: pockets.map_or_else(Vec::new, |(_, b)| b),
+        last_move,
+    }
+}
+
+/// Parse the body of a `variants=` field: comma-separated variant ids.
+/// Unknown ids are warned and dropped; a repeated id counts once.
+fn parse_variants(list: &str) -> Vec<VariantId> {
+    let mut out = Vec::new();
+    for tag in list.split(',').filter(|t| !t.is_empty()) {
+        match VariantId::parse_tag(tag) {
+            Some(v) if !out.contains(&v) => out.push(v),
+            Some(_) => warn!(tag, "repeated variant id; ignoring"),
+            None => warn!(tag, "unknown variant id; ignoring"),
+        }
+    }
+    out
+}
+
+/// Parse the body of a `promo=` field: comma-separated promotion
+/// targets, in the order their moves are generated. Unknown ids are
+/// warned and dropped; a repeated id counts once.
+fn parse_promotion_targets(list: &str) -> Vec<PromotionTarget> {
+    let mut out = Vec::new();
+    for tag in list.split(',').filter(|t| !t.is_empty()) {
+        match PromotionTarget::parse_tag(tag) {
+            Some(p) if !out.contains(&p) => out.push(p),
+            Some(_) => warn!(tag, "repeated promotion target; ignoring"),
+            None => warn!(tag, "unknown promotion target; ignoring"),
+        }
+    }
+    out
+}
+
+/// Parse the body of a `win=` field: comma-separated win-condition
+/// ids. Unknown ids are warned and dropped, as is a repeat — including
+/// a second `<n>check`, whatever its count.
+fn parse_win_conditions(list: &str) -> Vec<WinConditionId> {
+    let mut out: Vec<WinConditionId> = Vec::new();
+    for tag in list.split(',').filter(|t| !t.is_empty()) {
+        match WinConditionId::parse_tag(tag) {
+            Some(w)
+                if !out
+                    .iter()
+                    .any(|o| std::mem::discriminant(o) == std::mem::discriminant(&w)) =>
+            {
+                out.push(w)
+            }
+            Some(_) => warn!(tag, "repeated win condition; ignoring"),
+            None => warn!(tag, "unknown win condition; ignoring"),
+        }
+    }
+    out
+}
+
+/// Parse the body of a `checks=<w>+<b>` field. Malformed counts are
+/// warned and leave both at 0.
+fn parse_checks(s: &str) -> Option<(u8, u8)> {
+    let parsed = s
+        .split_once('+')
+        .and_then(|(w, b)| Some((w.parse().ok()?, b.parse().ok()?)));
+    if parsed.is_none() {
+        warn!(s, "bad checks field; expected <white>+<black>");
+    }
+    parsed
+}
+
+/// The `pk=(W=<pieces>,B=<pieces>)` field, or nothing while both
+/// pockets are empty. Each piece is its glyph without any payload —
+/// pocketed pieces are fresh, so it carries none — with multi-letter
+/// glyphs in parentheses like a grid cell: `pk=(W=N(BUS)PP,B=q)`.
+fn format_pockets(flags: &BoardFlags) -> String {
+    let side = |key: &str, pocket: &[PieceType]| {
+        if pocket.is_empty() {
+            return None;
+        }
+        let glyphs: String = pocket
+            .iter()
+            .map(|p| {
+                let symbol = p.symbol();
+                let glyph = symbol.split('(').next().unwrap_or_default();
+                if glyph.chars().count() == 1 {
+                    glyph.to_string()
+                } else {
+                    format!("({glyph})")
+                }
+            })
+            .collect();
+        Some(format!("{key}={glyphs}"))
+    };
+    let sides: Vec<String> = [
+        side("W", &flags.white_pocket),
+        side("B", &flags.black_pocket),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if sides.is_empty() {
+        String::new()
+    } else {
+        format!(" pk=({})", sides.join(","))
+    }
+}
+
+/// Parse the body of a `pk=` field into (White's, Black's) pockets.
+/// Each piece takes the colour of the side it's listed under. Unknown
+/// glyphs and pieces that can't be pocketed (kings, train carts) are
+/// warned and dropped; a body that isn't `(...)` is warned and leaves
+/// both pockets empty.
+fn parse_pockets(body: &str) -> Option<(Vec<PieceType>, Vec<PieceType>)> {
+    let Some(inner) = body.strip_prefix('(').and_then(|b| b.strip_suffix(')')) else {
+        warn!(body, "bad pk field; expected (W=..,B=..)");
+        return None;
+    };
+    let mut white = Vec::new();
+    let mut black = Vec::new();
+    for field in split_top_level(inner) {
+        let (owner, pocket) = match field.split_once('=') {
+            Some(("W", list)) => (Color::White, list),
+            Some(("B", list)) => (Color::Black, list),
+            _ => {
+                warn!(field, "unknown pk side; expected W= or B=");
+                continue;
+            }
+        };
+        let out = if owner == Color::White {
+            &mut white
+        } else {
+            &mut black
+        };
+        let mut chars = pocket.chars();
+        while let Some(ch) = chars.next() {
+            let glyph = if ch == '(' {
+                chars.by_ref().take_while(|c| *c != ')').collect()
+            } else {
+                ch.to_string()
+            };
+            match PieceType::symbol_to_piece(&glyph)
+                .and_then(|p| crate::board::crazyhouse::pocket_form(&p, owner))
+            {
+                Some(p) => out.push(p),
+                None => warn!(glyph, "piece can't be pocketed; ignoring"),
+            }
+        }
+    }
+    crate::board::crazyhouse::sort_pocket(&mut white);
+    crate::board::crazyhouse::sort_pocket(&mut black);
+    Some((white, black))
+}
+
+/// Parse the body of a `duck_phase=` field. Anything but `piece` or
+/// `placing` is warned and falls back to the default.
+fn parse_duck_phase(s: &str) -> Option<DuckPhase> {
+    match s {
+        "piece" => Some(DuckPhase::PieceMove),
+        "placing" => Some(DuckPhase::DuckPlacement),
+        other => {
+            warn!(other, "bad duck_phase field; expected piece or placing");
+            None
+        }
+    }
 }
 
 pub fn square_to_fen(square: &Square) -> String {
@@ -745,12 +1150,13 @@ pub fn square_to_fen(square: &Square) -> String {
         .piece
         .as_ref()
         .map(|p| p.symbol())
-        .unwrap_or("".to_string());
-    let is_standard_square =
-        matches!(square.square_type, SquareType::Standard) && square.conditions.is_empty();
+        .unwrap_or_default();
+    let is_standard_square = matches!(square.square_type, SquareType::Standard)
+        && square.conditions.is_empty()
+        && !square.duck;
 
     if piece_symbol.len() == 1 && is_standard_square {
-        return piece_symbol; // e.g., "P" or "r"
+        return piece_symbol.into_owned(); // e.g., "P" or "r"
     }
 
     // Non-standard notation
@@ -770,7 +1176,8 @@ pub fn square_to_fen(square: &Square) -> String {
             SquareType::Standard
             | SquareType::Turret
             | SquareType::Vent
-            | SquareType::Block => {}
+            | SquareType::Block
+            | SquareType::Hill => {}
             SquareType::Switch { targets } => {
                 parts.push(format!("TARGETS={}", format_id_list(targets)));
             }
@@ -797,6 +1204,10 @@ pub fn square_to_fen(square: &Square) -> String {
         }
     }
 
+    if square.duck {
+        parts.push("DUCK".to_string());
+    }
+
     for cond in &square.conditions {
         parts.push(format!("C={}", cond.to_fen()));
     }
@@ -968,7 +1379,8 @@ pub fn fen_to_square(fen: &str) -> Result<Square, FenError> {
         return Ok(Square {
             piece: None,
             square_type: SquareType::Standard,
-            conditions: vec![],
+            conditions: Conditions::new(),
+            duck: false,
         });
     }
 
@@ -976,7 +1388,8 @@ pub fn fen_to_square(fen: &str) -> Result<Square, FenError> {
     if fen.starts_with('(') && fen.ends_with(')') {
         let inner = &fen[1..fen.len() - 1];
         let mut piece: Option<PieceType> = None;
-        let mut conditions = Vec::new();
+        let mut conditions = Conditions::new();
+        let mut duck = false;
 
         // Variant payload accumulators — buffered through the loop and
         // collapsed into the right `SquareType` by `type_tag` once every
@@ -1013,6 +1426,8 @@ pub fn fen_to_square(fen: &str) -> Result<Square, FenError> {
                 "T" => {
                     type_tag = Some(value.to_string());
                 }
+                // Plan 11: value-less, like a bare condition tag.
+                "DUCK" => duck = true,
                 "ID" => match value.parse::<SignalId>() {
                     Ok(v) => id = Some(v),
                     Err(e) => warn!(value, ?e, "bad ID field"),
@@ -1099,6 +1514,7 @@ pub fn fen_to_square(fen: &str) -> Result<Square, FenError> {
             Some("TURRET") => SquareType::Turret,
             Some("VENT") => SquareType::Vent,
             Some("BLOCK") => SquareType::Block,
+            Some("HILL") => SquareType::Hill,
             Some("SWITCH") => SquareType::Switch {
                 targets: targets.unwrap_or_default(),
             },
@@ -1146,10 +1562,24 @@ pub fn fen_to_square(fen: &str) -> Result<Square, FenError> {
             }
         };
 
+        if duck && piece.is_some() {
+            return Err(FenError::BadExtendedSquare {
+                content: fen.to_string(),
+                reason: "the duck can't share a square with a piece",
+            });
+        }
+        if duck && !square_type.holds_duck() {
+            return Err(FenError::BadExtendedSquare {
+                content: fen.to_string(),
+                reason: "the duck can't stand on this terrain",
+            });
+        }
+
         return Ok(Square {
             piece,
             square_type,
             conditions,
+            duck,
         });
     }
 
@@ -1160,7 +1590,8 @@ pub fn fen_to_square(fen: &str) -> Result<Square, FenError> {
         Some(p) => Ok(Square {
             piece: Some(p),
             square_type: SquareType::Standard,
-            conditions: vec![],
+            conditions: Conditions::new(),
+            duck: false,
         }),
         None => Err(FenError::UnknownPieceSymbol(fen.to_string())),
     }
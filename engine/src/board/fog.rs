@@ -0,0 +1,204 @@
+//! Fog of war: a board as one side sees it, for dark-chess games.
+//!
+//! A side sees the squares its pieces stand on, every square one of
+//! them could move to, and every square one of them attacks — the
+//! pseudo-legal moves from `resolve_moves` and the threats from
+//! `resolve_threats`, worked out as if it were that side's turn. A
+//! piece riding in a carrier sees from the carrier's square, so a
+//! passenger in a Neutral train cart sees for its own side. Drops give
+//! no sight: they reach every empty square, and would show which ones
+//! are empty.
+//!
+//! `Board::visible_to` blanks everything else. A fogged square keeps
+//! only its kind of terrain: its piece, duck and conditions are
+//! removed — tornado countdowns included — and so is its signal
+//! wiring and state, since a gate or junction that changes out of
+//! sight gives away the plate that was stepped on. A fogged gate or
+//! junction reads as the FEN defaults — ID 0, open, state 0 — and a
+//! fogged switch or plate has no targets; those are placeholders, not
+//! information.
+//!
+//! On the squares a side does see, an enemy carrier shows only the
+//! passengers of the viewer's own colour — an enemy Bus looks empty,
+//! a train cart shows just the viewer's riders — and a Goblin's hostage
+//! shows only when the Goblin or the hostage is the viewer's. The
+//! en passant target and the last move are kept only when they don't
+//! reach into the fog. Pockets, castling rights and the clocks are
+//! public.
+//!
+//! The view is a `Board` so it can go through `board_to_fen` as is,
+//! but it's a picture, not a position: play goes on on the real board.
+
+use crate::board::chess960::castle_target_files;
+use crate::board::square::{Square, SquareType};
+use crate::board::{Board, Coord, DuckPhase, MoveType};
+use crate::pieces::fairy::goblin::GoblinState;
+use crate::pieces::{Color, piecetype::PieceType};
+
+/// The squares `game_move` shows its mover, who stands on `from` of a
+/// board `width` files wide.
+fn sighted(from: &Coord, move_type: &MoveType, width: u8) -> Vec<Coord> {
+    match move_type {
+        MoveType::MoveTo(c)
+        | MoveType::Promotion { target: c, .. }
+        | MoveType::MoveIntoCarrier(c)
+        | MoveType::PlaceTornado { target: c }
+        | MoveType::ThrowSwitch { switch: c } => vec![c.clone()],
+        MoveType::EnPassant { target, captured } => vec![target.clone(), captured.clone()],
+        MoveType::Castle { side } => vec![Coord {
+            file: castle_target_files(*side, width).0,
+            rank: from.rank,
+        }],
+        MoveType::PieceInCarrier { move_type, .. } => sighted(from, move_type, width),
+        MoveType::PhaseShift
+        | MoveType::PlaceDuck { .. }
+        | MoveType::MoveDuck { .. }
+        | MoveType::Drop { .. } => Vec::new(),
+    }
+}
+
+/// What's left of a square in the fog.
+fn fog_square(square: &mut Square) {
+    square.piece = None;
+    square.duck = false;
+    square.conditions.clear();
+    square.square_type = match &square.square_type {
+        SquareType::Switch { .. } => SquareType::Switch {
+            targets: Vec::new(),
+        },
+        SquareType::PressurePlate { fires_for, .. } => SquareType::PressurePlate {
+            targets: Vec::new(),
+            fires_for: fires_for.clone(),
+        },
+        SquareType::Junction { branches, .. } => SquareType::Junction {
+            id: 0,
+            state: 0,
+            branches: branches.clone(),
+        },
+        SquareType::Gate { .. } => SquareType::Gate { id: 0, open: true },
+        other => other.clone(),
+    };
+}
+
+/// Hide what `viewer` can't see inside a piece it can see.
+fn redact_piece(piece: &mut PieceType, viewer: Color) {
+    if piece.get_color() == viewer {
+        return;
+    }
+    if let Some(passengers) = piece.passengers_mut() {
+        passengers.retain(|p| p.get_color() == viewer);
+    }
+    if let PieceType::Goblin(goblin) = piece
+        && let GoblinState::Kidnapping { piece: hostage } = &goblin.state
+        && hostage.get_color() != viewer
+    {
+        goblin.state = GoblinState::Free;
+    }
+}
+
+impl Board {
+    /// Which squares `color` can see, as `[rank][file]` flags.
+    fn sight(&self, color: Color) -> Vec<Vec<bool>> {
+        let mut seen: Vec<Vec<bool>> = self.grid.iter().map(|row| vec![false; row.len()]).collect();
+        if color == Color::Neutral {
+            return seen;
+        }
+        let mut mark = |c: &Coord| {
+            if let Some(cell) = seen
+                .get_mut(c.rank as usize)
+                .and_then(|row| row.get_mut(c.file as usize))
+            {
+                *cell = true;
+            }
+        };
+        // Moves and threats as on `color`'s piece half-move, whoever is
+        // to move: some modifiers only answer for the side to move.
+        let mut probe = self.clone();
+        probe.flags.side_to_move = color;
+        probe.flags.duck_phase = DuckPhase::PieceMove;
+        let stack = &probe.rules().movement;
+        for (coord, piece) in self.iter_pieces() {
+            let rides = piece
+                .passengers()
+                .into_iter()
+                .flatten()
+                .any(|p| p.get_color() == color);
+            if piece.get_color() != color && !rides {
+                continue;
+            }
+            mark(&coord);
+            for m in stack.resolve_moves(&probe, &coord) {
+                if probe.effective_mover_color(piece, &m).0 == color {
+                    for c in sighted(&coord, &m.move_type, self.width()) {
+                        mark(&c);
+                    }
+                }
+            }
+        }
+        for rank in 0..self.height() {
+            for file in 0..self.width() {
+                let at = Coord { file, rank };
+                if !stack.resolve_threats(&probe, &at, color).is_empty() {
+                    mark(&at);
+                }
+            }
+        }
+        seen
+    }
+
+    /// The squares `color` can see (see `board::fog`), rank by rank.
+    pub fn visible_squares(&self, color: Color) -> Vec<Coord> {
+        let seen = self.sight(color);
+        let mut out = Vec::new();
+        for (rank, row) in seen.iter().enumerate() {
+            for (file, visible) in row.iter().enumerate() {
+                if *visible {
+                    out.push(Coord {
+                        file: file as u8,
+                        rank: rank as u8,
+                    });
+                }
+            }
+        }
+        out
+    }
+
+    /// This board as `color` sees it under fog of war: every square it
+    /// can't see blanked, and what it can see redacted (see
+    /// `board::fog`). A Neutral viewer sees nothing. The view is a
+    /// fresh board with no history: a hash of the real position would
+    /// give away what the fog hides.
+    pub fn visible_to(&self, color: Color) -> Board {
+        let seen = self.sight(color);
+        let visible = |c: &Coord| {
+            seen.get(c.rank as usize)
+                .and_then(|row| row.get(c.file as usize))
+                .copied()
+                .unwrap_or(false)
+        };
+        let mut view = Board::new(self.grid.clone(), self.flags.clone());
+        for (rank, row) in view.grid.iter_mut().enumerate() {
+            for (file, square) in row.iter_mut().enumerate() {
+                if !seen[rank][file] {
+                    fog_square(square);
+                } else if let Some(piece) = square.piece.as_mut() {
+                    redact_piece(piece, color);
+                }
+            }
+        }
+        if view
+            .flags
+            .en_passant_target
+            .as_ref()
+            .is_some_and(|ep| !visible(ep))
+        {
+            view.flags.en_passant_target = None;
+        }
+        if view.flags.last_move.as_ref().is_some_and(|lm| {
+            lm.mover_color != color && !(visible(&lm.from) && lm.to.as_ref().is_none_or(visible))
+        }) {
+            view.flags.last_move = None;
+        }
+        view
+    }
+}
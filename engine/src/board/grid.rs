@@ -0,0 +1,443 @@
+//! Flat board storage.
+//!
+//! `Board::grid` used to be a `Vec<Vec<Square>>`: one heap block per
+//! rank plus the outer one, so every clone of a board — a game's
+//! snapshot, a scratch probe that can't be synced in place — paid for
+//! `height + 1` allocations. `Grid` keeps
+//! the squares in a single row-major `Vec`, `width` to a rank, so a
+//! clone is one allocation and one copy.
+//!
+//! It still reads like the nested grid: `grid[rank][file]` indexes a
+//! rank and then a square, `len()` is the number of ranks, and
+//! iterating yields the ranks as slices, top (rank 0) first. Serde
+//! sees the same nested list of ranks as before, so JSON boards are
+//! unchanged. A grid is always rectangular: building one from ragged
+//! ranks panics (`From`) or fails (`from_ranks`, deserialising).
+//!
+//! The grid also carries the board's cached `AttackMaps`. Every
+//! mutable accessor forgets them before handing out the squares, so
+//! no write — a move phase or a hand edit — can leave them stale.
+//! They play no part in equality, `Debug` or serde.
+//!
+//! While a move is being applied the same accessors keep a journal:
+//! the first time a square is handed out mutably, its contents are
+//! saved. That's the move's undo record and the set of squares its
+//! hash and attack maps have to account for (see `board::undo`), and
+//! it costs one square clone per square written, nothing per square
+//! left alone. Writes that only touch some squares of a sweep go
+//! through `edit_matching`, so the rest aren't journaled.
+
+use std::fmt;
+use std::ops::{Index, IndexMut, Range};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::board::attacks::AttackMaps;
+use crate::board::square::Square;
+
+#[derive(Default)]
+pub struct Grid {
+    squares: Vec<Square>,
+    width: usize,
+    height: usize,
+    attacks: AttackMaps,
+    /// Pre-write contents of the squares written since
+    /// `start_journal`, by index, in first-write order. `None` when no
+    /// move is being applied.
+    journal: Option<Vec<(usize, Square)>>,
+}
+
+impl Clone for Grid {
+    /// A copy of the squares and attack maps. A journal in progress
+    /// belongs to the move writing this grid, and isn't copied.
+    fn clone(&self) -> Self {
+        Grid {
+            squares: self.squares.clone(),
+            width: self.width,
+            height: self.height,
+            attacks: self.attacks.clone(),
+            journal: None,
+        }
+    }
+
+    /// Reuses this grid's buffer, and each square's, where it can.
+    /// Leaves this grid's journal alone.
+    fn clone_from(&mut self, source: &Self) {
+        self.squares.clone_from(&source.squares);
+        self.width = source.width;
+        self.height = source.height;
+        self.attacks.clone_from(&source.attacks);
+    }
+}
+
+impl PartialEq for Grid {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.squares == other.squares
+    }
+}
+
+/// The ranks handed to `Grid::from_ranks` weren't all the same length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaggedGrid {
+    pub rank: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for RaggedGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rank {} has {} squares, expected {}",
+            self.rank, self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for RaggedGrid {}
+
+impl Grid {
+    /// A `width` × `height` grid of `square`.
+    pub fn filled(width: usize, height: usize, square: Square) -> Self {
+        Grid {
+            squares: vec![square; width * height],
+            width,
+            height,
+            attacks: AttackMaps::new(width * height),
+            journal: None,
+        }
+    }
+
+    /// Number of ranks.
+    pub fn len(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.height == 0
+    }
+
+    /// Squares per rank.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Every square, rank 0 first, files in order within a rank.
+    pub fn squares(&self) -> &[Square] {
+        &self.squares
+    }
+
+    pub fn squares_mut(&mut self) -> &mut [Square] {
+        self.note(0..self.squares.len());
+        self.attacks.clear();
+        &mut self.squares
+    }
+
+    /// The square at `(file, rank)`, if it's on the grid.
+    pub fn square(&self, file: usize, rank: usize) -> Option<&Square> {
+        if file < self.width && rank < self.height {
+            self.squares.get(rank * self.width + file)
+        } else {
+            None
+        }
+    }
+
+    pub fn square_mut(&mut self, file: usize, rank: usize) -> Option<&mut Square> {
+        let index = self.index_of(file, rank)?;
+        self.note(index..index + 1);
+        self.attacks.clear();
+        self.squares.get_mut(index)
+    }
+
+    /// Position of `(file, rank)` in `squares()`, if it's on the grid.
+    pub fn index_of(&self, file: usize, rank: usize) -> Option<usize> {
+        (file < self.width && rank < self.height).then(|| rank * self.width + file)
+    }
+
+    /// `(file, rank)` of position `index` in `squares()`.
+    pub fn file_rank(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    /// Run `edit` on every square `pick` selects. Only those count as
+    /// written: the rest are neither journaled nor forgotten by the
+    /// attack maps, as they would be through `iter_mut`.
+    pub fn edit_matching(
+        &mut self,
+        pick: impl Fn(&Square) -> bool,
+        mut edit: impl FnMut(&mut Square),
+    ) {
+        for index in 0..self.squares.len() {
+            if pick(&self.squares[index]) {
+                self.note(index..index + 1);
+                self.attacks.clear();
+                edit(&mut self.squares[index]);
+            }
+        }
+    }
+
+    /// Rank `rank`, if there is one.
+    pub fn get(&self, rank: usize) -> Option<&[Square]> {
+        (rank < self.height).then(|| &self[rank])
+    }
+
+    pub fn get_mut(&mut self, rank: usize) -> Option<&mut [Square]> {
+        (rank < self.height).then(|| &mut self[rank])
+    }
+
+    pub fn first(&self) -> Option<&[Square]> {
+        self.get(0)
+    }
+
+    /// The ranks, top first.
+    pub fn iter(&self) -> Rows<'_> {
+        Rows {
+            rest: &self.squares,
+            width: self.width,
+            left: self.height,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> RowsMut<'_> {
+        self.note(0..self.squares.len());
+        self.attacks.clear();
+        RowsMut {
+            rest: &mut self.squares,
+            width: self.width,
+            left: self.height,
+        }
+    }
+}
+
+impl Grid {
+    /// The cached attack maps. Filled through a shared reference; see
+    /// `board::attacks`.
+    pub(crate) fn attacks(&self) -> &AttackMaps {
+        &self.attacks
+    }
+
+    /// Take the attack maps out, leaving empty ones.
+    pub(crate) fn take_attacks(&mut self) -> AttackMaps {
+        std::mem::replace(&mut self.attacks, AttackMaps::new(self.squares.len()))
+    }
+
+    /// Install `attacks`, which must describe the current squares.
+    pub(crate) fn set_attacks(&mut self, attacks: AttackMaps) {
+        self.attacks = attacks;
+    }
+
+    /// Start journaling writes, dropping any journal left running.
+    pub(crate) fn start_journal(&mut self) {
+        self.journal = Some(Vec::new());
+    }
+
+    /// Stop journaling and hand back the saved contents of every
+    /// square the writes actually changed, in first-write order.
+    pub(crate) fn take_journal(&mut self) -> Vec<(usize, Square)> {
+        let mut journal = self.journal.take().unwrap_or_default();
+        journal.retain(|(index, saved)| self.squares[*index] != *saved);
+        journal
+    }
+
+    /// Put back the squares a journal saved and the attack maps that
+    /// went with them: the grid as it stood when the journal started.
+    pub(crate) fn rewind(&mut self, journal: Vec<(usize, Square)>, attacks: AttackMaps) {
+        for (index, square) in journal {
+            self.squares[index] = square;
+        }
+        self.attacks = attacks;
+    }
+
+    /// The squares journaled so far, with their saved contents. Empty
+    /// when no journal is running.
+    pub(crate) fn journaled(&self) -> impl Iterator<Item = (usize, &Square)> {
+        self.journal
+            .iter()
+            .flatten()
+            .map(|(index, saved)| (*index, saved))
+    }
+
+    /// Save the squares at `indices` that aren't journaled yet, if a
+    /// journal is running.
+    fn note(&mut self, indices: Range<usize>) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        for index in indices {
+            if !journal.iter().any(|(seen, _)| *seen == index) {
+                journal.push((index, self.squares[index].clone()));
+            }
+        }
+    }
+}
+
+impl Index<usize> for Grid {
+    type Output = [Square];
+
+    fn index(&self, rank: usize) -> &[Square] {
+        assert!(
+            rank < self.height,
+            "rank {rank} out of range for a grid {} ranks tall",
+            self.height
+        );
+        &self.squares[rank * self.width..(rank + 1) * self.width]
+    }
+}
+
+impl IndexMut<usize> for Grid {
+    fn index_mut(&mut self, rank: usize) -> &mut [Square] {
+        assert!(
+            rank < self.height,
+            "rank {rank} out of range for a grid {} ranks tall",
+            self.height
+        );
+        let ranks = rank * self.width..(rank + 1) * self.width;
+        self.note(ranks.clone());
+        self.attacks.clear();
+        &mut self.squares[ranks]
+    }
+}
+
+/// The ranks of a `Grid`, as slices.
+pub struct Rows<'a> {
+    rest: &'a [Square],
+    width: usize,
+    left: usize,
+}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = &'a [Square];
+
+    fn next(&mut self) -> Option<&'a [Square]> {
+        if self.left == 0 {
+            return None;
+        }
+        self.left -= 1;
+        let (row, rest) = self.rest.split_at(self.width);
+        self.rest = rest;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+impl DoubleEndedIterator for Rows<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.left == 0 {
+            return None;
+        }
+        self.left -= 1;
+        let (rest, row) = self.rest.split_at(self.rest.len() - self.width);
+        self.rest = rest;
+        Some(row)
+    }
+}
+
+impl ExactSizeIterator for Rows<'_> {}
+
+/// The ranks of a `Grid`, as mutable slices.
+pub struct RowsMut<'a> {
+    rest: &'a mut [Square],
+    width: usize,
+    left: usize,
+}
+
+impl<'a> Iterator for RowsMut<'a> {
+    type Item = &'a mut [Square];
+
+    fn next(&mut self) -> Option<&'a mut [Square]> {
+        if self.left == 0 {
+            return None;
+        }
+        self.left -= 1;
+        let (row, rest) = std::mem::take(&mut self.rest).split_at_mut(self.width);
+        self.rest = rest;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+impl ExactSizeIterator for RowsMut<'_> {}
+
+impl<'a> IntoIterator for &'a Grid {
+    type Item = &'a [Square];
+    type IntoIter = Rows<'a>;
+
+    fn into_iter(self) -> Rows<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Grid {
+    type Item = &'a mut [Square];
+    type IntoIter = RowsMut<'a>;
+
+    fn into_iter(self) -> RowsMut<'a> {
+        self.iter_mut()
+    }
+}
+
+impl Grid {
+    /// A grid of `ranks`, top first, or `RaggedGrid` if they aren't
+    /// all the same length.
+    pub fn from_ranks(ranks: Vec<Vec<Square>>) -> Result<Self, RaggedGrid> {
+        let height = ranks.len();
+        let width = ranks.first().map_or(0, Vec::len);
+        let mut squares = Vec::with_capacity(width * height);
+        for (rank, row) in ranks.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(RaggedGrid {
+                    rank,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+            squares.extend(row);
+        }
+        Ok(Grid {
+            squares,
+            width,
+            height,
+            attacks: AttackMaps::new(width * height),
+            journal: None,
+        })
+    }
+}
+
+/// For building boards by hand. Panics on ragged ranks; use
+/// `from_ranks` for input that might be.
+impl From<Vec<Vec<Square>>> for Grid {
+    fn from(ranks: Vec<Vec<Square>>) -> Self {
+        Grid::from_ranks(ranks).unwrap_or_else(|e| panic!("ragged grid: {e}"))
+    }
+}
+
+impl FromIterator<Vec<Square>> for Grid {
+    fn from_iter<I: IntoIterator<Item = Vec<Square>>>(ranks: I) -> Self {
+        Grid::from(ranks.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl fmt::Debug for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ranks = Vec::<Vec<Square>>::deserialize(deserializer)?;
+        Grid::from_ranks(ranks).map_err(serde::de::Error::custom)
+    }
+}
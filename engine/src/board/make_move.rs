@@ -1,35 +1,23 @@
+use smallvec::SmallVec;
 use tracing::debug;
 
 use crate::{
     board::{
-        Board, CastleSide, Coord, GameMove, MoveError, MoveType, PromotionTarget,
-        square::{SquareCondition, SquareType},
+        Board, CastleSide, Coord, GameMove, LastMove, MoveError, MoveType,
+        chess960::castle_target_files,
+        square::{Square, SquareCondition, SquareType},
+        undo::MoveUndo,
     },
     pieces::{Color, piecetype::PieceType},
 };
 
-/// Castle geometry: `(king_target_file, rook_target_file)` for a
-/// given `CastleSide`. Hardcoded to 8-wide standard-chess
-/// conventions; gate on `width >= 8` at move-gen time (see
-/// `king::castle_moves`). The rook's SOURCE file depends on width
-/// (right_edge for kingside, 0 for queenside) and is computed
-/// at the call site of `relocate_pieces`.
-///
-/// Centralizing this here means a future Chess960 variant or
-/// non-8-wide castle scheme only touches one site instead of four.
-pub(crate) fn castle_target_files(side: CastleSide) -> (u8, u8) {
-    match side {
-        CastleSide::Kingside => (6, 5),
-        CastleSide::Queenside => (2, 3),
-    }
-}
-
 impl Board {
     /// Attempts to execute a move on the board.
-    /// Returns Ok(()) if successful, Err(MoveError) if illegal. The error
-    /// is structured (see `MoveError`) — match on the discriminant to
-    /// surface a useful message in your UI.
-    pub fn make_move(&mut self, game_move: GameMove) -> Result<(), MoveError> {
+    /// Returns the move's `MoveUndo` if successful (hand it to
+    /// `unmake_move` to take the move back), Err(MoveError) if illegal.
+    /// The error is structured (see `MoveError`) — match on the
+    /// discriminant to surface a useful message in your UI.
+    pub fn make_move(&mut self, game_move: GameMove) -> Result<MoveUndo, MoveError> {
         self.validate_move(&game_move)?;
         let from = game_move.from.clone();
         let attempted = game_move.move_type.clone();
@@ -51,14 +39,39 @@ impl Board {
     /// (piece post-effects), the capture stack runs for every piece
     /// that was captured by this move. Handlers return `BoardOp`s
     /// which apply before phase 2 fires.
-    pub fn make_move_unchecked(&mut self, game_move: GameMove) -> Result<(), String> {
-        let board_before = self.clone();
-        self.relocate_pieces(&game_move)?;
-        self.fire_capture_stack(&board_before, &game_move);
-        let ctx = PostMoveCtx {
-            before_state: &board_before,
-            game_move: &game_move,
-        };
+    ///
+    /// The board is never copied. What the phases need of the pre-move
+    /// position is gathered into a `PreMove` up front, and the grid
+    /// journals each square the first time any phase writes it (see
+    /// `board::grid`). That journal advances the position hash,
+    /// decides how much of the cached attack maps survives (see
+    /// `board::attacks`) and becomes the returned `MoveUndo`.
+    /// On `Err` the journaled squares and the flags are put back, so
+    /// the board is left as it was and a caller can skip the move.
+    pub fn make_move_unchecked(&mut self, game_move: GameMove) -> Result<MoveUndo, String> {
+        let before = PreMove::new(self, &game_move);
+        let before_hash = self.position_hash();
+        let flags = self.flags.clone();
+        let attacks = self.take_attack_maps();
+        self.grid.start_journal();
+        let applied = self.apply_move_phases(&before, &game_move);
+        let journal = self.grid.take_journal();
+        if let Err(e) = applied {
+            self.grid.rewind(journal, attacks);
+            self.flags = flags;
+            return Err(e);
+        }
+        let history = self.record_position(before_hash, &flags, &journal);
+        self.carry_attack_maps(&attacks, &journal);
+        Ok(MoveUndo::new(self, flags, journal, history, attacks))
+    }
+
+    /// The three apply phases, with the capture stack between the
+    /// first two.
+    fn apply_move_phases(&mut self, before: &PreMove, game_move: &GameMove) -> Result<(), String> {
+        self.relocate_pieces(game_move)?;
+        self.fire_capture_stack(before, game_move);
+        let ctx = PostMoveCtx { before, game_move };
         self.apply_piece_post_effects(&ctx)?;
         self.apply_environment_reactions(&ctx);
         Ok(())
@@ -71,6 +84,10 @@ impl Board {
     /// only state, before any train tick could roll over the mover's
     /// king.
     ///
+    /// `before` must equal `self` on entry. It is taken separately so
+    /// `Board::probe_move` can run this on a scratch copy and read the
+    /// pre-move state off the untouched original.
+    ///
     /// **Critical:** the capture stack runs here. Without it, validation
     /// would see a different post-move board than real `make_move`, and
     /// any `BoardOp::PlacePiece` produced by a capture handler (e.g.
@@ -80,13 +97,14 @@ impl Board {
     /// would have blocked an enemy slider's ray.
     pub(crate) fn apply_move_for_validation(
         &mut self,
+        before: &Board,
         game_move: GameMove,
     ) -> Result<(), String> {
-        let board_before = self.clone();
+        let before = PreMove::new(before, &game_move);
         self.relocate_pieces(&game_move)?;
-        self.fire_capture_stack(&board_before, &game_move);
+        self.fire_capture_stack(&before, &game_move);
         let ctx = PostMoveCtx {
-            before_state: &board_before,
+            before: &before,
             game_move: &game_move,
         };
         self.apply_piece_post_effects(&ctx)?;
@@ -99,9 +117,8 @@ impl Board {
     /// on this). Shared between `make_move_unchecked` (real apply) and
     /// `apply_move_for_validation` (hypothetical) so the two paths
     /// produce the same intermediate board state.
-    fn fire_capture_stack(&mut self, board_before: &Board, game_move: &GameMove) {
-        let captures = capture_targets(board_before, game_move);
-        for cap in captures {
+    fn fire_capture_stack(&mut self, before: &PreMove, game_move: &GameMove) {
+        for cap in before.captures.iter().cloned() {
             let event = crate::movement::stack::capture::ResolutionEvent::Capture {
                 captor_coord: cap.captor_coord,
                 captor_origin: cap.captor_origin,
@@ -110,8 +127,7 @@ impl Board {
                 victim: cap.victim,
                 move_type: game_move.move_type.clone(),
             };
-            let ops = crate::movement::stack::capture::default_capture_stack()
-                .resolve_capture(self, &event);
+            let ops = self.rules().capture.resolve_capture(self, &event);
             for op in ops {
                 op.apply(self);
             }
@@ -122,6 +138,15 @@ impl Board {
     /// no environment reactions — those are separate phases. This is the
     /// pure mechanical effect of the move on the grid.
     fn relocate_pieces(&mut self, game_move: &GameMove) -> Result<(), String> {
+        // Plan 11: the duck isn't a piece — it has its own relocation.
+        if game_move.move_type.is_duck_move() {
+            return self.relocate_duck(game_move);
+        }
+        // Crazyhouse: a drop comes from the pocket, not a square.
+        if let MoveType::Drop { .. } = &game_move.move_type {
+            return self.relocate_drop(game_move);
+        }
+
         // Plan 08 safety net: any move that lands a piece on a non-walkable
         // square (closed Gate, Turret, Vent) is rejected here, even if a
         // piece-level generator forgot to filter. Catches new pieces and
@@ -131,7 +156,7 @@ impl Board {
         if let Some(landing) = piece_landing_square(game_move) {
             let walkable = self
                 .get_square_at(landing)
-                .map(|s| s.square_type.is_walkable())
+                .map(|s| s.is_walkable())
                 .unwrap_or(false);
             if !walkable {
                 return Err(format!(
@@ -194,12 +219,7 @@ impl Board {
                     self.maybe_clear_castle_on_rook_capture(target, &captured);
                 }
 
-                let new_piece = match into {
-                    PromotionTarget::Queen => PieceType::new_queen(pawn_color),
-                    PromotionTarget::Rook => PieceType::new_rook(pawn_color),
-                    PromotionTarget::Bishop => PieceType::new_bishop(pawn_color),
-                    PromotionTarget::Knight => PieceType::new_knight(pawn_color),
-                };
+                let new_piece = into.piece(pawn_color, target);
 
                 {
                     let from_sq = self
@@ -218,23 +238,17 @@ impl Board {
             }
             MoveType::Castle { side } => {
                 let back_rank = from.rank;
-                // Kingside rook lives on the right-edge file (`width - 1`),
-                // not necessarily file 7 — keep this in sync with
-                // `king.rs::castle_moves`.
-                let right_edge = self.width().saturating_sub(1);
-                let (king_target_file, rook_target_file) = castle_target_files(*side);
-                let rook_source_file = match side {
-                    CastleSide::Kingside => right_edge,
-                    CastleSide::Queenside => 0,
-                };
+                // The rook is the one the castling right names (see
+                // `board::chess960`), wherever it stands.
+                let (king_target_file, rook_target_file) =
+                    castle_target_files(*side, self.width());
+                let rook_source = self
+                    .castle_rook_square(piece.get_color(), *side)
+                    .ok_or_else(|| format!("Castle: no {side} castling right"))?;
                 let king_target = Coord {
                     file: king_target_file,
                     rank: back_rank,
                 };
-                let rook_source = Coord {
-                    file: rook_source_file,
-                    rank: back_rank,
-                };
                 let rook_target = Coord {
                     file: rook_target_file,
                     rank: back_rank,
@@ -403,6 +417,10 @@ impl Board {
                     );
                 }
             }
+            // Handled by `relocate_duck` / `relocate_drop` at the top.
+            MoveType::PlaceDuck { .. } | MoveType::MoveDuck { .. } | MoveType::Drop { .. } => {
+                return Err(format!("{:?} reached piece relocation", game_move.move_type));
+            }
             MoveType::PieceInCarrier {
                 piece_index,
                 move_type,
@@ -510,11 +528,12 @@ impl Board {
         Ok(())
     }
 
-    /// If `captured_piece` is a rook on its color's starting rook square,
-    /// drop the corresponding castle right. Mirrors the standard-chess rule
-    /// that capturing a rook on h1/a1/h8/a8 cancels future castling on
-    /// that side. Plan 03's `post_move_effects` covers rook *moves*; this
-    /// covers the case where the rook never moves but is captured in place.
+    /// If `captured_piece` is a rook one of its colour's castling rights
+    /// names (see `board::chess960`), drop that right. Mirrors the
+    /// standard-chess rule that capturing a rook on h1/a1/h8/a8 cancels
+    /// future castling on that side. Plan 03's `post_move_effects` covers
+    /// rook *moves*; this covers the case where the rook never moves but
+    /// is captured in place.
     pub(crate) fn maybe_clear_castle_on_rook_capture(
         &mut self,
         captured_square: &Coord,
@@ -523,31 +542,10 @@ impl Board {
         let PieceType::Rook(r) = captured_piece else {
             return;
         };
-        // Right-edge file (`width - 1`) hosts the kingside rook on any
-        // board width — file 7 was the old 8-wide assumption.
-        let right_edge = self.width().saturating_sub(1);
-        let white_back = self.height().saturating_sub(1);
-        match r.color {
-            Color::White => {
-                if captured_square.rank == white_back {
-                    if captured_square.file == 0 {
-                        self.flags.white_can_castle_queenside = false;
-                    } else if captured_square.file == right_edge {
-                        self.flags.white_can_castle_kingside = false;
-                    }
-                }
+        for side in [CastleSide::Kingside, CastleSide::Queenside] {
+            if self.castle_rook_square(r.color, side).as_ref() == Some(captured_square) {
+                self.flags.clear_castle(r.color, side);
             }
-            Color::Black => {
-                if captured_square.rank == 0 {
-                    if captured_square.file == 0 {
-                        self.flags.black_can_castle_queenside = false;
-                    } else if captured_square.file == right_edge {
-                        self.flags.black_can_castle_kingside = false;
-                    }
-                }
-            }
-            // Neutral pieces never hold castle rights.
-            Color::Neutral => {}
         }
     }
 
@@ -559,6 +557,13 @@ impl Board {
     /// runs them so king-safety sees the same state real make_move
     /// would, *minus* the train tick (which lives in phase 3).
     fn apply_piece_post_effects(&mut self, ctx: &PostMoveCtx<'_>) -> Result<(), String> {
+        // Plan 11: the duck half-move has no piece reactions, and the
+        // en passant target set by the piece half-move before it must
+        // survive into the opponent's turn.
+        if ctx.game_move.move_type.is_duck_move() {
+            return Ok(());
+        }
+
         // Reset en-passant target before piece-level hooks. Pawn's
         // post_move_effects re-sets it if this move was a double push.
         self.flags.en_passant_target = None;
@@ -577,7 +582,7 @@ impl Board {
         //
         // 2. MoveIntoCarrier — the mover is now stored inside the
         //    target carrier's passenger list, not at a top-level
-        //    square. Fetch it from `before_state` so hooks like
+        //    square. Fetch it from `ctx.before` so hooks like
         //    `King::post_move_effects` (clears castle rights) and
         //    `Skibidi::post_move_effects` (resets phase) still fire.
         //    Without this, a king-into-bus silently preserves castle
@@ -591,25 +596,42 @@ impl Board {
         //    hops cart A → cart B (round-4 addition). The hook still
         //    fires so a king-passenger clears its own castle rights.
         //
-        // PhaseShift and ThrowSwitch don't relocate a piece — skip.
+        // PhaseShift and ThrowSwitch don't relocate a piece — skip. Nor
+        // does a drop, which only sets a fresh piece down.
         let mover_dispatch: Option<PieceType> = match &ctx.game_move.move_type {
             MoveType::PhaseShift
             | MoveType::ThrowSwitch { .. }
-            | MoveType::PlaceTornado { .. } => None,
+            | MoveType::PlaceTornado { .. }
+            | MoveType::PlaceDuck { .. }
+            | MoveType::MoveDuck { .. }
+            | MoveType::Drop { .. } => None,
             MoveType::MoveTo(target)
             | MoveType::Promotion { target, .. }
             | MoveType::EnPassant { target, .. } => {
                 let square = self
                     .get_square_at(target)
                     .ok_or_else(|| format!("No square at {:?}", target))?;
-                let piece = square
-                    .piece
-                    .clone()
-                    .ok_or_else(|| format!("No piece at {:?}", target))?;
-                Some(piece)
+                match square.piece.clone() {
+                    Some(piece) => Some(piece),
+                    // A capture handler took the captor off the board
+                    // (Atomic's blast): it has no reactions left to
+                    // run, but it did leave its square — a corner rook
+                    // still costs its castling right.
+                    None if !ctx.before.captures.is_empty() => {
+                        if let Some(captor) = ctx
+                            .before
+                            .square_at(&ctx.game_move.from)
+                            .and_then(|s| s.piece.as_ref())
+                        {
+                            self.maybe_clear_castle_on_rook_capture(&ctx.game_move.from, captor);
+                        }
+                        None
+                    }
+                    None => return Err(format!("No piece at {:?}", target)),
+                }
             }
             MoveType::Castle { side } => {
-                let (king_file, _) = castle_target_files(*side);
+                let (king_file, _) = castle_target_files(*side, self.width());
                 let target = Coord {
                     file: king_file,
                     rank: ctx.game_move.from.rank,
@@ -625,11 +647,11 @@ impl Board {
             }
             MoveType::MoveIntoCarrier(_) => {
                 // Mover came from `from` and is now inside the target
-                // carrier; before_state still has it as a top-level
+                // carrier; `ctx.before` still has it as a top-level
                 // piece at `from`.
                 let square = ctx
-                    .before_state
-                    .get_square_at(&ctx.game_move.from)
+                    .before
+                    .square_at(&ctx.game_move.from)
                     .ok_or_else(|| format!("No square at {:?}", ctx.game_move.from))?;
                 square.piece.clone()
             }
@@ -641,12 +663,19 @@ impl Board {
                     move_type.as_ref(),
                     MoveType::MoveTo(_) | MoveType::MoveIntoCarrier(_)
                 );
-                if !supported_inner {
+                let blown_up = matches!(
+                    move_type.as_ref(),
+                    MoveType::MoveTo(target)
+                        if self.get_square_at(target).is_some_and(|s| s.piece.is_none())
+                );
+                if !supported_inner || blown_up {
+                    // `blown_up`: the passenger captured on exit and a
+                    // capture handler took it off the board (Atomic).
                     None
                 } else {
                     let carrier_sq = ctx
-                        .before_state
-                        .get_square_at(&ctx.game_move.from)
+                        .before
+                        .square_at(&ctx.game_move.from)
                         .ok_or_else(|| format!("No square at {:?}", ctx.game_move.from))?;
                     carrier_sq
                         .piece
@@ -666,7 +695,7 @@ impl Board {
             // re-fetching its on-board piece and downcasting; Skibidi
             // builds a fresh `Skibidi` with the new phase and writes
             // it back via `set_piece_at`.
-            piece.post_move_effects(ctx.before_state, self, ctx.game_move);
+            piece.post_move_effects(ctx.before, self, ctx.game_move);
         }
 
         // Plan 08 step 4: PressurePlate scan. For every square the move
@@ -674,7 +703,7 @@ impl Board {
         // landing-set varies by move shape — see `collect_landings` for the
         // mapping. A Castle settles two pieces (king + rook); a passenger
         // exiting a carrier settles one passenger piece on the target tile.
-        for landing in collect_landings(ctx.game_move) {
+        for landing in collect_landings(ctx.game_move, self.width()) {
             self.maybe_fire_pressure_plate(&landing);
         }
 
@@ -693,17 +722,18 @@ impl Board {
     /// logic now flows through `EnvReactionRegistry`. Each
     /// auto-mechanic is a handler registered against an `EnvPhase`.
     /// Future pieces (Magnet, Bell-Ringer, Boy, Marcher, NPC) plug
-    /// in via the same trait without re-touching this method.
+    /// in via the same trait without re-touching this method. The
+    /// registry is the one for this board's ruleset (`Board::rules`).
     fn apply_environment_reactions(&mut self, ctx: &PostMoveCtx<'_>) {
-        use crate::movement::env_reactions::default_registry;
-        self.apply_environment_reactions_with(ctx, default_registry());
+        let reg = &self.rules().env;
+        self.apply_environment_reactions_with(ctx, reg);
     }
 
     /// Same body as `apply_environment_reactions` but with an injected
-    /// registry. The default-flow caller passes `default_registry()`;
-    /// tests can pass a custom registry containing probe handlers to
-    /// pin ordering invariants that aren't testable through the
-    /// `OnceLock`-backed default. Round-4 audit added this seam to
+    /// registry. The default-flow caller passes the ruleset's cached
+    /// registry; tests can pass a custom registry containing probe
+    /// handlers to pin ordering invariants that aren't testable
+    /// through the cached one. Round-4 audit added this seam to
     /// sharpen `test_last_move_written_before_post_mover_phase` —
     /// otherwise the test couldn't distinguish "write happens before
     /// PostMover" from "write happens after PostMover" since both
@@ -725,20 +755,55 @@ impl Board {
         // write deferred to after the side flip, PostMover handlers
         // would see a stale `last_move` from the PREVIOUS turn.
         //
-        // `compute_last_move` is pure (read-only on `ctx.before_state`
-        // + the move payload), so moving the write earlier doesn't
+        // `compute_last_move` ran on the pre-move board, before
+        // anything was written, so moving the write earlier doesn't
         // depend on later mutation.
-        self.flags.last_move = compute_last_move(ctx.before_state, ctx.game_move);
-
-        // PostMover fires for handlers that want to react to the
-        // just-applied move on the mover's side (Boy step toward the
-        // enemy that just moved, Marcher march, NPC advance). At this
-        // point `side_to_move` is still the mover; the flip happens
-        // after the tick.
-        reg.run_phase(self, EnvPhase::PostMover, false, &mut env_ctx);
+        //
+        // Plan 11: the duck half-move isn't a piece move. It leaves the
+        // piece half-move's `last_move` in place and doesn't wake the
+        // PostMover handlers a second time.
+        let duck_move = ctx.game_move.move_type.is_duck_move();
+        if !duck_move {
+            self.flags.last_move = ctx.before.last_move.clone();
+
+            // PostMover fires for handlers that want to react to the
+            // just-applied move on the mover's side (Boy step toward the
+            // enemy that just moved, Marcher march, NPC advance). At this
+            // point `side_to_move` is still the mover; the flip happens
+            // after the tick.
+            reg.run_phase(self, EnvPhase::PostMover, false, &mut env_ctx);
+        }
+
+        // Plan 11: in Duck Chess the piece half-move keeps the turn with
+        // the mover, who still owes the duck. No tick, no flip — those
+        // belong to the duck half-move that completes the turn.
+        if self.flags.has_variant(crate::board::VariantId::DuckChess) {
+            if !duck_move {
+                self.flags.duck_phase = crate::board::DuckPhase::DuckPlacement;
+                self.advance_move_clocks(ctx);
+                return;
+            }
+            self.flags.duck_phase = crate::board::DuckPhase::PieceMove;
+        }
+
+        // Settle bonus moves before the tick: whether this move passes
+        // the turn decides whether the tick gate counts it.
+        self.settle_extra_moves(ctx);
+
         reg.run_phase(self, EnvPhase::TickGate, false, &mut env_ctx);
         reg.run_phase(self, EnvPhase::PostTick, false, &mut env_ctx);
 
+        // Clocks run after the tick so a train run-over this ply counts
+        // as a capture for the halfmove clock. `side_to_move` is still
+        // the mover here.
+        self.advance_move_clocks(ctx);
+
+        // A banked bonus move keeps the turn with the mover. Nothing
+        // else to do: PreMover is "start of the opponent's turn".
+        if self.flags.extra_moves > 0 {
+            return;
+        }
+
         // Plan 01: flip turn after env reactions so the train tick
         // and other auto-mechanics still see the mover as the
         // side-to-move if they need to.
@@ -768,9 +833,100 @@ impl Board {
         // PreMover fires AFTER the side flip — "start of opponent's
         // turn" handlers (Magnet pull, Bell-Ringer toll) want to see
         // the now-current side. They also see the just-written
-        // `last_move` from this turn. None registered in v1.
+        // `last_move` from this turn. The check counter of `NChecks`
+        // games is the only one so far.
         reg.run_phase(self, EnvPhase::PreMover, false, &mut env_ctx);
     }
+
+    /// Update the halfmove clock and fullmove number for the move in
+    /// `ctx`. The clock resets when the mover is a pawn or when the move
+    /// cost any material: a direct capture (`capture_targets`, which also
+    /// covers a Goblin kidnap that keeps the victim as payload) or fewer
+    /// pieces on the squares the apply has written so far than they
+    /// held before it — capture-stack `RemovePiece`s, train run-overs
+    /// and Neutral-cart boarding purges all land there without a
+    /// dedicated hook.
+    ///
+    /// A Duck Chess turn is two calls: the piece half-move counts for
+    /// the halfmove clock, the duck half-move only resets it if a train
+    /// ran something over. The fullmove number advances once, when the
+    /// duck half-move hands the turn over.
+    fn advance_move_clocks(&mut self, ctx: &PostMoveCtx<'_>) {
+        let before = ctx.before;
+        let duck_move = ctx.game_move.move_type.is_duck_move();
+        let source = before.square_at(&ctx.game_move.from).and_then(|s| s.piece.as_ref());
+        let pawn_moved = moving_piece(source, ctx.game_move)
+            .is_some_and(|p| matches!(p, PieceType::Pawn(_)));
+        let captured = !before.captures.is_empty() || self.lost_material();
+        if pawn_moved || captured {
+            self.flags.halfmove_clock = 0;
+        } else if !duck_move {
+            self.flags.halfmove_clock = self.flags.halfmove_clock.saturating_add(1);
+        }
+        let passes_turn = self.flags.extra_moves == 0 && !self.in_duck_placement();
+        if self.flags.side_to_move == Color::Black && passes_turn {
+            self.flags.fullmove_number = self.flags.fullmove_number.saturating_add(1);
+        }
+    }
+
+    /// Bonus-move bookkeeping (see `BoardFlags::extra_moves`). A move
+    /// played with bonus moves banked spends one; any grant from this
+    /// move's captures is already added in. What's left is forfeited
+    /// when keeping the turn would break the game: a move that checks
+    /// the opponent passes the turn (a second move could take the
+    /// king), and so does one after which the mover has nothing to
+    /// play.
+    fn settle_extra_moves(&mut self, ctx: &PostMoveCtx<'_>) {
+        if ctx.before.extra_moves > 0 {
+            self.flags.extra_moves = self.flags.extra_moves.saturating_sub(1);
+        }
+        if self.flags.extra_moves == 0 {
+            return;
+        }
+        let mover = self.flags.side_to_move;
+        let stuck = !self.has_legal_move(mover);
+        if stuck || self.is_in_check(mover.opposite()) {
+            debug!(extra_moves = self.flags.extra_moves, "bonus moves forfeited");
+            self.flags.extra_moves = 0;
+        }
+    }
+
+    /// Whether the squares written so far this move hold fewer pieces,
+    /// counting carrier passengers individually, than they did before
+    /// it. Used by the halfmove clock to spot material loss that didn't
+    /// come from the mover's own capture. Every square that lost a
+    /// piece was written, so nothing else needs counting.
+    fn lost_material(&self) -> bool {
+        let (before, after) = self
+            .grid
+            .journaled()
+            .fold((0, 0), |(before, after), (index, saved)| {
+                let now = &self.grid.squares()[index];
+                (before + square_census(saved), after + square_census(now))
+            });
+        after < before
+    }
+}
+
+/// Pieces on `square`, counting carrier passengers individually.
+fn square_census(square: &Square) -> usize {
+    square
+        .piece
+        .as_ref()
+        .map_or(0, |p| 1 + p.passengers().map_or(0, |ps| ps.len()))
+}
+
+/// The piece actually making `game_move`, given the occupant of its
+/// `from` square: the passenger for a `PieceInCarrier` move, the
+/// occupant itself otherwise.
+fn moving_piece<'a>(source: Option<&'a PieceType>, game_move: &GameMove) -> Option<&'a PieceType> {
+    let piece = source?;
+    match &game_move.move_type {
+        MoveType::PieceInCarrier { piece_index, .. } => {
+            piece.passengers()?.get(*piece_index as usize)
+        }
+        _ => Some(piece),
+    }
 }
 
 /// Build a `LastMove` snapshot from the move that just applied. Pulls
@@ -791,6 +947,19 @@ fn compute_last_move(
 ) -> Option<crate::board::LastMove> {
     use crate::board::LastMoveKind;
 
+    // A drop has no source piece on the board; it's recorded as
+    // landing on its target from nowhere further away than itself.
+    if let MoveType::Drop { piece, target } = &game_move.move_type {
+        return Some(crate::board::LastMove {
+            mover_color: piece.get_color(),
+            from: target.clone(),
+            to: Some(target.clone()),
+            captured_symbol: None,
+            primary_symbol: piece.symbol(),
+            kind: LastMoveKind::Drop,
+        });
+    }
+
     let from = game_move.from.clone();
     let source_piece = before.get_square_at(&from)?.piece.clone()?;
     let (mover_color, _) = before.effective_mover_color(&source_piece, game_move);
@@ -802,7 +971,7 @@ fn compute_last_move(
     // is no single piece-landing square; surface `None`.
     let to = match &game_move.move_type {
         MoveType::Castle { side } => {
-            let (king_file, _) = castle_target_files(*side);
+            let (king_file, _) = castle_target_files(*side, before.width());
             Some(Coord {
                 file: king_file,
                 rank: game_move.from.rank,
@@ -861,15 +1030,7 @@ fn compute_last_move(
     // `mover_color` give a deterministic answer that doesn't depend
     // on subsequent env reactions.
     let primary_symbol = match &game_move.move_type {
-        MoveType::Promotion { into, .. } => {
-            let promoted = match into {
-                PromotionTarget::Queen => PieceType::new_queen(mover_color),
-                PromotionTarget::Rook => PieceType::new_rook(mover_color),
-                PromotionTarget::Bishop => PieceType::new_bishop(mover_color),
-                PromotionTarget::Knight => PieceType::new_knight(mover_color),
-            };
-            promoted.symbol()
-        }
+        MoveType::Promotion { into, target } => into.piece(mover_color, target).symbol(),
         _ => source_piece.symbol(),
     };
 
@@ -883,6 +1044,8 @@ fn compute_last_move(
         MoveType::ThrowSwitch { .. } => LastMoveKind::ThrowSwitch,
         MoveType::PieceInCarrier { .. } => LastMoveKind::PieceInCarrier,
         MoveType::PlaceTornado { .. } => LastMoveKind::PlaceTornado,
+        MoveType::Drop { .. } => LastMoveKind::Drop,
+        MoveType::PlaceDuck { .. } | MoveType::MoveDuck { .. } => return None,
     };
 
     Some(crate::board::LastMove {
@@ -902,10 +1065,81 @@ fn compute_last_move(
 /// flag — is one field at the call site instead of a signature change
 /// fan-out.
 pub(crate) struct PostMoveCtx<'a> {
-    pub before_state: &'a Board,
+    pub before: &'a PreMove,
     pub game_move: &'a GameMove,
 }
 
+/// What the apply phases know of the board as it stood before a move,
+/// read off it before anything is written so the board itself never
+/// has to be copied. Piece hooks get it as `post_move_effects`'s
+/// `before`.
+#[derive(Debug, Clone)]
+pub struct PreMove {
+    /// Pre-move contents of the squares the move names: its source, its
+    /// landing square and an en passant victim's square.
+    squares: SmallVec<[(Coord, Square); 3]>,
+    /// `Board::castle_rook_square` for White kingside, White
+    /// queenside, Black kingside, Black queenside.
+    castle_rooks: [Option<Coord>; 4],
+    pub(crate) extra_moves: u8,
+    pub(crate) captures: Vec<CapturePair>,
+    pub(crate) last_move: Option<LastMove>,
+}
+
+impl PreMove {
+    pub(crate) fn new(board: &Board, game_move: &GameMove) -> Self {
+        let captured = match &game_move.move_type {
+            MoveType::EnPassant { captured, .. } => Some(captured),
+            _ => None,
+        };
+        let mut squares: SmallVec<[(Coord, Square); 3]> = SmallVec::new();
+        for at in [Some(&game_move.from), piece_landing_square(game_move), captured]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(square) = board.get_square_at(at)
+                && squares.iter().all(|(seen, _)| seen != at)
+            {
+                squares.push((at.clone(), square.clone()));
+            }
+        }
+        let rook = |color, side| board.castle_rook_square(color, side);
+        PreMove {
+            squares,
+            castle_rooks: [
+                rook(Color::White, CastleSide::Kingside),
+                rook(Color::White, CastleSide::Queenside),
+                rook(Color::Black, CastleSide::Kingside),
+                rook(Color::Black, CastleSide::Queenside),
+            ],
+            extra_moves: board.flags.extra_moves,
+            captures: capture_targets(board, game_move),
+            last_move: compute_last_move(board, game_move),
+        }
+    }
+
+    /// The square at `at` before the move, if the move names it (its
+    /// source, landing square or en passant victim); `None` for any
+    /// other square.
+    pub fn square_at(&self, at: &Coord) -> Option<&Square> {
+        self.squares
+            .iter()
+            .find_map(|(seen, square)| (seen == at).then_some(square))
+    }
+
+    /// `Board::castle_rook_square` on the pre-move board.
+    pub fn castle_rook_square(&self, color: Color, side: CastleSide) -> Option<Coord> {
+        let slot = match (color, side) {
+            (Color::White, CastleSide::Kingside) => 0,
+            (Color::White, CastleSide::Queenside) => 1,
+            (Color::Black, CastleSide::Kingside) => 2,
+            (Color::Black, CastleSide::Queenside) => 3,
+            (Color::Neutral, _) => return None,
+        };
+        self.castle_rooks[slot].clone()
+    }
+}
+
 /// Plan 10 step 10 helper: identify the captures this move produced.
 /// Read from `before` since the post-relocation board has already
 /// cleared the victim's square.
@@ -926,7 +1160,7 @@ pub(crate) struct PostMoveCtx<'a> {
 ///
 /// Carrier-boarding enemy-passenger captures (the inline `passengers.
 /// retain` in the `MoveIntoCarrier` arm) are still not surfaced.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct CapturePair {
     pub captor_coord: Coord,
     pub captor_origin: Option<Coord>,
@@ -1013,21 +1247,25 @@ pub(crate) fn capture_targets(before: &Board, game_move: &GameMove) -> Vec<Captu
 /// result of this move. Used by the PressurePlate scan in
 /// `apply_piece_post_effects` to fire plates for any of the landings.
 ///
-/// - `MoveTo` / `Promotion` / `EnPassant`: one landing (the target).
+/// - `MoveTo` / `Promotion` / `EnPassant` / `Drop`: one landing (the
+///   target).
 /// - `Castle`: two landings — king's destination and rook's destination.
 /// - `PieceInCarrier { MoveTo }`: passenger exits onto a tile; that's a
 ///   landing for the passenger. Other inner shapes don't surface a tile-
 ///   level landing.
 /// - `MoveIntoCarrier`, `PhaseShift`, `ThrowSwitch`: no new piece on a
 ///   tile (the carrier was already there / no piece relocated).
-fn collect_landings(game_move: &GameMove) -> Vec<Coord> {
+/// - `PlaceDuck` / `MoveDuck`: the duck isn't a piece and doesn't
+///   press plates.
+fn collect_landings(game_move: &GameMove, width: u8) -> Vec<Coord> {
     match &game_move.move_type {
         MoveType::MoveTo(c) => vec![c.clone()],
         MoveType::Promotion { target, .. } => vec![target.clone()],
         MoveType::EnPassant { target, .. } => vec![target.clone()],
+        MoveType::Drop { target, .. } => vec![target.clone()],
         MoveType::Castle { side } => {
             let r = game_move.from.rank;
-            let (king_file, rook_file) = castle_target_files(*side);
+            let (king_file, rook_file) = castle_target_files(*side, width);
             vec![
                 Coord {
                     file: king_file,
@@ -1046,7 +1284,9 @@ fn collect_landings(game_move: &GameMove) -> Vec<Coord> {
         MoveType::MoveIntoCarrier(_)
         | MoveType::PhaseShift
         | MoveType::ThrowSwitch { .. }
-        | MoveType::PlaceTornado { .. } => vec![],
+        | MoveType::PlaceTornado { .. }
+        | MoveType::PlaceDuck { .. }
+        | MoveType::MoveDuck { .. } => vec![],
     }
 }
 
@@ -1060,6 +1300,7 @@ fn piece_landing_square(game_move: &GameMove) -> Option<&Coord> {
         MoveType::MoveTo(c) => Some(c),
         MoveType::Promotion { target, .. } => Some(target),
         MoveType::EnPassant { target, .. } => Some(target),
+        MoveType::Drop { target, .. } => Some(target),
         // Boarding a carrier: the boarder's square is the carrier's tile,
         // so the carrier's tile must be walkable. (A Bus parked on a
         // closed Gate is unreachable.)
@@ -1082,6 +1323,8 @@ fn piece_landing_square(game_move: &GameMove) -> Option<&Coord> {
         },
         MoveType::PhaseShift
         | MoveType::ThrowSwitch { .. }
-        | MoveType::PlaceTornado { .. } => None,
+        | MoveType::PlaceTornado { .. }
+        | MoveType::PlaceDuck { .. }
+        | MoveType::MoveDuck { .. } => None,
     }
 }
@@ -0,0 +1,99 @@
+//! Dead-position detection by material.
+//!
+//! A position is dead when no sequence of legal moves can end in a win
+//! — checkmate, or a `BrainrotWin`. Proving that in general means
+//! searching; this module recognises the material-only cases and stays
+//! conservative — when in doubt, the position is alive.
+//!
+//! Pieces fall into three groups:
+//!
+//! - **Checkers** can win: the standard pieces, the Monkey (captures by
+//!   jumping), the Goblin, and anything not listed below. Train carts
+//!   count too: a Locomotive runs a king over. So does the Skibidi,
+//!   which never attacks a king but can freeze one for a Brainrot win.
+//! - **Inert** pieces can never attack a king: the Bus (its passengers
+//!   are counted separately) and the Stormcaller (never captures).
+//! - Kings, which never give check.
+//!
+//! The position is dead when there are no checkers at all, or when the
+//! only non-king material is a single minor piece, or only bishops all
+//! on one square colour. The minor-piece cases also need a plain board
+//! — every square `Standard` with no conditions — since walls, closed
+//! gates, brainrot or tornadoes can do the job of the missing blockers.
+//! Inert pieces are harmless alone but can block a king in, so they
+//! rule the minor-piece cases out as well.
+//!
+//! A Crazyhouse pocket that holds anything keeps the position alive:
+//! a dropped piece can land wherever it's needed.
+
+use crate::{
+    board::{Board, square::SquareType},
+    pieces::piecetype::PieceType,
+};
+
+#[derive(Default)]
+struct Material {
+    /// Non-king pieces that can win, excluding bishops and knights.
+    majors: usize,
+    knights: usize,
+    /// Square colour — `(file + rank) % 2` — of each bishop.
+    bishop_colors: Vec<u8>,
+    inert: usize,
+    /// A king is riding in a carrier, where the capture rules differ
+    /// (a Skibidi can take a passenger out of a neutral cart).
+    carried_king: bool,
+}
+
+impl Material {
+    fn add(&mut self, piece: &PieceType, square_color: u8, carried: bool) {
+        match piece {
+            PieceType::King(_) => self.carried_king |= carried,
+            PieceType::Knight(_) => self.knights += 1,
+            PieceType::Bishop(_) => self.bishop_colors.push(square_color),
+            PieceType::Bus(_) | PieceType::Stormcaller(_) => self.inert += 1,
+            _ => self.majors += 1,
+        }
+        for passenger in piece.passengers().unwrap_or_default() {
+            self.add(passenger, square_color, true);
+        }
+    }
+}
+
+impl Board {
+    /// Neither side can ever win with the material on the board.
+    /// Backs `GameStatus::Draw { reason: InsufficientMaterial }`.
+    pub fn is_insufficient_material(&self) -> bool {
+        if !self.flags.white_pocket.is_empty() || !self.flags.black_pocket.is_empty() {
+            return false;
+        }
+        let mut material = Material::default();
+        for (coord, piece) in self.iter_pieces() {
+            material.add(piece, (coord.file + coord.rank) % 2, false);
+        }
+        if material.carried_king || material.majors > 0 {
+            return false;
+        }
+        let minors = material.knights + material.bishop_colors.len();
+        if minors == 0 {
+            return true;
+        }
+        if material.inert > 0 || !self.is_plain() {
+            return false;
+        }
+        let one_minor = minors == 1;
+        let same_color_bishops = material.knights == 0
+            && material
+                .bishop_colors
+                .iter()
+                .all(|c| *c == material.bishop_colors[0]);
+        one_minor || same_color_bishops
+    }
+
+    /// Every square is `Standard` and carries no condition.
+    fn is_plain(&self) -> bool {
+        self.grid
+            .iter()
+            .flatten()
+            .all(|sq| sq.square_type == SquareType::Standard && sq.conditions.is_empty())
+    }
+}
@@ -1,20 +1,42 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    board::square::Square,
-    pieces::{Color, piecetype::PieceType},
+    board::{
+        chess960::CastleRookFiles,
+        grid::Grid,
+        square::Square,
+        win::{WinConditionId, WinReason},
+        zobrist::PositionHistory,
+    },
+    pieces::{
+        Color,
+        fairy::{bus::Bus, goblin::Goblin, skibidi::Skibidi, stormcaller::Stormcaller},
+        piecetype::PieceType,
+    },
 };
 
+pub mod attacks;
 pub mod brainrot;
+pub mod chess960;
+pub mod crazyhouse;
+pub mod duck;
 pub mod fen;
+pub mod fog;
+pub mod grid;
 pub mod make_move;
+pub mod material;
+pub mod san;
 pub mod signal;
 pub mod square;
 mod tests;
 pub mod tornado;
 pub mod trains;
+pub mod uci;
+pub mod undo;
+pub mod win;
+pub mod zobrist;
 
 pub type File = u8; // 0–7 for default boards
 pub type Rank = u8; // 0–7 for default boards
@@ -26,7 +48,7 @@ pub type Rank = u8; // 0–7 for default boards
 pub type SignalId = u32;
 
 /// We use this so there's no confusion with which index is which.
-#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Deserialize, Serialize)]
 pub struct Coord {
     pub file: File,
     pub rank: Rank,
@@ -41,22 +63,86 @@ impl std::fmt::Display for Coord {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+/// What a pawn may promote to. Which of these a game offers is
+/// `BoardFlags::promotion_targets`; standard chess offers the first
+/// four.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize, Deserialize)]
 pub enum PromotionTarget {
     Queen,
     Rook,
     Bishop,
     Knight,
+    Goblin,
+    Skibidi,
+    Monkey,
+    Bus,
+    Stormcaller,
 }
 
-impl std::fmt::Display for PromotionTarget {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
+impl PromotionTarget {
+    /// The standard-chess choices: what a game offers when it names
+    /// none.
+    pub const STANDARD: [PromotionTarget; 4] = [
+        PromotionTarget::Queen,
+        PromotionTarget::Rook,
+        PromotionTarget::Bishop,
+        PromotionTarget::Knight,
+    ];
+
+    /// Identifier used in the FEN `promo=` field, and the `Display`
+    /// form: `queen`, `rook`, `bishop`, `knight`, `goblin`, `skibidi`,
+    /// `monkey`, `bus`, `stormcaller`.
+    pub fn fen_tag(&self) -> &'static str {
+        match self {
             PromotionTarget::Queen => "queen",
             PromotionTarget::Rook => "rook",
             PromotionTarget::Bishop => "bishop",
             PromotionTarget::Knight => "knight",
-        })
+            PromotionTarget::Goblin => "goblin",
+            PromotionTarget::Skibidi => "skibidi",
+            PromotionTarget::Monkey => "monkey",
+            PromotionTarget::Bus => "bus",
+            PromotionTarget::Stormcaller => "stormcaller",
+        }
+    }
+
+    /// Inverse of `fen_tag`.
+    pub fn parse_tag(s: &str) -> Option<Self> {
+        match s {
+            "queen" => Some(PromotionTarget::Queen),
+            "rook" => Some(PromotionTarget::Rook),
+            "bishop" => Some(PromotionTarget::Bishop),
+            "knight" => Some(PromotionTarget::Knight),
+            "goblin" => Some(PromotionTarget::Goblin),
+            "skibidi" => Some(PromotionTarget::Skibidi),
+            "monkey" => Some(PromotionTarget::Monkey),
+            "bus" => Some(PromotionTarget::Bus),
+            "stormcaller" => Some(PromotionTarget::Stormcaller),
+            _ => None,
+        }
+    }
+
+    /// The piece a `color` pawn promoting on `square` becomes, fresh:
+    /// a Goblin `Free` with `square` as its home, a Skibidi at phase 1,
+    /// an empty Bus.
+    pub fn piece(&self, color: Color, square: &Coord) -> PieceType {
+        match self {
+            PromotionTarget::Queen => PieceType::new_queen(color),
+            PromotionTarget::Rook => PieceType::new_rook(color),
+            PromotionTarget::Bishop => PieceType::new_bishop(color),
+            PromotionTarget::Knight => PieceType::new_knight(color),
+            PromotionTarget::Goblin => PieceType::Goblin(Goblin::new(color, square.clone())),
+            PromotionTarget::Skibidi => PieceType::Skibidi(Skibidi::new(color)),
+            PromotionTarget::Monkey => PieceType::new_monkey(color),
+            PromotionTarget::Bus => PieceType::Bus(Bus::new(color)),
+            PromotionTarget::Stormcaller => PieceType::Stormcaller(Stormcaller::new(color)),
+        }
+    }
+}
+
+impl std::fmt::Display for PromotionTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.fen_tag())
     }
 }
 
@@ -112,6 +198,26 @@ pub enum MoveType {
     PlaceTornado {
         target: Coord,
     },
+    /// Plan 11 (Duck Chess): the first duck half-move of the game
+    /// drops the duck onto `to`. There's no duck on the board yet, so
+    /// `from` on the wrapping `GameMove` is ignored; the generator
+    /// repeats `to` there.
+    PlaceDuck {
+        to: Coord,
+    },
+    /// Plan 11 (Duck Chess): move the duck. `from` on the wrapping
+    /// `GameMove` is the duck's current square.
+    MoveDuck {
+        to: Coord,
+    },
+    /// Crazyhouse: set `piece` down from the mover's pocket onto the
+    /// empty square `target`. Nothing is on the board to move from, so
+    /// like `PlaceDuck` the wrapping `GameMove`'s `from` repeats
+    /// `target`. See `board::crazyhouse`.
+    Drop {
+        piece: PieceType,
+        target: Coord,
+    },
 }
 
 impl std::fmt::Display for MoveType {
@@ -133,10 +239,21 @@ impl std::fmt::Display for MoveType {
             }
             MoveType::ThrowSwitch { switch } => write!(f, "throw switch at {switch}"),
             MoveType::PlaceTornado { target } => write!(f, "place tornado at {target}"),
+            MoveType::PlaceDuck { to } => write!(f, "place the duck at {to}"),
+            MoveType::MoveDuck { to } => write!(f, "move the duck to {to}"),
+            MoveType::Drop { piece, target } => write!(f, "drop '{}' on {target}", piece.symbol()),
         }
     }
 }
 
+impl MoveType {
+    /// Is this the duck half-move of a Duck Chess turn rather than a
+    /// piece move?
+    pub fn is_duck_move(&self) -> bool {
+        matches!(self, MoveType::PlaceDuck { .. } | MoveType::MoveDuck { .. })
+    }
+}
+
 /// Represents a move from one coordinate to another.
 /// Will likely be expanded later with more info.
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -157,9 +274,12 @@ pub enum TrainTickRate {
 
 /// Plan 10 step 2 — snapshot of the previous move. Populated at the end
 /// of every `make_move` (in `apply_environment_reactions`, before the
-/// side flip). Read by pieces that react to history: Mirror replays the
-/// shape; Echo-spatial applies the from→to delta to a friendly piece;
-/// Boy Who Followed Geese chases the most recent mover.
+/// side flip). A bonus move (`BoardFlags::extra_moves`) is recorded like
+/// any other, so after a turn with bonus moves it describes the last of
+/// them, and `mover_color` can match the side to move mid-turn. Read by
+/// pieces that react to history: Mirror replays the shape; Echo-spatial
+/// applies the from→to delta to a friendly piece; Boy Who Followed
+/// Geese chases the most recent mover.
 ///
 /// Kept deliberately lean — five round-trippable fields. The lightweight
 /// `kind` discriminator lets a future Mirror-style piece know whether
@@ -178,13 +298,13 @@ pub struct LastMove {
     /// from→to vector); the rook's relocation is implicit.
     pub to: Option<Coord>,
     /// FEN symbol of the piece that was captured by this move, if any.
-    /// Stored as a `String` (not `PieceType`) so the FEN encoding stays
+    /// Stored as the symbol (not `PieceType`) so the FEN encoding stays
     /// flat — round-trip preserves the symbol, not the full piece state.
-    pub captured_symbol: Option<String>,
+    pub captured_symbol: Option<Cow<'static, str>>,
     /// FEN symbol of the piece that moved (post-promotion if the move
     /// was a Promotion). Captures the *piece kind* without preserving
     /// per-piece state.
-    pub primary_symbol: String,
+    pub primary_symbol: Cow<'static, str>,
     /// Lightweight discriminator for what *kind* of move this was.
     /// Round-trippable. Pieces that branch on move-shape (Mirror skips
     /// Castle/PhaseShift; Echo replays Move/Promote deltas) read this
@@ -193,7 +313,8 @@ pub struct LastMove {
 }
 
 /// Round-trippable discriminator for the kind of move recorded in
-/// `LastMove`. One variant per `MoveType` variant, plus a catch-all
+/// `LastMove`. One variant per piece-moving `MoveType` variant (the
+/// duck half-moves are never recorded), plus a catch-all
 /// for nested PieceInCarrier shapes that don't have a unique geometry
 /// to expose.
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
@@ -207,6 +328,74 @@ pub enum LastMoveKind {
     ThrowSwitch,
     PieceInCarrier,
     PlaceTornado,
+    Drop,
+}
+
+/// Plan 11: a rule variant that can be switched on for a position.
+/// Listed in `BoardFlags::variants`; an empty list is standard chess.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum VariantId {
+    /// A colourless duck blocks one square; every turn is a piece move
+    /// followed by a duck move. No check — the king is captured. See
+    /// `board::duck`.
+    DuckChess,
+    /// Every capture explodes the captor and the non-pawn pieces around
+    /// it; kings can't capture. See `movement::stack::atomic`.
+    Atomic,
+    /// Captured pieces change sides into the taker's pocket, and a turn
+    /// can be spent dropping one back onto the board. See
+    /// `board::crazyhouse`.
+    Crazyhouse,
+    /// Losing chess: captures are compulsory, the king is an ordinary
+    /// piece, and the side that loses all its pieces wins. See
+    /// `movement::stack::antichess`.
+    Antichess,
+    /// King of the Hill: a king that reaches the hill wins, on top of
+    /// checkmate. The hill is the board's `Hill` squares, or the centre
+    /// when it has none. See `board::win::hill_squares`.
+    KingOfTheHill,
+    /// Three-check: giving the third check wins, on top of checkmate.
+    /// The counts are `white_checks` / `black_checks` (FEN `checks=`).
+    ThreeCheck,
+}
+
+impl VariantId {
+    /// Identifier used in the FEN `variants=` field.
+    pub fn fen_tag(&self) -> &'static str {
+        match self {
+            VariantId::DuckChess => "duck_chess",
+            VariantId::Atomic => "atomic",
+            VariantId::Crazyhouse => "crazyhouse",
+            VariantId::Antichess => "antichess",
+            VariantId::KingOfTheHill => "king_of_the_hill",
+            VariantId::ThreeCheck => "three_check",
+        }
+    }
+
+    /// Inverse of `fen_tag`.
+    pub fn parse_tag(s: &str) -> Option<Self> {
+        match s {
+            "duck_chess" => Some(VariantId::DuckChess),
+            "atomic" => Some(VariantId::Atomic),
+            "crazyhouse" => Some(VariantId::Crazyhouse),
+            "antichess" => Some(VariantId::Antichess),
+            "king_of_the_hill" => Some(VariantId::KingOfTheHill),
+            "three_check" => Some(VariantId::ThreeCheck),
+            _ => None,
+        }
+    }
+}
+
+/// Plan 11: which half of a Duck Chess turn comes next. Ignored unless
+/// `VariantId::DuckChess` is active.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum DuckPhase {
+    /// The side to move hasn't moved a piece yet this turn.
+    #[default]
+    PieceMove,
+    /// The side to move has moved its piece and must now place or move
+    /// the duck.
+    DuckPlacement,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -216,12 +405,22 @@ pub struct BoardFlags {
     pub white_can_castle_queenside: bool,
     pub black_can_castle_kingside: bool,
     pub black_can_castle_queenside: bool,
+    /// Where each castling right's rook stands, when it isn't in the
+    /// corner (see `board::chess960`). Written into the FEN castling
+    /// field as Shredder file letters.
+    pub castle_rook_files: CastleRookFiles,
+    /// What a pawn may promote to, in the order the moves are
+    /// generated; empty = `PromotionTarget::STANDARD`. FEN field
+    /// `promo=<id>,<id>`, omitted when empty.
+    pub promotion_targets: Vec<PromotionTarget>,
     pub en_passant_target: Option<Coord>,
     /// Plan 09: how often `maybe_advance_trains` actually ticks the trains.
     pub train_tick_rate: TrainTickRate,
-    /// Plan 09: monotonic ply counter. Bumped at every successful move,
-    /// even when the trains don't advance this ply. Used by the tick-rate
-    /// gate. Resets only on board reset.
+    /// Plan 09: monotonic ply counter. Bumped at every successful move
+    /// that passes the turn, even when the trains don't advance this
+    /// ply — a move that leaves the mover a bonus move (see
+    /// `extra_moves`) doesn't count, so the tick-rate gate stays in step
+    /// with the turns. Resets only on board reset.
     pub ply_count: u32,
     /// Plan 10 step 2: snapshot of the move that produced this board
     /// state. `None` on freshly-constructed boards. Set inside
@@ -233,6 +432,88 @@ pub struct BoardFlags {
     /// opponent's next turn this field describes the opponent's last
     /// move (which is what the consuming pieces want).
     pub last_move: Option<LastMove>,
+    /// Plies since the last pawn move or capture — the standard FEN
+    /// halfmove clock. Unlike `ply_count` this resets: a pawn mover
+    /// (including a pawn stepping out of a carrier), a direct capture,
+    /// and any material loss during the move's env reactions (capture-
+    /// stack removals, train run-overs, cart-boarding purges) all zero
+    /// it. `status()` reports `Draw { reason: FiftyMove }` at 100.
+    pub halfmove_clock: u32,
+    /// Standard FEN fullmove number. Starts at 1 and increments after
+    /// each Black move that passes the turn.
+    pub fullmove_number: u32,
+    /// Bonus moves banked by the side to move. Non-zero means the move
+    /// about to be played is a bonus move: it spends one, and
+    /// `side_to_move` only flips once none are left. Granted through
+    /// `BoardOp::GrantExtraMoves` (the Goblin's "taker moves again");
+    /// forfeited by a move that checks the opponent or leaves the mover
+    /// nothing to play. FEN field `em=<n>`, omitted at 0.
+    pub extra_moves: u8,
+    /// Plan 11: active rule variants; empty = standard chess. FEN field
+    /// `variants=<id>,<id>`, omitted when empty.
+    pub variants: Vec<VariantId>,
+    /// Plan 11: the Duck Chess half-turn. FEN field
+    /// `duck_phase=placing`, omitted at the default `PieceMove`.
+    pub duck_phase: DuckPhase,
+    /// How the game can be won (see `board::win`); empty = the
+    /// variants' default, checkmate or Duck Chess's king capture. FEN
+    /// field `win=<id>,<id>`, omitted when empty.
+    pub win_conditions: Vec<WinConditionId>,
+    /// Checks given so far by White / Black, counted only in games won
+    /// by `WinConditionId::NChecks`. FEN field `checks=<w>+<b>`,
+    /// omitted while both are 0.
+    pub white_checks: u8,
+    pub black_checks: u8,
+    /// Crazyhouse: the pieces White / Black has captured and may drop,
+    /// each kept in its fresh state and in its new owner's colour. FEN
+    /// field `pk=(W=<pieces>,B=<pieces>)`, omitted while both are
+    /// empty.
+    pub white_pocket: Vec<PieceType>,
+    pub black_pocket: Vec<PieceType>,
+}
+
+impl BoardFlags {
+    /// Is `variant` active for this position?
+    pub fn has_variant(&self, variant: VariantId) -> bool {
+        self.variants.contains(&variant)
+    }
+
+    /// What a pawn may promote to in this game.
+    pub fn promotion_choices(&self) -> &[PromotionTarget] {
+        if self.promotion_targets.is_empty() {
+            &PromotionTarget::STANDARD
+        } else {
+            &self.promotion_targets
+        }
+    }
+
+    /// Checks `color` has given so far; 0 for Neutral.
+    pub fn checks_given(&self, color: Color) -> u8 {
+        match color {
+            Color::White => self.white_checks,
+            Color::Black => self.black_checks,
+            Color::Neutral => 0,
+        }
+    }
+
+    /// The pieces `color` holds in hand; empty for Neutral.
+    pub fn pocket(&self, color: Color) -> &[PieceType] {
+        match color {
+            Color::White => &self.white_pocket,
+            Color::Black => &self.black_pocket,
+            Color::Neutral => &[],
+        }
+    }
+
+    /// Mutable handle on `color`'s pocket. `None` for Neutral, which
+    /// has none.
+    pub fn pocket_mut(&mut self, color: Color) -> Option<&mut Vec<PieceType>> {
+        match color {
+            Color::White => Some(&mut self.white_pocket),
+            Color::Black => Some(&mut self.black_pocket),
+            Color::Neutral => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -242,8 +523,37 @@ pub enum GameStatus {
     Check { side_to_move: Color },
     Checkmate { winner: Color },
     Stalemate,
+    Draw { reason: DrawReason },
+    /// The side to move has no legal move and at least one of its
+    /// pieces is frozen by Brainrot from a `winner` Skibidi — the
+    /// Skibidi's "win by Brainrot instead of stalemate".
+    BrainrotWin { winner: Color },
+    /// Any other decisive result, from the game's win conditions (see
+    /// `board::win`): king capture, extinction, King of the Hill, the
+    /// N-th check, or the Duck Chess stalemate win. Checkmate and
+    /// Brainrot keep their own variants for wire compatibility.
+    Won { winner: Color, reason: WinReason },
 }
 
+/// Why a `GameStatus::Draw` was declared. Stalemate stays its own
+/// `GameStatus` variant for wire compatibility.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum DrawReason {
+    /// A hundred plies (fifty full moves) with no pawn move and no
+    /// capture. See `BoardFlags::halfmove_clock`.
+    FiftyMove,
+    /// The same position (same `position_hash`) has occurred three
+    /// times since the last irreversible move.
+    ThreefoldRepetition,
+    /// Neither side has the material to ever deliver checkmate. See
+    /// `Board::is_insufficient_material`.
+    InsufficientMaterial,
+}
+
+/// Halfmove-clock value at which `status()` declares the fifty-move
+/// draw.
+pub const FIFTY_MOVE_PLIES: u32 = 100;
+
 /// Helper used by `Board::find_king` and tests. Lives at module scope so
 /// the closure inside `find_king` doesn't need to capture anything.
 fn king_of_color(piece: &PieceType, color: Color) -> bool {
@@ -311,6 +621,23 @@ pub enum MoveError {
         piece_color: Color,
         attempted: MoveType,
     },
+    /// Antichess: the side to move can capture, so every move that
+    /// doesn't is illegal this turn. Like `CompelledByTornado`, the
+    /// move is in the raw set; the compulsion sits above the raw cap.
+    MustCapture { from: Coord, attempted: MoveType },
+    /// Plan 11 (Duck Chess): the side to move has moved its piece and
+    /// must now place or move the duck, but a piece move was attempted.
+    MustMoveDuck { from: Coord, attempted: MoveType },
+    /// Plan 11 (Duck Chess): a duck move that isn't allowed here — the
+    /// game isn't Duck Chess, it isn't the duck half-move, the duck
+    /// can't land on the target, or `PlaceDuck` was used with a duck
+    /// already on the board (and vice versa).
+    IllegalDuckMove { from: Coord, attempted: MoveType },
+    /// Crazyhouse: a drop that isn't allowed here — the game isn't
+    /// Crazyhouse, the piece isn't in the side to move's pocket, the
+    /// target isn't an empty walkable square, or a pawn was dropped on
+    /// the first or last rank.
+    IllegalDrop { from: Coord, attempted: MoveType },
     /// `make_move_unchecked` returned `Err` after `validate_move` already
     /// accepted the move. In practice this is unreachable from a normal
     /// `make_move` call — `validate_move` runs the same apply path on a
@@ -388,6 +715,23 @@ impl MoveError {
                  a tornado compels this side — you must move onto a tornado \
                  square (or this piece is trapped on one)."
             ),
+            MoveError::MustCapture { from, attempted } => format!(
+                "Cannot {attempted} from {from}: a capture is available, and \
+                 captures are compulsory."
+            ),
+            MoveError::MustMoveDuck { from, attempted } => format!(
+                "Cannot {attempted} from {from}: the piece has moved this turn, \
+                 so the duck must be placed or moved now."
+            ),
+            MoveError::IllegalDuckMove { from, attempted } => format!(
+                "Cannot {attempted} (from {from}): the duck can only be moved in \
+                 Duck Chess, right after a piece move, onto an empty square."
+            ),
+            MoveError::IllegalDrop { from, attempted } => format!(
+                "Cannot {attempted} (at {from}): a drop needs the piece in your \
+                 pocket and an empty square, and pawns can't go on the first or \
+                 last rank."
+            ),
             MoveError::ApplyFailed {
                 from,
                 attempted,
@@ -412,24 +756,42 @@ impl std::error::Error for MoveError {}
 // rejects. Memory-safe (all grid access is bounds-checked) but
 // engine-invalid. No `Board`-from-JSON ingress exists today (the API
 // takes `board_fen`); any future one must validate before use.
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
-    pub grid: Vec<Vec<Square>>,
+    pub grid: Grid,
     pub flags: BoardFlags,
+    /// The cached position hash and the repetition history (see
+    /// `board::zobrist`). Game history, not position: the FEN doesn't
+    /// carry it, serde skips it and equality ignores it.
+    #[serde(skip)]
+    pub(crate) history: PositionHistory,
+}
+
+/// Equality of the positions: grid and flags. The history is left out,
+/// so a board compares equal to its own FEN round-trip.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.grid == other.grid && self.flags == other.flags
+    }
 }
 
 impl Board {
+    /// A board of `grid` and `flags`, with no history behind it.
+    pub fn new(grid: Grid, flags: BoardFlags) -> Self {
+        Board {
+            grid,
+            flags,
+            history: PositionHistory::default(),
+        }
+    }
+
     /// Get an immutable reference to the square at `coord`, if within bounds.
     pub fn get_square_at(&self, coord: &Coord) -> Option<&Square> {
-        self.grid
-            .get(coord.rank as usize)
-            .and_then(|row| row.get(coord.file as usize))
+        self.grid.square(coord.file as usize, coord.rank as usize)
     }
     /// Get a mutable reference to the square at `coord`, if within bounds.
     pub fn get_square_mut(&mut self, coord: &Coord) -> Option<&mut Square> {
-        self.grid
-            .get_mut(coord.rank as usize)
-            .and_then(|row| row.get_mut(coord.file as usize))
+        self.grid.square_mut(coord.file as usize, coord.rank as usize)
     }
 
     pub fn set_piece_at(&mut self, coord: &Coord, piece: PieceType) {
@@ -440,7 +802,7 @@ impl Board {
 
     pub fn square_is_empty(&self, coord: &Coord) -> bool {
         if let Some(square) = self.get_square_at(coord) {
-            square.square_type.is_walkable() && square.piece.is_none()
+            square.is_walkable() && square.piece.is_none()
         } else {
             false
         }
@@ -449,7 +811,8 @@ impl Board {
     /// True if `coord` is in bounds AND its square type is walkable
     /// (Standard / Switch / Junction / Track / PressurePlate / open Gate).
     /// Out-of-bounds → false. Unwalkable types (Block, Turret, Vent,
-    /// closed Gate) → false.
+    /// closed Gate) → false. Terrain only: the Duck Chess duck doesn't
+    /// count (see `Square::is_walkable` for the occupancy-aware check).
     pub fn is_walkable_at(&self, coord: &Coord) -> bool {
         self.get_square_at(coord)
             .map(|sq| sq.square_type.is_walkable())
@@ -468,8 +831,10 @@ impl Board {
     ///   when the source is a `Switch` tile.
     /// - Train-threat modifiers and (future) king-safety filter sit
     ///   on the threat side and don't touch this path.
+    ///
+    /// The stack is the one for this board's ruleset (`Board::rules`).
     pub fn get_moves(&self, from: &Coord) -> Vec<GameMove> {
-        crate::movement::stack::default_stack().resolve_moves(self, from)
+        self.rules().movement.resolve_moves(self, from)
     }
 
     /// Takes a from and to coordinate and returns true if the move is valid.
@@ -490,7 +855,7 @@ impl Board {
         &self,
         source_piece: &PieceType,
         game_move: &GameMove,
-    ) -> (Color, Option<String>) {
+    ) -> (Color, Option<Cow<'static, str>>) {
         let piece_color = source_piece.get_color();
         match &game_move.move_type {
             MoveType::PieceInCarrier { piece_index, .. } => {
@@ -513,8 +878,19 @@ impl Board {
     /// 2. Source has a piece.
     /// 3. Piece is the side to move.
     /// 4. Move is in the piece's raw move set (`get_moves`).
-    /// 5. Applying the move doesn't leave the mover's own king in check.
+    /// 5. Applying the move doesn't leave the mover's own king in check
+    ///    (skipped in Duck Chess, which has no check).
+    ///
+    /// Duck moves, and every move during the Duck Chess duck half-move,
+    /// are checked by `validate_duck_move` instead, and Crazyhouse
+    /// drops by `validate_drop`.
     pub fn validate_move(&self, game_move: &GameMove) -> Result<(), MoveError> {
+        if game_move.move_type.is_duck_move() || self.in_duck_placement() {
+            return self.validate_duck_move(game_move);
+        }
+        if let MoveType::Drop { .. } = &game_move.move_type {
+            return self.validate_drop(game_move);
+        }
         let Some(square) = self.get_square_at(&game_move.from) else {
             return Err(MoveError::NoSourceSquare {
                 from: game_move.from.clone(),
@@ -540,10 +916,10 @@ impl Board {
         if effective_color != side_to_move {
             return Err(MoveError::WrongTurn {
                 from: game_move.from.clone(),
-                piece_symbol,
+                piece_symbol: piece_symbol.into_owned(),
                 piece_color: effective_color,
                 side_to_move,
-                passenger_symbol,
+                passenger_symbol: passenger_symbol.map(Cow::into_owned),
             });
         }
 
@@ -551,39 +927,39 @@ impl Board {
         if !raw_moves.iter().any(|m| m == game_move) {
             return Err(MoveError::PieceCannotMakeMove {
                 from: game_move.from.clone(),
-                piece_symbol,
+                piece_symbol: piece_symbol.into_owned(),
                 piece_color,
                 attempted: game_move.move_type.clone(),
                 candidate_alternatives: raw_moves.into_iter().map(|m| m.move_type).collect(),
             });
         }
 
-        let mut hypothetical = self.clone();
         // Plan 09: validate runs the apply through phase 2 (piece-level
         // post-effects) but not phase 3 (train tick). If we let the
         // tick run, a train could capture the mover's king during the
         // hypothetical apply and `is_in_check` would then look for a
         // king that doesn't exist, silently returning `false`.
-        match hypothetical.apply_move_for_validation(game_move.clone()) {
-            Ok(()) => {
-                // King-safety check is per the *mover's* king. For a
-                // PieceInCarrier move out of a neutral cart the mover
-                // is the passenger, so use the effective colour here
-                // too — otherwise `is_in_check(Neutral)` short-
-                // circuits to false and we'd never catch a passenger
-                // exiting into a square that leaves their own king
-                // in check.
-                if hypothetical.is_in_check(effective_color) {
-                    return Err(MoveError::WouldLeaveKingInCheck {
-                        from: game_move.from.clone(),
-                        piece_symbol,
-                        piece_color: effective_color,
-                        attempted: game_move.move_type.clone(),
-                    });
-                }
+        let ignores_check = !self.ruleset().enforces_check();
+        let applied = self.probe_move(game_move, |hypothetical| {
+            // King-safety check is per the *mover's* king. For a
+            // PieceInCarrier move out of a neutral cart the mover is
+            // the passenger, so use the effective colour here too —
+            // otherwise `is_in_check(Neutral)` short-circuits to false
+            // and we'd never catch a passenger exiting into a square
+            // that leaves their own king in check.
+            hypothetical.map(|h| !ignores_check && self.leaves_king_in_check(h, effective_color))
+        });
+        match applied {
+            Ok(true) => Err(MoveError::WouldLeaveKingInCheck {
+                from: game_move.from.clone(),
+                piece_symbol: piece_symbol.into_owned(),
+                piece_color: effective_color,
+                attempted: game_move.move_type.clone(),
+            }),
+            Ok(false) => {
                 // Plan 13 (audit R1/C1): king-safety is enforced here via
-                // the clone-apply above, but the tornado compulsion sits
-                // at movement-stack priority 305 — above the 299 cap that
+                // the probe above, but the tornado compulsion sits at
+                // movement-stack priority 305 — above the 299 cap that
                 // `get_moves` (the raw-membership check) uses, and it only
                 // runs in `resolve_legal_moves`. Without this gate
                 // `make_move` would execute a compulsion-violating move or
@@ -604,11 +980,17 @@ impl Board {
                 {
                     return Err(MoveError::CompelledByTornado {
                         from: game_move.from.clone(),
-                        piece_symbol,
+                        piece_symbol: piece_symbol.into_owned(),
                         piece_color: effective_color,
                         attempted: game_move.move_type.clone(),
                     });
                 }
+                if self.breaks_capture_compulsion(game_move) {
+                    return Err(MoveError::MustCapture {
+                        from: game_move.from.clone(),
+                        attempted: game_move.move_type.clone(),
+                    });
+                }
                 Ok(())
             }
             Err(reason) => Err(MoveError::ApplyFailed {
@@ -628,6 +1010,9 @@ impl Board {
     /// `would_capture_at` filter and Neutral-passenger descent) now
     /// lives in `LegacyPieceAttacksModifier` at priority 50; step 4
     /// will split it into per-piece modifiers in the 0..99 band.
+    ///
+    /// Answers are cached on the grid (`board::attacks`): asking again
+    /// before the square's surroundings change is a lookup.
     pub fn is_attacked_by(&self, target: &Coord, attacker: Color) -> bool {
         // "Is this square attacked by the Neutral side?" is semantically
         // meaningless — Neutral is unaligned and has no king. Keep the
@@ -636,9 +1021,12 @@ impl Board {
         if attacker == Color::Neutral {
             return false;
         }
-        !crate::movement::stack::default_stack()
-            .resolve_threats(self, target, attacker)
-            .is_empty()
+        // The duck can't be captured, so its square is never attacked —
+        // pawns' diagonal attacks would otherwise still reach it.
+        if self.get_square_at(target).is_some_and(|s| s.duck) {
+            return false;
+        }
+        self.attacked_cached(target, attacker)
     }
 
     /// Locate the king of `color`, if one exists on the board. Returns
@@ -681,6 +1069,25 @@ impl Board {
         }
     }
 
+    /// Would `color`, having made the move that turned this position
+    /// into `after`, be left in check? In games won by king capture a
+    /// move can also take a king off the board outright (an Atomic
+    /// blast): losing `color`'s own king counts as being left in check,
+    /// even if the opposing king went with it, and taking only the
+    /// opposing king never does — that ends the game first.
+    pub(crate) fn leaves_king_in_check(&self, after: &Board, color: Color) -> bool {
+        if self.ruleset().has_win(WinConditionId::KingCapture) {
+            let lost = |c: Color| self.find_king(c).is_some() && after.find_king(c).is_none();
+            if lost(color) {
+                return true;
+            }
+            if lost(color.opposite()) {
+                return false;
+            }
+        }
+        after.is_in_check(color)
+    }
+
     /// Subset of `get_moves(from)` after dropping any move that would
     /// leave the moving side's own king in check (or fail apply for
     /// any other reason).
@@ -689,55 +1096,132 @@ impl Board {
     /// `resolve_legal_moves`, which runs the full stack including
     /// the king-safety modifier at priority 300. Same correctness
     /// guarantee as the legacy inline filter; Duck Chess (plan 11)
-    /// will opt out by reading the variant flag inside the modifier.
+    /// opts out by leaving the modifier out of its ruleset's stack,
+    /// and during its duck half-move this is the duck's moves instead.
     pub fn legal_moves(&self, from: &Coord) -> Vec<GameMove> {
-        crate::movement::stack::default_stack().resolve_legal_moves(self, from)
+        self.rules().movement.resolve_legal_moves(self, from)
+    }
+
+    /// Every legal move from `color`'s move sources (see
+    /// `move_sources`) in one pass: the same moves, in the same order,
+    /// as `legal_moves` on each source in turn, but check state, pins
+    /// and the tornado probe are worked out once for the position
+    /// rather than once per piece (see `resolve_all_legal_moves`). Like
+    /// the per-square path, a Neutral cart's source can yield moves of
+    /// either rider colour; filter on the mover when that matters.
+    pub fn all_legal_moves(&self, color: Color) -> Vec<GameMove> {
+        self.rules()
+            .movement
+            .resolve_all_legal_moves(self, &self.move_sources(color))
     }
 
-    /// Overall status from the perspective of `side_to_move`. `BrainrotWin`
-    /// is intentionally absent — plan 04 will fold that in once the
-    /// distinguish-stalemate-from-brainrot heuristic lands.
+    /// Does `color` have any legal move? `all_legal_moves` that stops
+    /// at the first source with one.
+    pub fn has_legal_move(&self, color: Color) -> bool {
+        self.rules()
+            .movement
+            .any_legal_move(self, &self.move_sources(color))
+    }
+
+    /// Overall status from the perspective of `side_to_move`.
+    ///
+    /// The game's win conditions (`Rules::win`, see `board::win`) are
+    /// asked first, in priority order, and the first decisive one ends
+    /// the game — checkmate in standard chess. With no legal move and
+    /// no win, the game is a `BrainrotWin` for the opponent when one of
+    /// the stuck side's pieces stands in an enemy Skibidi's Brainrot
+    /// (see `is_brainrotted_by`), and a `Stalemate` otherwise. That's
+    /// deliberately loose: the rotted piece needn't be the one that
+    /// would otherwise have moved.
+    ///
+    /// The fifty-move, threefold-repetition and insufficient-material
+    /// draws come after both: a move that mates on the hundredth ply is
+    /// still checkmate (FIDE 9.3), and a stalemate stays a stalemate.
+    /// A game without check (`RuleSet::enforces_check`) never reports
+    /// `Check`. The insufficient-material draw only applies to games
+    /// won by checkmate alone: a bare king can still take the other,
+    /// or walk to the hill.
+    ///
+    /// Mid-turn, with a bonus move banked (`BoardFlags::extra_moves`),
+    /// the status is the mover's: `make_move` forfeits the bonus rather
+    /// than leave the opponent in check or the mover without a move, so
+    /// neither reading arises here.
     pub fn status(&self) -> GameStatus {
         let to_move = self.flags.side_to_move;
-        // Same-color pieces are the primary source of legal moves. But
-        // a Neutral cart carrying a passenger of `to_move` also has
-        // legal `PieceInCarrier` moves on `to_move`'s turn (via
-        // `passenger_moves`), and skipping the cart would mis-declare
-        // stalemate/checkmate when the side's only remaining pieces
-        // are riding a neutral train. `find_king` already descends
-        // into carriers; do the same here for symmetry.
-        // Collect coords first to avoid holding the iterator borrow
-        // across `legal_moves(coord)` calls (which take `&self`).
-        let coords_to_check: Vec<Coord> = self
+        let stuck = !self.has_legal_move(to_move);
+        if let Some(won) = self.rules().win.decide(self, stuck) {
+            return won;
+        }
+        if stuck {
+            let rotted = self.iter_pieces().any(|(coord, piece)| {
+                piece.get_color() == to_move && self.is_brainrotted_by(&coord, to_move.opposite())
+            });
+            return if rotted {
+                GameStatus::BrainrotWin {
+                    winner: to_move.opposite(),
+                }
+            } else {
+                GameStatus::Stalemate
+            };
+        }
+        if self.flags.halfmove_clock >= FIFTY_MOVE_PLIES {
+            return GameStatus::Draw {
+                reason: DrawReason::FiftyMove,
+            };
+        }
+        if self.repetition_count() >= 3 {
+            return GameStatus::Draw {
+                reason: DrawReason::ThreefoldRepetition,
+            };
+        }
+        let ruleset = self.ruleset();
+        if ruleset.win_conditions() == [WinConditionId::Checkmate]
+            && self.is_insufficient_material()
+        {
+            return GameStatus::Draw {
+                reason: DrawReason::InsufficientMaterial,
+            };
+        }
+        if ruleset.enforces_check() && self.is_in_check(to_move) {
+            return GameStatus::Check {
+                side_to_move: to_move,
+            };
+        }
+        GameStatus::Ongoing
+    }
+
+    /// Squares that can originate a move for `color`. Same-color pieces
+    /// are the primary source of legal moves. But a Neutral cart
+    /// carrying a passenger of `color` also has legal `PieceInCarrier`
+    /// moves on `color`'s turn (via `passenger_moves`), and skipping the
+    /// cart would mis-declare stalemate/checkmate when the side's only
+    /// remaining pieces are riding a neutral train. `find_king` already
+    /// descends into carriers; do the same here for symmetry.
+    ///
+    /// During the Duck Chess duck half-move the sources are the duck's
+    /// instead (see `duck_sources`), whatever `color` is asked for. In
+    /// Crazyhouse, every square `color` could drop a pocketed piece on
+    /// is a source too (see `drop_sources`): `status()` has to see the
+    /// drops that block a check before it calls mate.
+    ///
+    /// Collected up front so callers don't hold the `iter_pieces`
+    /// borrow across `legal_moves(coord)` calls.
+    pub(crate) fn move_sources(&self, color: Color) -> Vec<Coord> {
+        if self.in_duck_placement() {
+            return self.duck_sources();
+        }
+        let mut sources: Vec<Coord> = self
             .iter_pieces()
             .filter(|(_, p)| {
-                p.get_color() == to_move
+                p.get_color() == color
                     || (p.get_color() == Color::Neutral
-                        && p.passengers().is_some_and(|ps| {
-                            ps.iter().any(|q| q.get_color() == to_move)
-                        }))
+                        && p.passengers()
+                            .is_some_and(|ps| ps.iter().any(|q| q.get_color() == color)))
             })
             .map(|(c, _)| c)
             .collect();
-        let any_legal = coords_to_check
-            .iter()
-            .any(|coord| !self.legal_moves(coord).is_empty());
-
-        if any_legal {
-            if self.is_in_check(to_move) {
-                return GameStatus::Check {
-                    side_to_move: to_move,
-                };
-            }
-            return GameStatus::Ongoing;
-        }
-        if self.is_in_check(to_move) {
-            GameStatus::Checkmate {
-                winner: to_move.opposite(),
-            }
-        } else {
-            GameStatus::Stalemate
-        }
+        sources.extend(self.drop_sources(color));
+        sources
     }
 
     pub fn all_pieces(&self) -> Vec<(Coord, PieceType)> {
@@ -779,22 +1263,26 @@ impl Board {
             self.grid.len()
         );
         debug_assert!(
-            self.grid.iter().all(|row| row.len() <= 255),
-            "iter_pieces: at least one row exceeds the u8 Coord limit"
+            self.grid.width() <= 255,
+            "iter_pieces: grid width {} exceeds u8 Coord limit",
+            self.grid.width()
         );
-        self.grid.iter().enumerate().flat_map(|(rank, row)| {
-            row.iter().enumerate().filter_map(move |(file, square)| {
+        let width = self.grid.width();
+        self.grid
+            .squares()
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, square)| {
                 square.piece.as_ref().map(|p| {
                     (
                         Coord {
-                            file: file as u8,
-                            rank: rank as u8,
+                            file: (i % width) as u8,
+                            rank: (i / width) as u8,
                         },
                         p,
                     )
                 })
             })
-        })
     }
 
     /// Returns true if (file, rank) is inside the board grid.
@@ -802,7 +1290,7 @@ impl Board {
         rank >= 0
             && file >= 0
             && (rank as usize) < self.grid.len()
-            && (file as usize) < self.grid[rank as usize].len()
+            && (file as usize) < self.grid.width()
     }
 
     /// Number of ranks (rows) in the board. `height() - 1` is the bottom rank.
@@ -810,10 +1298,10 @@ impl Board {
         self.grid.len() as u8
     }
 
-    /// Number of files (columns) on the board, measured by the first row.
-    /// Rows are expected to be uniform in length; FEN parsing enforces this.
+    /// Number of files (columns) on the board. Every rank of a `Grid`
+    /// is the same length.
     pub fn width(&self) -> u8 {
-        self.grid.first().map(|row| row.len() as u8).unwrap_or(0)
+        self.grid.width() as u8
     }
 
     /// Algebraic notation ("e4") for a Coord, using this board's height to
@@ -825,4 +1313,23 @@ impl Board {
         let algebraic_rank = self.height().saturating_sub(c.rank);
         format!("{file_letter}{algebraic_rank}")
     }
+
+    /// Inverse of `format_coord`: "e4" → `Coord`. `None` for anything
+    /// that isn't a file letter followed by a rank number on this
+    /// board.
+    pub fn parse_coord(&self, s: &str) -> Option<Coord> {
+        let mut chars = s.chars();
+        let file_letter = chars.next().filter(|c| c.is_ascii_lowercase())?;
+        let rank_str = chars.as_str();
+        if rank_str.is_empty() || !rank_str.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let algebraic_rank: u8 = rank_str.parse().ok()?;
+        let file = file_letter as u8 - b'a';
+        let rank = self.height().checked_sub(algebraic_rank)?;
+        if algebraic_rank == 0 || !self.in_bounds(file as isize, rank as isize) {
+            return None;
+        }
+        Some(Coord { file, rank })
+    }
 }
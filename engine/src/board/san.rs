@@ -0,0 +1,342 @@
+//! Standard algebraic notation, plus extensions for the fairy moves.
+//!
+//! The standard shapes render exactly as in chess: `e4`, `Nbd7`,
+//! `R1e2`, `exd5`, `exd6` (en passant), `e8=Q`, `O-O`, `O-O-O`, with a
+//! `+` / `#` suffix for check / mate. Piece letters are the piece's FEN
+//! glyph, uppercased and stripped of any `(...)` payload, so fairy
+//! pieces get `G`, `S`, `W`, `M`, `BUS`, `LOCO`, `CART` — promotions
+//! into them included (`e8=G`, `e8=BUS`).
+//!
+//! The fairy move types have no chess spelling. They render as:
+//!
+//! | `MoveType`         | SAN          | reads as                                  |
+//! |--------------------|--------------|-------------------------------------------|
+//! | `MoveIntoCarrier`  | `N>c3`       | knight boards the carrier on c3           |
+//! | `PieceInCarrier`   | `c3/1:Nxd5`  | passenger #1 of the carrier on c3 acts    |
+//! | nested             | `c3/1/0:Pd4` | passenger #0 of passenger #1 of c3 acts   |
+//! | `PhaseShift`       | `Se4~`       | the Skibidi on e4 phase-shifts            |
+//! | `ThrowSwitch`      | `Re4^`       | the rook on e4 throws its switch          |
+//! | `PlaceTornado`     | `W*e5`       | a Stormcaller places a tornado on e5      |
+//! | `PlaceDuck`        | `&e5`        | the duck is set down on e5                |
+//! | `MoveDuck`         | `&e5`        | the duck moves to e5                      |
+//! | `Drop`             | `N@e4`       | a knight is dropped from the pocket on e4 |
+//!
+//! There's only one duck, so `&` moves never need its square. Duck
+//! Chess has no check, so none of its moves carry a `+` / `#`. A drop
+//! always writes its letter, `P` included (`P@e4`), as in Crazyhouse.
+//!
+//! `>` and `*` moves disambiguate like ordinary moves (`Nb>c3`).
+//! `~` and `^` name the acting square outright, since nothing else
+//! identifies the piece. After the `:` of a passenger move the letter
+//! is always written, `P` included; the carrier square already pins
+//! down the piece, so there is never a disambiguator. The letter is
+//! likewise always written for a pawn making a fairy move (`P>e3`) —
+//! only the chess shapes drop it.
+//!
+//! Parsing goes the other way round: `san_to_move` renders every legal
+//! move and picks the one whose notation matches, so the two
+//! directions can't drift apart.
+
+use crate::{
+    board::{Board, CastleSide, Coord, GameMove, MoveError, MoveType, PromotionTarget},
+    pieces::piecetype::PieceType,
+};
+
+/// Why `Board::san_to_move` couldn't resolve a string.
+#[derive(PartialEq, Debug, Clone)]
+pub enum SanError {
+    /// Nothing left once whitespace and annotation suffixes were
+    /// stripped.
+    EmptyInput,
+    /// No legal move for the side to move renders as this string.
+    NoMatch(String),
+    /// More than one legal move renders as this string. Canonical SAN
+    /// never produces this; it guards against notation collisions.
+    Ambiguous { san: String, candidates: Vec<GameMove> },
+}
+
+impl std::fmt::Display for SanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SanError::EmptyInput => write!(f, "SAN is empty."),
+            SanError::NoMatch(san) => write!(f, "No legal move matches '{san}'."),
+            SanError::Ambiguous { san, candidates } => write!(
+                f,
+                "'{san}' matches {} legal moves; add a disambiguator.",
+                candidates.len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+/// SAN piece letter: the FEN glyph, uppercased, payload dropped.
+fn piece_letter(piece: &PieceType) -> String {
+    let symbol = piece.symbol();
+    symbol
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .to_uppercase()
+}
+
+/// The piece letter of what a pawn promotes to, as `piece_letter`
+/// would write that piece.
+fn promotion_letter(into: &PromotionTarget) -> &'static str {
+    match into {
+        PromotionTarget::Queen => "Q",
+        PromotionTarget::Rook => "R",
+        PromotionTarget::Bishop => "B",
+        PromotionTarget::Knight => "N",
+        PromotionTarget::Goblin => "G",
+        PromotionTarget::Skibidi => "S",
+        PromotionTarget::Monkey => "M",
+        PromotionTarget::Bus => "BUS",
+        PromotionTarget::Stormcaller => "W",
+    }
+}
+
+/// Do `a` and `b` land on the same square the same way? Two such moves
+/// by same-letter pieces need a disambiguator.
+fn same_action(a: &MoveType, b: &MoveType) -> bool {
+    match (a, b) {
+        (MoveType::MoveTo(x), MoveType::MoveTo(y))
+        | (MoveType::MoveIntoCarrier(x), MoveType::MoveIntoCarrier(y))
+        | (MoveType::PlaceTornado { target: x }, MoveType::PlaceTornado { target: y })
+        | (MoveType::EnPassant { target: x, .. }, MoveType::EnPassant { target: y, .. }) => {
+            x == y
+        }
+        (
+            MoveType::Promotion { target: x, into: i },
+            MoveType::Promotion { target: y, into: j },
+        ) => x == y && i == j,
+        _ => false,
+    }
+}
+
+/// Strip what SAN readers conventionally tolerate but `move_to_san`
+/// never needs to match on: surrounding whitespace, check / mate
+/// marks, `!` / `?` annotations, an `e.p.` tag, and zeros for the
+/// castling O's.
+fn normalize(san: &str) -> String {
+    let mut s = san.trim();
+    s = s.trim_end_matches(['+', '#', '!', '?']);
+    s = s.strip_suffix("e.p.").unwrap_or(s).trim_end();
+    match s {
+        "0-0" => "O-O".to_string(),
+        "0-0-0" => "O-O-O".to_string(),
+        _ => s.to_string(),
+    }
+}
+
+impl Board {
+    /// Render `game_move` in SAN (see the module docs for the fairy
+    /// extensions). The move must be legal here — the check / mate
+    /// suffix comes from actually playing it — so an illegal move
+    /// returns the `MoveError` `make_move` would.
+    pub fn move_to_san(&self, game_move: &GameMove) -> Result<String, MoveError> {
+        let mut after = self.clone();
+        after.make_move(game_move.clone())?;
+        let legal = self.side_legal_moves();
+        let mut san = self.san_body(game_move, &legal);
+        if !self.ruleset().enforces_check() {
+            return Ok(san);
+        }
+        let defender = after.flags.side_to_move;
+        if after.is_in_check(defender) {
+            let mated = !after.has_legal_move(defender);
+            san.push(if mated { '#' } else { '+' });
+        }
+        Ok(san)
+    }
+
+    /// Resolve a SAN string to the legal move it names for the side to
+    /// move. Check and annotation suffixes are optional and ignored.
+    pub fn san_to_move(&self, san: &str) -> Result<GameMove, SanError> {
+        let wanted = normalize(san);
+        if wanted.is_empty() {
+            return Err(SanError::EmptyInput);
+        }
+        let legal = self.side_legal_moves();
+        let mut candidates: Vec<GameMove> = legal
+            .iter()
+            .filter(|m| self.san_body(m, &legal) == wanted)
+            .cloned()
+            .collect();
+        match candidates.len() {
+            0 => Err(SanError::NoMatch(san.to_string())),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(SanError::Ambiguous {
+                san: san.to_string(),
+                candidates,
+            }),
+        }
+    }
+
+    /// Every legal move for the side to move, passenger moves
+    /// included. Neutral carts can carry both colours, so moves are
+    /// kept by the colour of whoever actually moves. The duck belongs
+    /// to nobody; its moves are kept whenever they're legal, as are
+    /// drops, which only the side to move is offered. A generator can
+    /// offer the same move twice (the Monkey reaches one landing by
+    /// several jump chains); only the first copy is kept, so a move
+    /// never collides with itself.
+    fn side_legal_moves(&self) -> Vec<GameMove> {
+        let color = self.flags.side_to_move;
+        let mut moves: Vec<GameMove> = Vec::new();
+        for m in self.all_legal_moves(color) {
+            let ours = m.move_type.is_duck_move()
+                || matches!(m.move_type, MoveType::Drop { .. })
+                || self
+                    .get_square_at(&m.from)
+                    .and_then(|s| s.piece.as_ref())
+                    .is_some_and(|p| self.effective_mover_color(p, &m).0 == color);
+            if ours && !moves.contains(&m) {
+                moves.push(m);
+            }
+        }
+        moves
+    }
+
+    /// SAN without the check suffix. `legal` is every legal move for
+    /// the mover's side, consulted for disambiguation.
+    fn san_body(&self, game_move: &GameMove, legal: &[GameMove]) -> String {
+        if let MoveType::PlaceDuck { to } | MoveType::MoveDuck { to } = &game_move.move_type {
+            return format!("&{}", self.format_coord(to));
+        }
+        if let MoveType::Drop { piece, target } = &game_move.move_type {
+            return format!("{}@{}", piece_letter(piece), self.format_coord(target));
+        }
+        let from = &game_move.from;
+        let Some(piece) = self.get_square_at(from).and_then(|s| s.piece.as_ref()) else {
+            return String::new();
+        };
+        let letter = piece_letter(piece);
+        let is_pawn = matches!(piece, PieceType::Pawn(_));
+        let occupied = |c: &Coord| self.get_square_at(c).is_some_and(|s| s.piece.is_some());
+
+        match &game_move.move_type {
+            MoveType::Castle { side } => match side {
+                CastleSide::Kingside => "O-O".to_string(),
+                CastleSide::Queenside => "O-O-O".to_string(),
+            },
+            MoveType::MoveTo(to) if is_pawn => {
+                let capture = occupied(to);
+                let prefix = self.disambiguation(game_move, &letter, legal, capture);
+                let x = if capture { "x" } else { "" };
+                format!("{prefix}{x}{}", self.format_coord(to))
+            }
+            MoveType::Promotion { target, into } => {
+                let capture = occupied(target);
+                let prefix = self.disambiguation(game_move, &letter, legal, capture);
+                let x = if capture { "x" } else { "" };
+                format!(
+                    "{prefix}{x}{}={}",
+                    self.format_coord(target),
+                    promotion_letter(into)
+                )
+            }
+            MoveType::EnPassant { target, .. } => {
+                let prefix = self.disambiguation(game_move, &letter, legal, true);
+                format!("{prefix}x{}", self.format_coord(target))
+            }
+            MoveType::MoveTo(to) => {
+                let prefix = self.disambiguation(game_move, &letter, legal, false);
+                let x = if occupied(to) { "x" } else { "" };
+                format!("{letter}{prefix}{x}{}", self.format_coord(to))
+            }
+            MoveType::MoveIntoCarrier(to) => {
+                let prefix = self.disambiguation(game_move, &letter, legal, false);
+                format!("{letter}{prefix}>{}", self.format_coord(to))
+            }
+            MoveType::PlaceTornado { target } => {
+                let prefix = self.disambiguation(game_move, &letter, legal, false);
+                format!("{letter}{prefix}*{}", self.format_coord(target))
+            }
+            MoveType::PhaseShift => format!("{letter}{}~", self.format_coord(from)),
+            // Rendered before the piece lookup: the duck isn't a piece,
+            // and a dropped piece isn't on the board yet.
+            MoveType::PlaceDuck { .. } | MoveType::MoveDuck { .. } | MoveType::Drop { .. } => {
+                String::new()
+            }
+            MoveType::ThrowSwitch { switch } => {
+                format!("{letter}{}^", self.format_coord(switch))
+            }
+            MoveType::PieceInCarrier { .. } => {
+                let mut out = self.format_coord(from);
+                let mut carrier = piece;
+                let mut move_type = &game_move.move_type;
+                while let MoveType::PieceInCarrier {
+                    piece_index,
+                    move_type: inner,
+                } = move_type
+                {
+                    out.push_str(&format!("/{piece_index}"));
+                    match carrier
+                        .passengers()
+                        .and_then(|ps| ps.get(*piece_index as usize))
+                    {
+                        Some(passenger) => carrier = passenger,
+                        None => return out,
+                    }
+                    move_type = inner.as_ref();
+                }
+                out.push(':');
+                out.push_str(&piece_letter(carrier));
+                match move_type {
+                    MoveType::MoveTo(to) => {
+                        if occupied(to) {
+                            out.push('x');
+                        }
+                        out.push_str(&self.format_coord(to));
+                    }
+                    MoveType::MoveIntoCarrier(to) => {
+                        out.push('>');
+                        out.push_str(&self.format_coord(to));
+                    }
+                    // `make_move_unchecked` rejects every other inner
+                    // shape, so no legal move reaches here.
+                    other => out.push_str(&other.to_string()),
+                }
+                out
+            }
+        }
+    }
+
+    /// The from-square qualifier SAN needs to tell `game_move` apart
+    /// from the same action by another `letter` piece: the file if that
+    /// suffices, else the rank, else both. `force_file` is the pawn-
+    /// capture rule — the file is written even when nothing competes.
+    fn disambiguation(
+        &self,
+        game_move: &GameMove,
+        letter: &str,
+        legal: &[GameMove],
+        force_file: bool,
+    ) -> String {
+        let from = &game_move.from;
+        let rivals: Vec<&Coord> = legal
+            .iter()
+            .filter(|m| &m.from != from && same_action(&m.move_type, &game_move.move_type))
+            .filter(|m| {
+                self.get_square_at(&m.from)
+                    .and_then(|s| s.piece.as_ref())
+                    .is_some_and(|p| piece_letter(p) == letter)
+            })
+            .map(|m| &m.from)
+            .collect();
+        let square = self.format_coord(from);
+        let (file, rank) = square.split_at(1);
+        if rivals.is_empty() {
+            return if force_file { file.to_string() } else { String::new() };
+        }
+        if rivals.iter().all(|c| c.file != from.file) {
+            file.to_string()
+        } else if !force_file && rivals.iter().all(|c| c.rank != from.rank) {
+            rank.to_string()
+        } else {
+            square
+        }
+    }
+}
@@ -2,7 +2,7 @@ use tracing::{debug, trace, warn};
 
 use crate::board::{
     Board, Coord, SignalId,
-    square::{PressureTrigger, SquareType},
+    square::{PressureTrigger, Square, SquareType},
 };
 
 impl Board {
@@ -81,46 +81,48 @@ impl Board {
     /// (step 4) regardless of the standing piece's brainrot state, and
     /// the receivers downstream of that pulse should too.
     fn activate_receiver(&mut self, id: SignalId) {
-        for row in &mut self.grid {
-            for sq in row {
-                match &mut sq.square_type {
-                    SquareType::Junction {
-                        id: jid,
-                        state,
-                        branches,
-                    } if *jid == id => {
-                        // Defensive: a Junction with no branches would
-                        // panic on modulo-by-zero. The editor should
-                        // reject such squares, but a hand-edited FEN can
-                        // sneak one in — log and skip.
-                        if branches.is_empty() {
-                            warn!(id, "junction has no branches; signal ignored");
-                        } else {
-                            // Compute in `usize` and cast back; using
-                            // `branches.len() as u8` for the modulus
-                            // would wrap to `len % 256` for >255-branch
-                            // junctions and could even produce a zero
-                            // modulus (panic in debug). The cast at the
-                            // end is bounded by `branches.len()` which
-                            // we keep ≤ 255 (see fen.rs parse).
-                            let next = ((*state as usize).wrapping_add(1)) % branches.len();
-                            let new_state = next as u8;
-                            trace!(
-                                id,
-                                old = *state,
-                                new = new_state,
-                                "junction advanced"
-                            );
-                            *state = new_state;
-                        }
-                    }
-                    SquareType::Gate { id: gid, open } if *gid == id => {
-                        trace!(id, was_open = *open, "gate toggled");
-                        *open = !*open;
+        let receives = |sq: &Square| match &sq.square_type {
+            SquareType::Junction { id: jid, .. } | SquareType::Gate { id: jid, .. } => *jid == id,
+            _ => false,
+        };
+        self.grid.edit_matching(receives, |sq| {
+            match &mut sq.square_type {
+                SquareType::Junction {
+                    id: jid,
+                    state,
+                    branches,
+                } if *jid == id => {
+                    // Defensive: a Junction with no branches would
+                    // panic on modulo-by-zero. The editor should
+                    // reject such squares, but a hand-edited FEN can
+                    // sneak one in — log and skip.
+                    if branches.is_empty() {
+                        warn!(id, "junction has no branches; signal ignored");
+                    } else {
+                        // Compute in `usize` and cast back; using
+                        // `branches.len() as u8` for the modulus
+                        // would wrap to `len % 256` for >255-branch
+                        // junctions and could even produce a zero
+                        // modulus (panic in debug). The cast at the
+                        // end is bounded by `branches.len()` which
+                        // we keep ≤ 255 (see fen.rs parse).
+                        let next = ((*state as usize).wrapping_add(1)) % branches.len();
+                        let new_state = next as u8;
+                        trace!(
+                            id,
+                            old = *state,
+                            new = new_state,
+                            "junction advanced"
+                        );
+                        *state = new_state;
                     }
-                    _ => {}
                 }
+                SquareType::Gate { id: gid, open } if *gid == id => {
+                    trace!(id, was_open = *open, "gate toggled");
+                    *open = !*open;
+                }
+                _ => {}
             }
-        }
+        });
     }
 }
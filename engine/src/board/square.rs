@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 
 use crate::board::SignalId;
 use crate::pieces::{Color, piecetype::PieceType};
@@ -8,11 +9,20 @@ use crate::pieces::{Color, piecetype::PieceType};
 pub struct Square {
     pub piece: Option<PieceType>,
     pub square_type: SquareType,
-    pub conditions: Vec<SquareCondition>,
+    pub conditions: Conditions,
+    /// Plan 11: the Duck Chess duck stands here. Not a condition —
+    /// conditions are terrain that stays put, the duck moves every
+    /// turn. Never set together with `piece`.
+    pub duck: bool,
 }
 
+/// A square's conditions. Inline up to two — a square rarely carries
+/// more than Brainrot and a tornado — so a board clone doesn't
+/// allocate for them. Serialised as a plain list.
+pub type Conditions = SmallVec<[SquareCondition; 2]>;
+
 /// ------------- Square types -------------
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize, Deserialize)]
 pub enum SquareType {
     Standard,
     Turret,
@@ -45,6 +55,10 @@ pub enum SquareType {
     /// between adjacent Track tiles. Non-train pieces can also walk over
     /// Track squares — they're walkable like Standard.
     Track { direction: TrackDir },
+    /// A King of the Hill goal square: a king standing here wins under
+    /// `WinConditionId::KingOfTheHill`. Plain floor otherwise. A board
+    /// with no `Hill` squares uses the centre (see `win::hill_squares`).
+    Hill,
 }
 
 /// Cardinal direction for tracks and junction branches. Diagonals are
@@ -104,7 +118,7 @@ impl TrackDir {
 }
 
 /// What triggers a `PressurePlate` to fire when a piece settles on it.
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize, Deserialize)]
 pub enum PressureTrigger {
     AnyPiece,
     OnlyColor(Color),
@@ -125,6 +139,7 @@ impl SquareType {
             SquareType::Gate { .. } => "GATE",
             SquareType::PressurePlate { .. } => "PLATE",
             SquareType::Track { .. } => "TRACK",
+            SquareType::Hill => "HILL",
         }
     }
 
@@ -139,16 +154,25 @@ impl SquareType {
             | SquareType::Switch { .. }
             | SquareType::Junction { .. }
             | SquareType::PressurePlate { .. }
-            | SquareType::Track { .. } => true,
+            | SquareType::Track { .. }
+            | SquareType::Hill => true,
             SquareType::Gate { open, .. } => *open,
             SquareType::Turret | SquareType::Vent | SquareType::Block => false,
         }
     }
+
+    /// Can the Duck Chess duck stand on this terrain? Any walkable
+    /// square except the rails (Track, Junction), so a train never
+    /// runs into the duck.
+    pub fn holds_duck(&self) -> bool {
+        self.is_walkable()
+            && !matches!(self, SquareType::Track { .. } | SquareType::Junction { .. })
+    }
 }
 /// ------------- End Square types -------------
 
 /// ------------- Square conditions -------------
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize, Deserialize)]
 pub enum SquareCondition {
     Frozen,
     Brainrot,
@@ -194,7 +218,8 @@ impl Square {
         Self {
             piece: None,
             square_type: SquareType::Standard,
-            conditions: vec![],
+            conditions: Conditions::new(),
+            duck: false,
         }
     }
     pub fn set_piece(mut self, piece: PieceType) -> Self {
@@ -214,6 +239,19 @@ impl Square {
         self
     }
 
+    /// Can a piece land on or slide through this square? Walkable
+    /// terrain without the duck on it. Move generators use this rather
+    /// than `SquareType::is_walkable` so the duck blocks like a wall.
+    pub fn is_walkable(&self) -> bool {
+        self.square_type.is_walkable() && !self.duck
+    }
+
+    /// Can the duck be placed or moved here? Terrain that holds it and
+    /// nothing standing on it yet.
+    pub fn can_take_duck(&self) -> bool {
+        self.piece.is_none() && !self.duck && self.square_type.holds_duck()
+    }
+
     pub fn has_piece(&self) -> bool {
         self.piece.is_some()
     }
@@ -2,10 +2,11 @@
 mod tests {
     use crate::{
         board::{
-            Board, BoardFlags, CastleSide, Coord, GameMove, GameStatus, MoveError, MoveType,
-            PromotionTarget,
+            Board, BoardFlags, CastleSide, Coord, DrawReason, DuckPhase, GameMove, GameStatus,
+            MoveError, MoveType, PromotionTarget, VariantId,
             fen::{board_to_fen, fen_to_board},
             square::{Square, SquareCondition, SquareType},
+            win::{WinConditionId, WinReason, hill_squares},
         },
         pieces::{
             Color,
@@ -21,41 +22,65 @@ mod tests {
 
     /// Helper: blank 8×8 board with default flags.
     fn empty_board() -> Board {
-        Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     #[test]
     fn test_empty_board_fen() {
-        let board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         let fen = board_to_fen(&board);
-        assert_eq!(fen, "8/8/8/8/8/8/8/8 w KQkq - tr=full p=0");
+        assert_eq!(fen, "8/8/8/8/8/8/8/8 w KQkq - 0 1 tr=full p=0");
 
         let board2 = fen_to_board(&fen).unwrap();
         assert_eq!(board2, board);
@@ -63,26 +88,38 @@ mod tests {
 
     #[test]
     fn test_standard_pieces_fen() {
-        let mut board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let mut board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         board.grid[0][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
         board.grid[7][7] = Square::new().set_piece(PieceType::new_king(Color::Black));
 
         let fen = board_to_fen(&board);
-        assert_eq!(fen, "R7/8/8/8/8/8/8/7k w KQkq - tr=full p=0");
+        assert_eq!(fen, "R7/8/8/8/8/8/8/7k w KQkq - 0 1 tr=full p=0");
 
         let board2 = fen_to_board(&fen).unwrap();
         assert_eq!(board2, board);
@@ -90,20 +127,32 @@ mod tests {
 
     #[test]
     fn test_extended_square_fen() {
-        let mut board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let mut board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         // Place a white rook on a vent square
         board.grid[0][0] = Square::new()
@@ -111,7 +160,7 @@ mod tests {
             .set_square_type(SquareType::Vent);
 
         let fen = board_to_fen(&board);
-        assert_eq!(fen, "(P=R,T=VENT)7/8/8/8/8/8/8/8 w KQkq - tr=full p=0");
+        assert_eq!(fen, "(P=R,T=VENT)7/8/8/8/8/8/8/8 w KQkq - 0 1 tr=full p=0");
 
         let board2 = fen_to_board(&fen).unwrap();
         assert_eq!(board2, board);
@@ -119,27 +168,39 @@ mod tests {
 
     #[test]
     fn test_square_with_conditions_fen() {
-        let mut board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let mut board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         board.grid[1][1] = Square::new()
             .set_piece(PieceType::new_knight(Color::Black))
             .add_square_condition(SquareCondition::Frozen);
 
         let fen = board_to_fen(&board);
-        assert_eq!(fen, "8/1(P=n,C=FROZEN)6/8/8/8/8/8/8 w KQkq - tr=full p=0");
+        assert_eq!(fen, "8/1(P=n,C=FROZEN)6/8/8/8/8/8/8 w KQkq - 0 1 tr=full p=0");
 
         let board2 = fen_to_board(&fen).unwrap();
         assert_eq!(board2, board);
@@ -147,20 +208,32 @@ mod tests {
 
     #[test]
     fn test_square_with_conditions_and_types_fen() {
-        let mut board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let mut board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         board.grid[1][1] = Square::new()
             .set_piece(PieceType::new_knight(Color::Black))
@@ -168,7 +241,7 @@ mod tests {
             .set_square_type(SquareType::Vent);
 
         let fen = board_to_fen(&board);
-        assert_eq!(fen, "8/1(P=n,T=VENT,C=FROZEN)6/8/8/8/8/8/8 w KQkq - tr=full p=0");
+        assert_eq!(fen, "8/1(P=n,T=VENT,C=FROZEN)6/8/8/8/8/8/8 w KQkq - 0 1 tr=full p=0");
 
         let board2 = fen_to_board(&fen).unwrap();
         assert_eq!(board2, board);
@@ -178,20 +251,32 @@ mod tests {
     /// `:<remaining>` payload and round-trips byte-identically.
     #[test]
     fn test_tornado_fen_roundtrip() {
-        let mut board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let mut board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         board.grid[1][1] = Square::new()
             .set_piece(PieceType::new_knight(Color::Black))
@@ -200,7 +285,7 @@ mod tests {
         let fen = board_to_fen(&board);
         assert_eq!(
             fen,
-            "8/1(P=n,C=TORNADO:3)6/8/8/8/8/8/8 w KQkq - tr=full p=0"
+            "8/1(P=n,C=TORNADO:3)6/8/8/8/8/8/8 w KQkq - 0 1 tr=full p=0"
         );
 
         let board2 = fen_to_board(&fen).unwrap();
@@ -214,8 +299,8 @@ mod tests {
     fn test_tornado_fen_bare_defaults_to_3() {
         let board = fen_to_board("(C=TORNADO)7/8/8/8/8/8/8/8 w KQkq -").unwrap();
         assert_eq!(
-            board.grid[0][0].conditions,
-            vec![SquareCondition::Tornado { remaining: 3 }]
+            board.grid[0][0].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 3 }]
         );
         // Re-serializing canonicalizes to the explicit-suffix form.
         assert!(board_to_fen(&board).contains("C=TORNADO:3"));
@@ -227,8 +312,8 @@ mod tests {
     fn test_tornado_fen_zero_clamps_to_1() {
         let board = fen_to_board("(C=TORNADO:0)7/8/8/8/8/8/8/8 w KQkq -").unwrap();
         assert_eq!(
-            board.grid[0][0].conditions,
-            vec![SquareCondition::Tornado { remaining: 1 }]
+            board.grid[0][0].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 1 }]
         );
     }
 
@@ -238,27 +323,39 @@ mod tests {
     fn test_tornado_fen_garbage_suffix_defaults_to_3() {
         let board = fen_to_board("(C=TORNADO:abc)7/8/8/8/8/8/8/8 w KQkq -").unwrap();
         assert_eq!(
-            board.grid[0][0].conditions,
-            vec![SquareCondition::Tornado { remaining: 3 }]
+            board.grid[0][0].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 3 }]
         );
     }
 
     #[test]
     fn test_fen_roundtrip() {
-        let mut board = Board {
-            grid: vec![vec![Square::new(); 8]; 8],
-            flags: BoardFlags {
+        let mut board = Board::new(
+            vec![vec![Square::new(); 8]; 8].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: true,
                 white_can_castle_queenside: true,
                 black_can_castle_kingside: true,
                 black_can_castle_queenside: true,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
 
         // Mix of standard and extended squares
         board.grid[0][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
@@ -630,7 +727,7 @@ mod tests {
         let mut board = empty_board();
         let bus_with_pawn = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![PieceType::new_pawn(Color::White)],
+            pieces: vec![PieceType::new_pawn(Color::White)].into(),
         });
         board.grid[3][3] = Square::new()
             .set_piece(bus_with_pawn)
@@ -1236,7 +1333,7 @@ mod tests {
         // asks for its moves — white pawn at (3,6) can single-push to (3,5).
         let bus_with_pawn = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![PieceType::new_pawn(Color::White)],
+            pieces: vec![PieceType::new_pawn(Color::White)].into(),
         });
         board.grid[6][3] = Square::new().set_piece(bus_with_pawn);
         // Plate at (3,5) wired to junction id=1.
@@ -1400,11 +1497,11 @@ mod tests {
         let mut board = empty_board();
         let bus_with_king = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![PieceType::new_king(Color::White)],
+            pieces: vec![PieceType::new_king(Color::White)].into(),
         });
         let empty_bus = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![],
+            pieces: vec![].into(),
         });
         board.grid[3][3] = Square::new().set_piece(bus_with_king);
         board.grid[3][4] = Square::new().set_piece(empty_bus);
@@ -1425,12 +1522,35 @@ mod tests {
         );
     }
 
+    /// Cloning a loaded carrier shares its passenger list until one
+    /// copy writes to it, and the list serialises as a plain array.
+    #[test]
+    fn test_passengers_are_copy_on_write() {
+        let pawn = PieceType::new_pawn(Color::White);
+        let bus = Bus {
+            color: Color::White,
+            pieces: vec![pawn.clone()].into(),
+        };
+        let mut copy = bus.clone();
+        assert!(std::ptr::eq(bus.pieces.as_slice(), copy.pieces.as_slice()));
+        copy.pieces.to_mut().push(PieceType::new_knight(Color::White));
+        assert_eq!(bus.pieces.len(), 1);
+        assert_eq!(copy.pieces.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&bus.pieces).unwrap(),
+            serde_json::to_value(vec![pawn]).unwrap()
+        );
+        let mut emptied = bus.clone();
+        emptied.pieces.to_mut().clear();
+        assert_eq!(emptied, Bus::new(Color::White));
+    }
+
     /// Doc says the Bus moves like a rook: orthogonal sliding. The previous
     /// code declared 8 directions with range 1.
     #[test]
     fn test_bus_moves_like_rook() {
         let mut board = empty_board();
-        let bus = PieceType::Bus(Bus { color: Color::White, pieces: vec![] });
+        let bus = PieceType::Bus(Bus { color: Color::White, pieces: vec![].into() });
         board.grid[3][3] = Square::new().set_piece(bus);
 
         let from = Coord { file: 3, rank: 3 };
@@ -1896,7 +2016,7 @@ mod tests {
             pieces: vec![
                 PieceType::new_pawn(Color::White),
                 PieceType::new_knight(Color::Black),
-            ],
+            ].into(),
         }));
 
         let fen = board_to_fen(&board);
@@ -1914,7 +2034,7 @@ mod tests {
         let pawn = PieceType::new_pawn(Color::White);
         let full_bus = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![pawn.clone(), pawn.clone(), pawn.clone(), pawn.clone(), pawn.clone()],
+            pieces: vec![pawn.clone(), pawn.clone(), pawn.clone(), pawn.clone(), pawn.clone()].into(),
         });
         board.grid[3][3] = Square::new().set_piece(full_bus);
         // Knight at (1,2) can L-move to (3,3) — and to other squares.
@@ -1961,7 +2081,7 @@ mod tests {
             pieces: vec![PieceType::Skibidi(Skibidi {
                 color: Color::White,
                 phase: 1,
-            })],
+            })].into(),
         }));
 
         // Should not panic.
@@ -1986,11 +2106,11 @@ mod tests {
         let mut board = empty_board();
         board.grid[3][3] = Square::new().set_piece(PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![],
+            pieces: vec![].into(),
         }));
         board.grid[3][4] = Square::new().set_piece(PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![],
+            pieces: vec![].into(),
         }));
 
         let moves = board.get_moves(&Coord { file: 3, rank: 3 });
@@ -2013,13 +2133,13 @@ mod tests {
             color: Color::White,
             pieces: vec![PieceType::Bus(Bus {
                 color: Color::White,
-                pieces: vec![],
-            })],
+                pieces: vec![].into(),
+            })].into(),
         }));
         // A friendly Bus to "land" the passenger in.
         board.grid[3][4] = Square::new().set_piece(PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![],
+            pieces: vec![].into(),
         }));
 
         // Should not panic, and no PieceInCarrier{MoveIntoCarrier} move
@@ -2672,7 +2792,7 @@ mod tests {
         let mut board = empty_board();
         let bus_with_king = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![PieceType::new_king(Color::White)],
+            pieces: vec![PieceType::new_king(Color::White)].into(),
         });
         board.grid[4][4] = Square::new().set_piece(bus_with_king);
 
@@ -2690,7 +2810,7 @@ mod tests {
         let mut board = empty_board();
         let bus_with_king = PieceType::Bus(Bus {
             color: Color::White,
-            pieces: vec![PieceType::new_king(Color::White)],
+            pieces: vec![PieceType::new_king(Color::White)].into(),
         });
         board.grid[4][4] = Square::new().set_piece(bus_with_king);
         // Black rook sweeping the Bus along rank 4.
@@ -2939,20 +3059,32 @@ mod tests {
     /// `height` ranks, all castling flags off (a non-standard board
     /// shouldn't claim default castle rights).
     fn empty_board_sized(width: usize, height: usize) -> Board {
-        Board {
-            grid: vec![vec![Square::new(); width]; height],
-            flags: BoardFlags {
+        Board::new(
+            vec![vec![Square::new(); width]; height].into(),
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     /// `Board::height` / `width` should reflect the underlying grid
@@ -2977,7 +3109,7 @@ mod tests {
     fn test_fen_multi_digit_run_length_roundtrip() {
         let board = empty_board_sized(10, 10);
         let fen = board_to_fen(&board);
-        assert_eq!(fen, "10/10/10/10/10/10/10/10/10/10 w - - tr=full p=0");
+        assert_eq!(fen, "10/10/10/10/10/10/10/10/10/10 w - - 0 1 tr=full p=0");
         let parsed = fen_to_board(&fen).unwrap();
         assert_eq!(parsed.width(), 10);
         assert_eq!(parsed.height(), 10);
@@ -3074,22 +3206,45 @@ mod tests {
         );
     }
 
-    /// On a board narrower than 8 there is no room for the standard
-    /// kingside-castle geometry, so castling moves are not generated
-    /// even when the flag is set.
+    /// Castling isn't tied to 8 files: on a 6-wide board the kingside
+    /// castle still ends with the king on the next-to-last file and the
+    /// rook just inside it.
     #[test]
-    fn test_castle_not_offered_on_narrow_board() {
+    fn test_castle_on_narrow_board() {
         let mut board = empty_board_sized(6, 8);
         board.flags.white_can_castle_kingside = true;
-        // King at file 4, rank 7 (white back rank); rook at file 5 — the
-        // only candidate kingside rook on a 6-wide board.
-        board.grid[7][4] = Square::new().set_piece(PieceType::new_king(Color::White));
+        board.grid[7][2] = Square::new().set_piece(PieceType::new_king(Color::White));
         board.grid[7][5] = Square::new().set_piece(PieceType::new_rook(Color::White));
 
-        let moves = board.get_moves(&Coord { file: 4, rank: 7 });
+        board
+            .make_move(GameMove {
+                from: Coord { file: 2, rank: 7 },
+                move_type: MoveType::Castle { side: CastleSide::Kingside },
+            })
+            .expect("kingside castle on a 6-wide board should succeed");
+        assert!(matches!(
+            &board.grid[7][4].piece,
+            Some(PieceType::King(k)) if k.color == Color::White
+        ));
+        assert!(matches!(
+            &board.grid[7][3].piece,
+            Some(PieceType::Rook(r)) if r.color == Color::White
+        ));
+    }
+
+    /// A board too narrow for the rook's queenside destination (the
+    /// d-file) doesn't get a queenside castle, even with the flag set.
+    #[test]
+    fn test_castle_not_offered_when_it_does_not_fit() {
+        let mut board = empty_board_sized(3, 8);
+        board.flags.white_can_castle_queenside = true;
+        board.grid[7][1] = Square::new().set_piece(PieceType::new_king(Color::White));
+        board.grid[7][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
+
+        let moves = board.get_moves(&Coord { file: 1, rank: 7 });
         assert!(
             !moves.iter().any(|m| matches!(m.move_type, MoveType::Castle { .. })),
-            "no castle move should be generated on a 6-wide board, got: {moves:?}"
+            "no castle move should be generated on a 3-wide board, got: {moves:?}"
         );
     }
 
@@ -3114,8 +3269,8 @@ mod tests {
             "kingside castle should be generated when rook sits at width-1 on a 10-wide board, got: {moves:?}"
         );
 
-        // Apply it; king should end at file 6, rook at file 5 (per the
-        // standard target geometry — the rook *source* was file 9).
+        // Apply it; king should end at file 8, rook at file 7 — the
+        // g- and f-file squares counted from the right edge.
         board
             .make_move(GameMove {
                 from: Coord { file: 4, rank: 7 },
@@ -3123,11 +3278,11 @@ mod tests {
             })
             .expect("kingside castle on wide board should succeed");
         assert!(matches!(
-            &board.grid[7][6].piece,
+            &board.grid[7][8].piece,
             Some(PieceType::King(k)) if k.color == Color::White
         ));
         assert!(matches!(
-            &board.grid[7][5].piece,
+            &board.grid[7][7].piece,
             Some(PieceType::Rook(r)) if r.color == Color::White
         ));
         assert!(board.grid[7][9].piece.is_none(), "rook source file 9 should now be empty");
@@ -3812,7 +3967,7 @@ mod tests {
             other => panic!(
                 "loco should curve south through the L and end at (3,5); got {other:?} \
                  at (5,3); full row3={:?} col3@4={:?} col3@5={:?}",
-                board.grid[3], board.grid[4][3], board.grid[5][3]
+                &board.grid[3], board.grid[4][3], board.grid[5][3]
             ),
         }
     }
@@ -3904,7 +4059,7 @@ mod tests {
             .set_piece(PieceType::Carriage(Carriage {
                 train_id: 1,
                 chain_index: 1,
-                passengers: vec![PieceType::new_pawn(Color::Black)],
+                passengers: vec![PieceType::new_pawn(Color::Black)].into(),
             }));
         // White knight at file=4, rank=5 — one knight-move away from
         // (file=3, rank=3) via Δfile=-1, Δrank=-2.
@@ -3967,7 +4122,7 @@ mod tests {
             .set_piece(PieceType::Carriage(Carriage {
                 train_id: 1,
                 chain_index: 1,
-                passengers: vec![PieceType::new_king(Color::Black)],
+                passengers: vec![PieceType::new_king(Color::Black)].into(),
             }));
         // Distant white king so the position is legal.
         board.grid[7][0] = Square::new().set_piece(PieceType::new_king(Color::White));
@@ -4085,7 +4240,7 @@ mod tests {
             .set_piece(PieceType::Carriage(Carriage {
                 train_id: 1,
                 chain_index: 1,
-                passengers: vec![PieceType::new_king(Color::Black)],
+                passengers: vec![PieceType::new_king(Color::Black)].into(),
             }));
         board.grid[7][0] = Square::new().set_piece(PieceType::new_king(Color::White));
         // White's turn (default).
@@ -4576,7 +4731,7 @@ mod tests {
         let loco = Locomotive {
             train_id: 5,
             heading: TrainHeading::Reverse,
-            passengers: vec![PieceType::new_king(Color::Black)],
+            passengers: vec![PieceType::new_king(Color::Black)].into(),
             last_dir: Some(TrackDir::N),
         };
         let sym = crate::pieces::Piece::symbol(&loco);
@@ -4589,7 +4744,7 @@ mod tests {
         let cart = Carriage {
             train_id: 5,
             chain_index: 2,
-            passengers: vec![PieceType::new_pawn(Color::White)],
+            passengers: vec![PieceType::new_pawn(Color::White)].into(),
         };
         let sym = crate::pieces::Piece::symbol(&cart);
         assert_eq!(sym, "CART(ID=5,I=2,P=(P))");
@@ -4821,7 +4976,7 @@ mod tests {
             .set_piece(PieceType::Carriage(Carriage {
                 train_id: 1,
                 chain_index: 1,
-                passengers: vec![PieceType::new_king(Color::Black)],
+                passengers: vec![PieceType::new_king(Color::Black)].into(),
             }));
         // White knight a knight-move away.
         board.grid[5][4] = Square::new().set_piece(PieceType::new_knight(Color::White));
@@ -5120,7 +5275,7 @@ mod tests {
             .set_piece(PieceType::Carriage(Carriage {
                 train_id: 1,
                 chain_index: 1,
-                passengers: vec![PieceType::new_pawn(Color::White)],
+                passengers: vec![PieceType::new_pawn(Color::White)].into(),
             }));
         board.grid[3][4] = Square::new()
             .set_square_type(SquareType::Track {
@@ -5129,7 +5284,7 @@ mod tests {
             .set_piece(PieceType::Carriage(Carriage {
                 train_id: 2,
                 chain_index: 1,
-                passengers: vec![PieceType::new_knight(Color::Black)],
+                passengers: vec![PieceType::new_knight(Color::Black)].into(),
             }));
         board.grid[7][0] = Square::new().set_piece(PieceType::new_king(Color::White));
         board.grid[0][7] = Square::new().set_piece(PieceType::new_king(Color::Black));
@@ -5343,7 +5498,7 @@ mod tests {
         board.grid[3][3].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 2,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::E),
         }));
 
@@ -5380,7 +5535,7 @@ mod tests {
         board.flags.side_to_move = Color::Black;
         // Neutral loco at (3, 4), carrying a Black pawn passenger.
         let mut loco = Locomotive::new(7, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::new_pawn(Color::Black)];
+        loco.passengers = vec![PieceType::new_pawn(Color::Black)].into();
         board.grid[4][3].piece = Some(PieceType::Locomotive(loco));
         // Black king at (4, 3) — diagonally one tile from the cart so
         // a Black pawn's attack diagonal could "hit" it.
@@ -5443,7 +5598,7 @@ mod tests {
         board.grid[3][4].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 1,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::W),
         }));
         board.grid[3][3].piece = Some(PieceType::Carriage(Carriage::new(1, 1)));
@@ -5452,7 +5607,7 @@ mod tests {
         board.grid[3][2].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 2,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::W),
         }));
         // Kings somewhere safe.
@@ -5492,7 +5647,7 @@ mod tests {
             },
         };
         let mut bus = Bus::new(Color::White);
-        bus.pieces = vec![PieceType::Goblin(goblin)];
+        bus.pieces = vec![PieceType::Goblin(goblin)].into();
         board.grid[0][3].piece = Some(PieceType::Bus(bus));
         // Kings so the board is well-formed.
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
@@ -5591,7 +5746,7 @@ mod tests {
         board.grid[3][4].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 1,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::W),
         }));
         board.grid[3][3].piece = Some(PieceType::Carriage(Carriage::new(1, 1)));
@@ -5600,14 +5755,14 @@ mod tests {
         board.grid[3][6].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 3,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::E),
         }));
         // Train A (trailing): loco at (2,3) wants (3,3).
         board.grid[3][2].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 2,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::W),
         }));
         // Kings somewhere safe.
@@ -5644,7 +5799,7 @@ mod tests {
         let mut skib = Skibidi::new(Color::White);
         skib.phase = 3;
         let mut bus_a = Bus::new(Color::White);
-        bus_a.pieces = vec![PieceType::Skibidi(skib)];
+        bus_a.pieces = vec![PieceType::Skibidi(skib)].into();
         board.grid[0][3].piece = Some(PieceType::Bus(bus_a));
         board.grid[0][4].piece = Some(PieceType::Bus(Bus::new(Color::White)));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
@@ -5746,14 +5901,14 @@ mod tests {
         board.grid[3][4].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 1,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::W),
         }));
         // Train C at (5,3) heading west, last_dir=E → next would be (4,3).
         board.grid[3][5].piece = Some(PieceType::Locomotive(Locomotive {
             train_id: 2,
             heading: TrainHeading::Forward,
-            passengers: vec![],
+            passengers: vec![].into(),
             last_dir: Some(crate::board::square::TrackDir::E),
         }));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
@@ -5789,7 +5944,7 @@ mod tests {
         // White Bus at (e7) carrying a White pawn. Pawn at the carrier's
         // tile sees rank 7 immediately above and would emit Promotion.
         let mut bus = Bus::new(Color::White);
-        bus.pieces = vec![PieceType::new_pawn(Color::White)];
+        bus.pieces = vec![PieceType::new_pawn(Color::White)].into();
         // (file=4, rank=6) — rank 7 is one step north (promotion rank).
         board.grid[6][4].piece = Some(PieceType::Bus(bus));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
@@ -5835,7 +5990,7 @@ mod tests {
             state: GoblinState::Kidnapping { piece: kidnapped },
         };
         let mut cart = Locomotive::new(99, TrainHeading::Forward);
-        cart.passengers = vec![PieceType::Goblin(goblin)];
+        cart.passengers = vec![PieceType::Goblin(goblin)].into();
         board.grid[6][5].piece = Some(PieceType::Locomotive(cart));
 
         // f8 = (5, 7), g8 = (6, 7) — must NOT register as attacked by White.
@@ -5867,7 +6022,7 @@ mod tests {
         // would have no top-level pieces and `status()` would
         // mis-declare Stalemate.
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::new_king(Color::Black)];
+        loco.passengers = vec![PieceType::new_king(Color::Black)].into();
         board.grid[4][4].piece = Some(PieceType::Locomotive(loco));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
 
@@ -5896,7 +6051,7 @@ mod tests {
         let mut skib = Skibidi::new(Color::White);
         skib.phase = 2;
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::Skibidi(skib)];
+        loco.passengers = vec![PieceType::Skibidi(skib)].into();
         board.grid[4][4].piece = Some(PieceType::Locomotive(loco));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
         board.grid[7][7].piece = Some(PieceType::new_king(Color::Black));
@@ -5922,7 +6077,7 @@ mod tests {
         let mut skib = Skibidi::new(Color::White);
         skib.phase = 2;
         let mut bus = Bus::new(Color::White);
-        bus.pieces = vec![PieceType::Skibidi(skib)];
+        bus.pieces = vec![PieceType::Skibidi(skib)].into();
         board.grid[0][4].piece = Some(PieceType::Bus(bus));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
         board.grid[7][7].piece = Some(PieceType::new_king(Color::Black));
@@ -6056,7 +6211,7 @@ mod tests {
         board.grid[3][3].piece = Some(PieceType::Monkey(Monkey { color: Color::White }));
         board.grid[4][4].piece = Some(PieceType::new_pawn(Color::Black));
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::new_king(Color::Black)];
+        loco.passengers = vec![PieceType::new_king(Color::Black)].into();
         board.grid[5][5].piece = Some(PieceType::Locomotive(loco));
         // White king somewhere safe.
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
@@ -6116,7 +6271,7 @@ mod tests {
         // Black king passenger.
         board.grid[4][4].piece = Some(PieceType::Skibidi(Skibidi::new(Color::White)));
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::new_king(Color::Black)];
+        loco.passengers = vec![PieceType::new_king(Color::Black)].into();
         board.grid[5][5].piece = Some(PieceType::Locomotive(loco));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
 
@@ -6144,7 +6299,7 @@ mod tests {
         // those squares should NOT be flagged — the Bus is White, and
         // a hand-rolled mismatch must not leak threats for Black.
         let mut bus = Bus::new(Color::White);
-        bus.pieces = vec![PieceType::new_knight(Color::Black)];
+        bus.pieces = vec![PieceType::new_knight(Color::Black)].into();
         board.grid[4][4].piece = Some(PieceType::Bus(bus));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
         board.grid[7][7].piece = Some(PieceType::new_king(Color::Black));
@@ -6218,7 +6373,7 @@ mod tests {
         // doesn't rewrite to MoveIntoCarrier — the MoveTo proceeds as
         // a capture.
         let mut bus = Bus::new(Color::White);
-        bus.pieces = vec![PieceType::new_king(Color::White)];
+        bus.pieces = vec![PieceType::new_king(Color::White)].into();
         board.grid[5][5].piece = Some(PieceType::Bus(bus));
         let goblin = Goblin {
             color: Color::Black,
@@ -6270,7 +6425,7 @@ mod tests {
         // pawn is at its starting rank (rank=1 for White) so a
         // double-push to (1, 3) is legal.
         let mut bus = Bus::new(Color::White);
-        bus.pieces = vec![PieceType::new_pawn(Color::White)];
+        bus.pieces = vec![PieceType::new_pawn(Color::White)].into();
         board.grid[1][1].piece = Some(PieceType::Bus(bus));
         board.grid[0][0].piece = Some(PieceType::new_king(Color::White));
         board.grid[7][7].piece = Some(PieceType::new_king(Color::Black));
@@ -6626,8 +6781,8 @@ mod tests {
             mover_color: Color::Black,
             from: Coord { file: 4, rank: 1 },
             to: Some(Coord { file: 4, rank: 3 }),
-            captured_symbol: Some("Q".to_string()),
-            primary_symbol: "p".to_string(),
+            captured_symbol: Some("Q".into()),
+            primary_symbol: "p".into(),
             kind: LastMoveKind::Move,
         });
         let fen = board_to_fen(&board);
@@ -6665,7 +6820,7 @@ mod tests {
                 from: Coord { file: 0, rank: 0 },
                 to: to.clone(),
                 captured_symbol: None,
-                primary_symbol: "K".to_string(),
+                primary_symbol: "K".into(),
                 kind: *kind,
             };
             board.flags.last_move = Some(original.clone());
@@ -6884,7 +7039,7 @@ mod tests {
             .set_piece(PieceType::Locomotive(Locomotive {
                 train_id: 1,
                 heading: TrainHeading::Forward,
-                passengers: vec![],
+                passengers: vec![].into(),
                 last_dir: Some(TrackDir::W),
             }));
         // White king on the phantom cold-start tile (where the buggy
@@ -6949,7 +7104,7 @@ mod tests {
             .set_piece(PieceType::Locomotive(Locomotive {
                 train_id: 1,
                 heading: TrainHeading::Forward,
-                passengers: vec![],
+                passengers: vec![].into(),
                 last_dir: Some(TrackDir::W),
             }));
         board.grid[3][3] = Square::new().set_square_type(SquareType::Track {
@@ -6966,7 +7121,7 @@ mod tests {
             .set_piece(PieceType::Locomotive(Locomotive {
                 train_id: 2,
                 heading: TrainHeading::Forward,
-                passengers: vec![],
+                passengers: vec![].into(),
                 last_dir: Some(TrackDir::E),
             }));
         board.grid[3][5] = Square::new().set_square_type(SquareType::Track {
@@ -7016,7 +7171,7 @@ mod tests {
         // White Bus at (3,3) carrying a white pawn passenger.
         let bus = Bus {
             color: Color::White,
-            pieces: vec![PieceType::new_pawn(Color::White)],
+            pieces: vec![PieceType::new_pawn(Color::White)].into(),
         };
         board.grid[3][3] = Square::new().set_piece(PieceType::Bus(bus));
         // Black Kidnapping Goblin at (4,2), holding a black knight.
@@ -7182,7 +7337,7 @@ mod tests {
         // Black Bus at (3,3) with no passengers.
         let bus = Bus {
             color: Color::Black,
-            pieces: vec![],
+            pieces: vec![].into(),
         };
         board.grid[3][3] = Square::new().set_piece(PieceType::Bus(bus));
         // White Knight at (4,5) — can L-hop to (3,3) to capture.
@@ -7262,7 +7417,7 @@ mod tests {
             .set_piece(PieceType::Locomotive(Locomotive {
                 train_id: 1,
                 heading: TrainHeading::Forward,
-                passengers: vec![PieceType::new_king(Color::Black)],
+                passengers: vec![PieceType::new_king(Color::Black)].into(),
                 last_dir: Some(TrackDir::W),
             }));
         // White king elsewhere.
@@ -7492,29 +7647,41 @@ mod tests {
     #[test]
     fn test_iter_pieces_yields_all_placed_pieces() {
         // Construct a 6×4 board directly (bypasses FEN).
-        let mut board = Board {
-            grid: (0..4)
+        let mut board = Board::new(
+            (0..4)
                 .map(|_| (0..6).map(|_| Square::new()).collect())
                 .collect(),
-            flags: BoardFlags {
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: crate::board::TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        };
+        );
         board.grid[0][0] = Square::new().set_piece(PieceType::new_king(Color::White));
         board.grid[2][3] = Square::new().set_piece(PieceType::new_rook(Color::Black));
         board.grid[3][5] = Square::new().set_piece(PieceType::new_knight(Color::White));
 
         let mut yielded: Vec<(Coord, String)> = board
             .iter_pieces()
-            .map(|(c, p)| (c, p.symbol()))
+            .map(|(c, p)| (c, p.symbol().into_owned()))
             .collect();
         yielded.sort_by_key(|(c, _)| (c.rank, c.file));
 
@@ -7586,7 +7753,7 @@ mod tests {
             .set_piece(PieceType::Locomotive(Locomotive {
                 train_id: 1,
                 heading: TrainHeading::Forward,
-                passengers: vec![],
+                passengers: vec![].into(),
                 last_dir: Some(TrackDir::W),
             }));
         board.grid[0][4] = Square::new().set_square_type(SquareType::Track {
@@ -7632,8 +7799,8 @@ mod tests {
     /// `last_move` it observes. Run a move and assert the probe saw
     /// the just-applied move, not the previous one.
     ///
-    /// We can't easily register a custom handler against the default
-    /// registry (it's a OnceLock), so the test is structural: after
+    /// We can't easily register a custom handler against the cached
+    /// per-ruleset registry, so the test is structural: after
     /// `make_move`, `board.flags.last_move` reflects the move just
     /// applied. PostMover's correctness depends on the SAME write
     /// happening before the PostMover phase fires, which is observable
@@ -7684,22 +7851,22 @@ mod tests {
             observed: observed.clone(),
         }));
 
-        // We can't intercept make_move's call to default_registry(),
-        // so simulate make_move's phase 1 + phase 2 manually, then
-        // call the pub(crate) `_with` variant of phase 3. This is
+        // We can't intercept make_move's lookup of the ruleset's
+        // registry, so simulate make_move's phase 1 + phase 2 manually,
+        // then call the pub(crate) `_with` variant of phase 3. This is
         // the same code path make_move uses, just with our custom
-        // registry instead of the OnceLock default.
-        let before = board.clone();
+        // registry instead of the cached one.
         let game_move = GameMove {
             from: Coord { file: 4, rank: 6 },
             move_type: MoveType::MoveTo(Coord { file: 4, rank: 5 }),
         };
+        let before = crate::board::make_move::PreMove::new(&board, &game_move);
         // Phase 1 (relocate) manually — move the pawn.
         board.grid[6][4].piece = None;
         board.grid[5][4].piece = Some(PieceType::new_pawn(Color::White));
         // Phase 3 (env reactions) with the custom registry.
         let ctx = crate::board::make_move::PostMoveCtx {
-            before_state: &before,
+            before: &before,
             game_move: &game_move,
         };
         board.apply_environment_reactions_with(&ctx, &reg);
@@ -7910,7 +8077,7 @@ mod tests {
         // Black bus at b2 (rank=6, file=1) carrying a black queen.
         let bus = PieceType::Bus(Bus {
             color: Color::Black,
-            pieces: vec![PieceType::new_queen(Color::Black)],
+            pieces: vec![PieceType::new_queen(Color::Black)].into(),
         });
         board.grid[6][1] = Square::new().set_piece(bus);
         // Kings for legality.
@@ -7946,7 +8113,7 @@ mod tests {
 
     /// R4 audit F1: a Kidnapping Goblin run over by a train must flow
     /// through the capture stack. Pre-fix, `advance_trains` bypassed
-    /// `default_capture_stack().resolve_capture(...)` entirely, so any
+    /// the capture stack's `resolve_capture(...)` entirely, so any
     /// future death-rattle modifier (Bomb, Antipode, Plague Doctor)
     /// would silently no-op on train captures. The current
     /// `GoblinDropVictimCapture` handler skips on `captor_origin =
@@ -8239,7 +8406,7 @@ mod tests {
             },
         });
         let mut bus = Bus::new(Color::Black);
-        bus.pieces = vec![kidnapping_goblin];
+        bus.pieces = vec![kidnapping_goblin].into();
         board.grid[3][3].piece = Some(PieceType::Bus(bus));
         // White Queen at (4,4) — diagonal-1 from the Bus.
         board.grid[4][4].piece = Some(PieceType::new_queen(Color::White));
@@ -8335,7 +8502,7 @@ mod tests {
             },
         });
         let mut bus = Bus::new(Color::Black);
-        bus.pieces = vec![kidnapping_goblin];
+        bus.pieces = vec![kidnapping_goblin].into();
         board.grid[3][2] = board.grid[3][2].clone().set_piece(PieceType::Bus(bus));
         // Idle-king pair to tick the train.
         board.grid[7][6].piece = Some(PieceType::new_king(Color::White));
@@ -8413,7 +8580,7 @@ mod tests {
             },
         });
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![kidnapping_goblin];
+        loco.passengers = vec![kidnapping_goblin].into();
         board.grid[3][3] = board.grid[3][3]
             .clone()
             .set_piece(PieceType::Locomotive(loco));
@@ -8747,7 +8914,7 @@ mod tests {
             phase: 2,
         });
         let mut bus = Bus::new(Color::Black);
-        bus.pieces = vec![skibidi_passenger];
+        bus.pieces = vec![skibidi_passenger].into();
         board.grid[3][3].piece = Some(PieceType::Bus(bus));
         // White Queen at (4,4) — captures the Bus diagonally.
         board.grid[4][4].piece = Some(PieceType::new_queen(Color::White));
@@ -8809,7 +8976,7 @@ mod tests {
         loco.passengers = vec![PieceType::Skibidi(Skibidi {
             color: Color::White,
             phase: 3,
-        })];
+        })].into();
         board.grid[3][3] = board.grid[3][3]
             .clone()
             .set_piece(PieceType::Locomotive(loco));
@@ -9001,7 +9168,7 @@ mod tests {
         // Neutral Locomotive on a closed Gate at (3,3) carrying a
         // White Queen (which would project queen-like threats).
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::new_queen(Color::White)];
+        loco.passengers = vec![PieceType::new_queen(Color::White)].into();
         board.grid[3][3] = Square::new()
             .set_square_type(SquareType::Gate { id: 1, open: false })
             .set_piece(PieceType::Locomotive(loco));
@@ -9150,7 +9317,7 @@ mod tests {
         // Same scenario as above but the carrier is on a Track tile
         // (walkable).
         let mut loco = Locomotive::new(1, TrainHeading::Forward);
-        loco.passengers = vec![PieceType::new_queen(Color::White)];
+        loco.passengers = vec![PieceType::new_queen(Color::White)].into();
         board.grid[3][3] = Square::new()
             .set_square_type(SquareType::Track {
                 direction: TrackDir::E,
@@ -9411,10 +9578,9 @@ mod tests {
             // Populated `flags.last_move` — the `BoardFlags` node that
             // ships in `/board/new_state` (capture-bearing `LastMove`,
             // exercising `LastMoveKind` + `Option<captured_symbol>`).
-            // The flag fields are POSITIONAL (`… ep tr p lm`); the
-            // `tr=full p=0` tokens are required so `lm=(…)` lands in
-            // the last-move slot and actually populates `last_move`
-            // (a non-vacuity guard below enforces this).
+            // The tail fields are classified by prefix, so `lm=(…)`
+            // populates `last_move` wherever it sits (a non-vacuity
+            // guard below enforces this).
             "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - tr=full p=0 lm=(C=B,F=4-1,K=MOVE,T=4-3,V=Q,P=p)",
         ];
         for fen in fens {
@@ -9433,8 +9599,8 @@ mod tests {
         // Non-vacuity guard: the 5th FEN must genuinely populate
         // `flags.last_move`, otherwise its `LastMove`/`LastMoveKind`
         // serde is never exercised and the round-trip above is a
-        // no-op for that node (positional flag tokens make this an
-        // easy, silent mistake).
+        // no-op for that node (a malformed `lm=` payload silently
+        // coerces to `None`, so this is an easy mistake to miss).
         let lm_board = fen_to_board(fens[4]).expect("last-move FEN parses");
         let lm = lm_board
             .flags
@@ -9465,6 +9631,14 @@ mod tests {
                 GameStatus::Checkmate { winner: Color::Black },
                 r#"{"status":"Checkmate","data":{"winner":"Black"}}"#,
             ),
+            (
+                GameStatus::Draw { reason: DrawReason::FiftyMove },
+                r#"{"status":"Draw","data":{"reason":"FiftyMove"}}"#,
+            ),
+            (
+                GameStatus::Draw { reason: DrawReason::ThreefoldRepetition },
+                r#"{"status":"Draw","data":{"reason":"ThreefoldRepetition"}}"#,
+            ),
         ];
         for (status, want) in cases {
             let json = serde_json::to_string(&status).unwrap();
@@ -9476,4 +9650,2458 @@ mod tests {
             );
         }
     }
+
+    // ---------------------------------------------------------------
+    // Halfmove clock, fullmove number, fifty-move draw
+    // ---------------------------------------------------------------
+
+    fn king_step(board: &mut Board, color: Color, df: i8) {
+        let k = board.find_king(color).expect("king present");
+        board
+            .make_move(GameMove {
+                from: k.clone(),
+                move_type: MoveType::MoveTo(Coord {
+                    file: (k.file as i8 + df) as u8,
+                    rank: k.rank,
+                }),
+            })
+            .expect("king step");
+    }
+
+    #[test]
+    fn test_quiet_moves_tick_clock_and_fullmove() {
+        let mut board = board_with_idle_kings();
+        king_step(&mut board, Color::White, 1);
+        assert_eq!(board.flags.halfmove_clock, 1);
+        assert_eq!(board.flags.fullmove_number, 1, "white's move keeps the fullmove");
+        king_step(&mut board, Color::Black, -1);
+        assert_eq!(board.flags.halfmove_clock, 2);
+        assert_eq!(board.flags.fullmove_number, 2, "black's move bumps the fullmove");
+    }
+
+    #[test]
+    fn test_pawn_move_resets_halfmove_clock() {
+        let mut board = board_with_idle_kings();
+        board.grid[6][4] = Square::new().set_piece(PieceType::new_pawn(Color::White));
+        board.flags.halfmove_clock = 37;
+        board
+            .make_move(GameMove {
+                from: Coord { file: 4, rank: 6 },
+                move_type: MoveType::MoveTo(Coord { file: 4, rank: 5 }),
+            })
+            .unwrap();
+        assert_eq!(board.flags.halfmove_clock, 0);
+    }
+
+    #[test]
+    fn test_capture_resets_halfmove_clock() {
+        let mut board = board_with_idle_kings();
+        board.grid[4][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        board.grid[4][5] = Square::new().set_piece(PieceType::new_knight(Color::Black));
+        board.flags.halfmove_clock = 37;
+        board
+            .make_move(GameMove {
+                from: Coord { file: 0, rank: 4 },
+                move_type: MoveType::MoveTo(Coord { file: 5, rank: 4 }),
+            })
+            .unwrap();
+        assert_eq!(board.flags.halfmove_clock, 0);
+    }
+
+    /// A train run-over is a capture nobody "made" — the clock still
+    /// resets because the piece census dropped during the env tick.
+    #[test]
+    fn test_train_run_over_resets_halfmove_clock() {
+        let mut board = board_with_idle_kings();
+        board.flags.train_tick_rate = TrainTickRate::EveryPly;
+        board.flags.halfmove_clock = 37;
+        lay_east_track(&mut board, 3, 1, 5);
+        board.grid[3][1] = board.grid[3][1]
+            .clone()
+            .set_piece(PieceType::Locomotive(Locomotive::new(
+                1,
+                TrainHeading::Forward,
+            )));
+        board.grid[3][2] = board.grid[3][2]
+            .clone()
+            .set_piece(PieceType::new_knight(Color::Black));
+        king_step(&mut board, Color::White, 1);
+        assert!(matches!(
+            board.grid[3][2].piece,
+            Some(PieceType::Locomotive(_))
+        ));
+        assert_eq!(board.flags.halfmove_clock, 0);
+    }
+
+    #[test]
+    fn test_fifty_move_draw_status() {
+        let mut board = board_with_idle_kings();
+        // Mating material, so the bare-kings dead position doesn't
+        // pre-empt the fifty-move draw.
+        board.grid[4][4] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        board.flags.halfmove_clock = 99;
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        king_step(&mut board, Color::White, 1);
+        assert_eq!(
+            board.status(),
+            GameStatus::Draw { reason: DrawReason::FiftyMove }
+        );
+    }
+
+    /// Mate on the hundredth ply is still mate.
+    #[test]
+    fn test_checkmate_outranks_fifty_move_draw() {
+        // Back-rank mate: black king h8 boxed by its own pawns, white
+        // rook on a8.
+        let mut board =
+            fen_to_board("R6k/6pp/8/8/8/8/8/7K b - - 100 80").unwrap();
+        assert_eq!(board.flags.halfmove_clock, 100);
+        assert_eq!(
+            board.status(),
+            GameStatus::Checkmate { winner: Color::White }
+        );
+        board.grid[0][0].piece = None;
+        board.grid[7][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        assert_eq!(
+            board.status(),
+            GameStatus::Draw { reason: DrawReason::FiftyMove }
+        );
+    }
+
+    #[test]
+    fn test_fen_halfmove_fullmove_roundtrip() {
+        let fen = "4k3/8/8/8/8/8/8/4K3 b - - 42 117 tr=ply p=9";
+        let board = fen_to_board(fen).unwrap();
+        assert_eq!(board.flags.halfmove_clock, 42);
+        assert_eq!(board.flags.fullmove_number, 117);
+        assert_eq!(board.flags.train_tick_rate, TrainTickRate::EveryPly);
+        assert_eq!(board.flags.ply_count, 9);
+        assert_eq!(board_to_fen(&board), fen);
+    }
+
+    /// Standard six-field FENs and the pre-clock engine form both parse.
+    #[test]
+    fn test_fen_tail_fields_classified_by_shape() {
+        let std6 = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 12 30").unwrap();
+        assert_eq!(std6.flags.halfmove_clock, 12);
+        assert_eq!(std6.flags.fullmove_number, 30);
+        assert_eq!(std6.flags.train_tick_rate, TrainTickRate::EveryFullTurn);
+        assert_eq!(std6.flags.ply_count, 0);
+
+        let legacy = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - tr=2ply p=5").unwrap();
+        assert_eq!(legacy.flags.halfmove_clock, 0);
+        assert_eq!(legacy.flags.fullmove_number, 1);
+        assert_eq!(legacy.flags.train_tick_rate, TrainTickRate::EveryNPly(2));
+        assert_eq!(legacy.flags.ply_count, 5);
+
+        let zero_fm = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 0").unwrap();
+        assert_eq!(zero_fm.flags.fullmove_number, 1, "fullmove 0 normalises to 1");
+    }
+
+    // ---------------------------------------------------------------
+    // Position hash, threefold repetition
+    // ---------------------------------------------------------------
+
+    /// The incrementally advanced hash must match a from-scratch
+    /// computation after every move — including plies where the train
+    /// ticks and a tornado counts down, which touch squares the mover
+    /// never did.
+    #[test]
+    fn test_incremental_hash_matches_full_recompute() {
+        let mut board = board_with_idle_kings();
+        lay_east_track(&mut board, 3, 1, 5);
+        board.grid[3][1] = board.grid[3][1]
+            .clone()
+            .set_piece(PieceType::Locomotive(Locomotive::new(
+                1,
+                TrainHeading::Forward,
+            )));
+        board.grid[5][5] = Square::new().add_square_condition(SquareCondition::Tornado { remaining: 3 });
+        for _ in 0..3 {
+            waste_full_turn(&mut board);
+            assert_eq!(
+                board.history.hash,
+                Some(board.compute_position_hash())
+            );
+        }
+        assert!(matches!(
+            board.grid[3][4].piece,
+            Some(PieceType::Locomotive(_))
+        ));
+    }
+
+    #[test]
+    fn test_threefold_repetition_draw() {
+        let mut board = board_with_idle_kings();
+        let start = board.position_hash();
+        for round in 1..=2 {
+            king_step(&mut board, Color::White, 1);
+            king_step(&mut board, Color::Black, -1);
+            king_step(&mut board, Color::White, -1);
+            king_step(&mut board, Color::Black, 1);
+            assert_eq!(board.position_hash(), start, "back to the start");
+            assert_eq!(board.repetition_count(), round + 1);
+        }
+        assert_eq!(
+            board.status(),
+            GameStatus::Draw { reason: DrawReason::ThreefoldRepetition }
+        );
+    }
+
+    /// An irreversible move wipes the history: nothing before it can
+    /// recur.
+    #[test]
+    fn test_pawn_move_clears_repetition_history() {
+        let mut board = board_with_idle_kings();
+        board.grid[6][4] = Square::new().set_piece(PieceType::new_pawn(Color::White));
+        king_step(&mut board, Color::White, 1);
+        king_step(&mut board, Color::Black, -1);
+        assert_eq!(board.history.seen.len(), 2);
+        board
+            .make_move(GameMove {
+                from: Coord { file: 4, rank: 6 },
+                move_type: MoveType::MoveTo(Coord { file: 4, rank: 5 }),
+            })
+            .unwrap();
+        assert!(board.history.seen.is_empty());
+        assert_eq!(board.repetition_count(), 1);
+    }
+
+    /// Payload state the FEN carries in parens is part of the position.
+    #[test]
+    fn test_hash_covers_piece_payloads_and_conditions() {
+        let base = board_with_idle_kings();
+        let hash_with = |edit: &dyn Fn(&mut Board)| {
+            let mut b = base.clone();
+            edit(&mut b);
+            b.position_hash()
+        };
+
+        let free = hash_with(&|b| {
+            b.grid[3][3] = Square::new().set_piece(PieceType::Goblin(Goblin::new(
+                Color::White,
+                Coord { file: 3, rank: 3 },
+            )));
+        });
+        let kidnapping = hash_with(&|b| {
+            let mut g = Goblin::new(Color::White, Coord { file: 3, rank: 3 });
+            g.state = GoblinState::Kidnapping {
+                piece: std::sync::Arc::new(PieceType::new_knight(Color::Black)),
+            };
+            b.grid[3][3] = Square::new().set_piece(PieceType::Goblin(g));
+        });
+        assert_ne!(free, kidnapping, "goblin payload");
+
+        let phase = |p: u8| {
+            hash_with(&|b| {
+                b.grid[3][3] = Square::new().set_piece(PieceType::Skibidi(Skibidi {
+                    color: Color::White,
+                    phase: p,
+                }));
+            })
+        };
+        assert_ne!(phase(1), phase(2), "skibidi phase");
+
+        let tornado = |n: u8| {
+            hash_with(&|b| {
+                b.grid[3][3] =
+                    Square::new().add_square_condition(SquareCondition::Tornado { remaining: n });
+            })
+        };
+        assert_ne!(tornado(2), tornado(3), "tornado countdown");
+    }
+
+    /// `ply_count` itself is not hashed, but where it sits in the train
+    /// tick cycle is.
+    #[test]
+    fn test_hash_covers_train_tick_phase_only() {
+        let mut a = board_with_idle_kings();
+        a.flags.train_tick_rate = TrainTickRate::EveryNPly(3);
+        a.flags.ply_count = 1;
+        let mut b = a.clone();
+        b.flags.ply_count = 4;
+        assert_eq!(a.position_hash(), b.position_hash(), "same phase");
+        b.flags.ply_count = 5;
+        assert_ne!(a.position_hash(), b.position_hash(), "different phase");
+    }
+
+    /// The hash is derived state: it doesn't ride the FEN, and a parsed
+    /// board hashes the same as the board it came from.
+    #[test]
+    fn test_hash_survives_fen_roundtrip() {
+        let mut board = board_with_idle_kings();
+        king_step(&mut board, Color::White, 1);
+        let parsed = fen_to_board(&board_to_fen(&board)).unwrap();
+        assert_eq!(parsed.history.hash, None);
+        assert_eq!(parsed.position_hash(), board.position_hash());
+        assert_eq!(parsed, board);
+    }
+
+    // ---------------------------------------------------------------
+    // make_move / unmake_move
+    // ---------------------------------------------------------------
+
+    /// Make `mv`, check it changed something, unmake it, and check the
+    /// board is back — including the flags `PartialEq` ignores.
+    fn assert_unmake_restores(board: &mut Board, mv: GameMove) {
+        let before = board.clone();
+        let undo = board.make_move(mv).expect("move applies");
+        assert_ne!(*board, before, "the move must change the board");
+        board.unmake_move(undo);
+        assert_eq!(*board, before);
+        assert_eq!(board.history.hash, before.history.hash);
+        assert_eq!(
+            board.history.seen,
+            before.history.seen
+        );
+    }
+
+    fn legal_move_matching(
+        board: &Board,
+        from: Coord,
+        pred: impl Fn(&MoveType) -> bool,
+    ) -> GameMove {
+        board
+            .legal_moves(&from)
+            .into_iter()
+            .find(|m| pred(&m.move_type))
+            .expect("expected move is legal")
+    }
+
+    #[test]
+    fn test_unmake_castle_restores_rook_and_rights() {
+        let mut board = fen_to_board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq -").unwrap();
+        let mv = legal_move_matching(&board, Coord { file: 4, rank: 7 }, |t| {
+            matches!(t, MoveType::Castle { side: CastleSide::Queenside })
+        });
+        assert_unmake_restores(&mut board, mv);
+    }
+
+    #[test]
+    fn test_unmake_en_passant_restores_captured_pawn() {
+        let mut board = fen_to_board("4k3/8/8/3pP3/8/8/8/4K3 w - d6").unwrap();
+        let mv = legal_move_matching(&board, Coord { file: 4, rank: 3 }, |t| {
+            matches!(t, MoveType::EnPassant { .. })
+        });
+        assert_unmake_restores(&mut board, mv);
+    }
+
+    #[test]
+    fn test_unmake_capturing_promotion() {
+        let mut board = fen_to_board("1n2k3/P7/8/8/8/8/8/4K3 w - -").unwrap();
+        let mv = legal_move_matching(&board, Coord { file: 0, rank: 1 }, |t| {
+            matches!(
+                t,
+                MoveType::Promotion { target, into: PromotionTarget::Knight } if target.file == 1
+            )
+        });
+        assert_unmake_restores(&mut board, mv);
+    }
+
+    /// A move that fails part-way leaves nothing behind: the rook
+    /// lifted for a carrier that isn't there goes back.
+    #[test]
+    fn test_failed_move_puts_the_board_back() {
+        let mut board = fen_to_board("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        let before = board.clone();
+        let err = board.make_move_unchecked(GameMove {
+            from: Coord { file: 0, rank: 7 },
+            move_type: MoveType::MoveIntoCarrier(Coord { file: 0, rank: 4 }),
+        });
+        assert!(err.is_err());
+        assert_eq!(board, before);
+        assert_eq!(board.position_hash(), before.position_hash());
+    }
+
+    /// The capture stack's `BoardOp::PlacePiece` (Goblin kidnap drop)
+    /// lands on a square the mover never touched.
+    #[test]
+    fn test_unmake_capture_stack_drop() {
+        let mut board = empty_board();
+        let mut goblin = Goblin::new(Color::White, Coord { file: 0, rank: 0 });
+        goblin.state = GoblinState::Kidnapping {
+            piece: std::sync::Arc::new(PieceType::new_pawn(Color::Black)),
+        };
+        board.grid[3][3] = Square::new().set_piece(PieceType::Goblin(goblin));
+        board.grid[0][3] = Square::new().set_piece(PieceType::new_rook(Color::Black));
+        board.grid[7][7] = Square::new().set_piece(PieceType::new_king(Color::White));
+        board.grid[0][7] = Square::new().set_piece(PieceType::new_king(Color::Black));
+        board.flags.side_to_move = Color::Black;
+        assert_unmake_restores(
+            &mut board,
+            GameMove {
+                from: Coord { file: 3, rank: 0 },
+                move_type: MoveType::MoveTo(Coord { file: 3, rank: 3 }),
+            },
+        );
+    }
+
+    #[test]
+    fn test_unmake_switch_throw_reopens_gate() {
+        let mut board = empty_board();
+        board.grid[3][3] = Square::new()
+            .set_piece(PieceType::new_pawn(Color::White))
+            .set_square_type(SquareType::Switch { targets: vec![1] });
+        board.grid[5][5] =
+            Square::new().set_square_type(SquareType::Gate { id: 1, open: true });
+        let from = Coord { file: 3, rank: 3 };
+        assert_unmake_restores(
+            &mut board,
+            GameMove {
+                from: from.clone(),
+                move_type: MoveType::ThrowSwitch { switch: from },
+            },
+        );
+    }
+
+    /// Phase shift paints brainrot on the neighbours during recalc.
+    #[test]
+    fn test_unmake_phase_shift_clears_brainrot() {
+        let mut board = empty_board();
+        board.grid[4][4] = Square::new().set_piece(PieceType::Skibidi(Skibidi {
+            color: Color::White,
+            phase: 1,
+        }));
+        board.grid[4][5] = Square::new().set_piece(PieceType::new_knight(Color::Black));
+        let before = board.clone();
+        let undo = board
+            .make_move(GameMove {
+                from: Coord { file: 4, rank: 4 },
+                move_type: MoveType::PhaseShift,
+            })
+            .unwrap();
+        assert!(board.grid[4][5].conditions.contains(&SquareCondition::Brainrot));
+        board.unmake_move(undo);
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn test_unmake_train_run_over_and_tornado_tick() {
+        let mut board = board_with_idle_kings();
+        board.flags.train_tick_rate = TrainTickRate::EveryPly;
+        lay_east_track(&mut board, 3, 1, 5);
+        board.grid[3][1] = board.grid[3][1]
+            .clone()
+            .set_piece(PieceType::Locomotive(Locomotive::new(
+                1,
+                TrainHeading::Forward,
+            )));
+        board.grid[3][2] = board.grid[3][2]
+            .clone()
+            .set_piece(PieceType::new_knight(Color::Black));
+        board.grid[5][5] =
+            Square::new().add_square_condition(SquareCondition::Tornado { remaining: 2 });
+        assert_unmake_restores(
+            &mut board,
+            GameMove {
+                from: Coord { file: 0, rank: 7 },
+                move_type: MoveType::MoveTo(Coord { file: 1, rank: 7 }),
+            },
+        );
+    }
+
+    /// A line of moves unmade in reverse lands on the start, hash and
+    /// repetition history included, and replaying it reproduces the
+    /// same end position.
+    #[test]
+    fn test_unmake_sequence_in_reverse() {
+        let mut board = board_with_idle_kings();
+        board.grid[6][3] = Square::new().set_piece(PieceType::new_pawn(Color::White));
+        let start = board.clone();
+        let line = [
+            (Coord { file: 0, rank: 7 }, Coord { file: 1, rank: 7 }),
+            (Coord { file: 7, rank: 0 }, Coord { file: 6, rank: 0 }),
+            (Coord { file: 3, rank: 6 }, Coord { file: 3, rank: 4 }),
+            (Coord { file: 6, rank: 0 }, Coord { file: 7, rank: 0 }),
+        ];
+        let mut undos = Vec::new();
+        for (from, to) in line.iter().cloned() {
+            undos.push(
+                board
+                    .make_move(GameMove {
+                        from,
+                        move_type: MoveType::MoveTo(to),
+                    })
+                    .unwrap(),
+            );
+        }
+        let end = board.clone();
+        while let Some(undo) = undos.pop() {
+            board.unmake_move(undo);
+        }
+        assert_eq!(board, start);
+        assert_eq!(board.history.hash, None);
+        for (from, to) in line.iter().cloned() {
+            board
+                .make_move(GameMove {
+                    from,
+                    move_type: MoveType::MoveTo(to),
+                })
+                .unwrap();
+        }
+        assert_eq!(board, end);
+        assert_eq!(board.history.hash, end.history.hash);
+    }
+
+    // ---------------------------------------------------------------
+    // SAN
+    // ---------------------------------------------------------------
+
+    /// Algebraic square → `Coord` on an 8-tall board, so SAN fixtures
+    /// read naturally.
+    fn sq(name: &str) -> Coord {
+        let b = name.as_bytes();
+        Coord {
+            file: b[0] - b'a',
+            rank: 8 - (b[1] - b'0'),
+        }
+    }
+
+    /// Every legal move renders to SAN that parses back to itself.
+    fn assert_san_roundtrips(board: &Board) {
+        let sources = board.move_sources(board.flags.side_to_move);
+        for from in &sources {
+            for mv in board.legal_moves(from) {
+                let san = board.move_to_san(&mv).expect("legal move renders");
+                assert_eq!(board.san_to_move(&san), Ok(mv), "{san}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_san_plays_fools_mate() {
+        let mut board =
+            fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap();
+        let mut rendered = Vec::new();
+        for san in ["f3", "e5", "g4", "Qh4"] {
+            let mv = board.san_to_move(san).expect(san);
+            rendered.push(board.move_to_san(&mv).unwrap());
+            board.make_move(mv).unwrap();
+        }
+        assert_eq!(rendered, ["f3", "e5", "g4", "Qh4#"]);
+        assert_eq!(
+            board.status(),
+            GameStatus::Checkmate {
+                winner: Color::Black
+            }
+        );
+    }
+
+    #[test]
+    fn test_san_castling_and_check_suffix() {
+        let board = fen_to_board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq -").unwrap();
+        let short = board.san_to_move("O-O").unwrap();
+        assert_eq!(
+            short.move_type,
+            MoveType::Castle {
+                side: CastleSide::Kingside
+            }
+        );
+        assert_eq!(board.san_to_move("0-0-0+"), board.san_to_move("O-O-O"));
+        assert_eq!(
+            board.move_to_san(&board.san_to_move("Rxa8").unwrap()).unwrap(),
+            "Rxa8+"
+        );
+        assert_san_roundtrips(&board);
+    }
+
+    #[test]
+    fn test_san_disambiguates_by_file_rank_then_square() {
+        let board = fen_to_board("k7/8/8/8/8/Q1Q5/8/Q3K3 w - -").unwrap();
+        // Three queens reach b2. c3 is alone on its file, a1 alone on
+        // its rank; a3 shares both with a rival and needs the square.
+        assert_eq!(board.san_to_move("Qcb2").unwrap().from, sq("c3"));
+        assert_eq!(board.san_to_move("Q1b2").unwrap().from, sq("a1"));
+        assert_eq!(board.san_to_move("Qa3b2").unwrap().from, sq("a3"));
+        assert_eq!(board.san_to_move("Qcc1").unwrap().from, sq("c3"));
+        // Only the a-file queens reach a2; the rank splits them.
+        assert_eq!(board.san_to_move("Q1a2").unwrap().from, sq("a1"));
+        assert!(matches!(
+            board.san_to_move("Qb2"),
+            Err(crate::board::san::SanError::NoMatch(_))
+        ));
+        assert_san_roundtrips(&board);
+    }
+
+    #[test]
+    fn test_san_pawn_captures_promotion_and_en_passant() {
+        let board = fen_to_board("3r1k2/4P3/8/3pP3/8/8/8/4K3 w - d6").unwrap();
+        let ep = board.san_to_move("exd6").unwrap();
+        assert!(matches!(ep.move_type, MoveType::EnPassant { .. }));
+        assert_eq!(board.san_to_move("exd6 e.p."), Ok(ep));
+        let promo = board.san_to_move("exd8=N").unwrap();
+        assert_eq!(
+            promo.move_type,
+            MoveType::Promotion {
+                target: sq("d8"),
+                into: PromotionTarget::Knight
+            }
+        );
+        assert_eq!(board.move_to_san(&promo).unwrap(), "exd8=N");
+        assert_eq!(board.move_to_san(&board.san_to_move("e8=Q").unwrap()).unwrap(), "e8=Q+");
+        assert_san_roundtrips(&board);
+    }
+
+    #[test]
+    fn test_san_fairy_move_types() {
+        use crate::pieces::fairy::stormcaller::Stormcaller;
+
+        let mut board = board_with_idle_kings();
+        board.grid[sq("c3").rank as usize][sq("c3").file as usize] =
+            Square::new().set_piece(PieceType::Bus(Bus {
+                color: Color::White,
+                pieces: vec![PieceType::new_knight(Color::White)].into(),
+            }));
+        board.grid[sq("b1").rank as usize][sq("b1").file as usize] =
+            Square::new().set_piece(PieceType::new_knight(Color::White));
+        board.grid[sq("e4").rank as usize][sq("e4").file as usize] = Square::new()
+            .set_piece(PieceType::Skibidi(Skibidi {
+                color: Color::White,
+                phase: 1,
+            }))
+            .set_square_type(SquareType::Switch { targets: vec![1] });
+        board.grid[sq("g2").rank as usize][sq("g2").file as usize] = Square::new()
+            .set_piece(PieceType::Stormcaller(Stormcaller::new(Color::White)));
+
+        let board_bus = board.san_to_move("N>c3").unwrap();
+        assert_eq!(board_bus.from, sq("b1"));
+        assert_eq!(board_bus.move_type, MoveType::MoveIntoCarrier(sq("c3")));
+
+        let exit = board.san_to_move("c3/0:Nd5").unwrap();
+        assert_eq!(exit.from, sq("c3"));
+        assert!(matches!(
+            &exit.move_type,
+            MoveType::PieceInCarrier { piece_index: 0, move_type }
+                if **move_type == MoveType::MoveTo(sq("d5"))
+        ));
+
+        assert_eq!(
+            board.san_to_move("Se4~").unwrap().move_type,
+            MoveType::PhaseShift
+        );
+        assert_eq!(
+            board.san_to_move("Se4^").unwrap().move_type,
+            MoveType::ThrowSwitch { switch: sq("e4") }
+        );
+        assert_eq!(
+            board.san_to_move("W*h3").unwrap().move_type,
+            MoveType::PlaceTornado { target: sq("h3") }
+        );
+        assert_san_roundtrips(&board);
+    }
+
+    /// The Monkey can reach one landing by two jump chains, so its
+    /// generator offers the same move twice; the copies mustn't read
+    /// as two candidates for one string.
+    #[test]
+    fn test_san_monkey_move_roundtrips() {
+        let board =
+            fen_to_board("8/8/7k/(P=BUS)1ps1S2/4MP2/8/8/4K3 w - - 10 15 tr=full p=28").unwrap();
+        let mv = board.san_to_move("Mg4").unwrap();
+        assert_eq!(mv.from, sq("e4"));
+        assert_eq!(board.move_to_san(&mv).unwrap(), "Mg4");
+        assert_san_roundtrips(&board);
+    }
+
+    #[test]
+    fn test_san_passenger_move_always_names_the_piece() {
+        let mut board = board_with_idle_kings();
+        board.grid[sq("d4").rank as usize][sq("d4").file as usize] =
+            Square::new().set_piece(PieceType::Bus(Bus {
+                color: Color::White,
+                pieces: vec![PieceType::new_pawn(Color::White)].into(),
+            }));
+        let inner = GameMove {
+            from: sq("d4"),
+            move_type: MoveType::PieceInCarrier {
+                piece_index: 0,
+                move_type: std::sync::Arc::new(MoveType::MoveTo(sq("d5"))),
+            },
+        };
+        assert_eq!(board.move_to_san(&inner).unwrap(), "d4/0:Pd5");
+        assert_eq!(board.san_to_move("d4/0:Pd5"), Ok(inner));
+    }
+
+    #[test]
+    fn test_san_rejects_empty_and_illegal() {
+        use crate::board::san::SanError;
+
+        let board = fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap();
+        assert_eq!(board.san_to_move("  +"), Err(SanError::EmptyInput));
+        assert_eq!(
+            board.san_to_move("e5"),
+            Err(SanError::NoMatch("e5".to_string()))
+        );
+        let illegal = GameMove {
+            from: sq("e2"),
+            move_type: MoveType::MoveTo(sq("e5")),
+        };
+        assert!(board.move_to_san(&illegal).is_err());
+    }
+
+    // ---------------------------------------------------------------
+    // Long-algebraic (UCI) move strings
+    // ---------------------------------------------------------------
+
+    /// Every pseudo-legal move of every piece renders to a string that
+    /// parses back to the same `GameMove`.
+    fn assert_uci_roundtrips(board: &Board) {
+        for (from, _) in board.all_pieces() {
+            for mv in board.get_moves(&from) {
+                let s = board.move_to_uci(&mv);
+                assert_eq!(board.uci_to_move(&s), Ok(mv), "{s}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_uci_standard_shapes() {
+        let board = fen_to_board("r3k2r/4P3/8/3pP3/8/8/8/R3K2R w KQkq d6").unwrap();
+        assert_eq!(
+            board.uci_to_move("e1g1").unwrap().move_type,
+            MoveType::Castle {
+                side: CastleSide::Kingside
+            }
+        );
+        assert_eq!(
+            board.uci_to_move("e1c1").unwrap().move_type,
+            MoveType::Castle {
+                side: CastleSide::Queenside
+            }
+        );
+        assert_eq!(
+            board.uci_to_move("e5d6").unwrap().move_type,
+            MoveType::EnPassant {
+                target: sq("d6"),
+                captured: sq("d5")
+            }
+        );
+        let promo = board.uci_to_move("e7e8n").unwrap();
+        assert_eq!(
+            promo.move_type,
+            MoveType::Promotion {
+                target: sq("e8"),
+                into: PromotionTarget::Knight
+            }
+        );
+        assert_eq!(board.move_to_uci(&promo), "e7e8n");
+        assert_eq!(
+            board.uci_to_move("e1e2").unwrap(),
+            GameMove {
+                from: sq("e1"),
+                move_type: MoveType::MoveTo(sq("e2"))
+            }
+        );
+        assert_uci_roundtrips(&board);
+        assert_uci_roundtrips(
+            &fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_uci_fairy_shapes() {
+        use crate::pieces::fairy::stormcaller::Stormcaller;
+
+        let mut board = board_with_idle_kings();
+        board.grid[sq("d4").rank as usize][sq("d4").file as usize] =
+            Square::new().set_piece(PieceType::Bus(Bus {
+                color: Color::White,
+                pieces: vec![
+                    PieceType::new_pawn(Color::White),
+                    PieceType::new_knight(Color::White),
+                    PieceType::new_rook(Color::White),
+                ].into(),
+            }));
+        board.grid[sq("e5").rank as usize][sq("e5").file as usize] =
+            Square::new().set_piece(PieceType::Bus(Bus {
+                color: Color::White,
+                pieces: vec![].into(),
+            }));
+        board.grid[sq("b1").rank as usize][sq("b1").file as usize] =
+            Square::new().set_piece(PieceType::new_knight(Color::White));
+        board.grid[sq("e4").rank as usize][sq("e4").file as usize] = Square::new()
+            .set_piece(PieceType::Skibidi(Skibidi {
+                color: Color::White,
+                phase: 1,
+            }))
+            .set_square_type(SquareType::Switch { targets: vec![1] });
+        board.grid[sq("g2").rank as usize][sq("g2").file as usize] = Square::new()
+            .set_piece(PieceType::Stormcaller(Stormcaller::new(Color::White)));
+
+        let exit = board.uci_to_move("d4/2:d5").unwrap();
+        assert_eq!(
+            exit.move_type,
+            MoveType::PieceInCarrier {
+                piece_index: 2,
+                move_type: std::sync::Arc::new(MoveType::MoveTo(sq("d5")))
+            }
+        );
+        let transfer = board.uci_to_move("d4/1:>e5").unwrap();
+        assert_eq!(board.move_to_uci(&transfer), "d4/1:>e5");
+        let nested = GameMove {
+            from: sq("d4"),
+            move_type: MoveType::PieceInCarrier {
+                piece_index: 2,
+                move_type: std::sync::Arc::new(MoveType::PieceInCarrier {
+                    piece_index: 0,
+                    move_type: std::sync::Arc::new(MoveType::MoveIntoCarrier(sq("e5"))),
+                }),
+            },
+        };
+        assert_eq!(board.move_to_uci(&nested), "d4/2/0:>e5");
+        assert_eq!(board.uci_to_move("d4/2/0:>e5"), Ok(nested));
+        assert_eq!(
+            board.uci_to_move("b1>d4").unwrap().move_type,
+            MoveType::MoveIntoCarrier(sq("d4"))
+        );
+        assert_eq!(board.uci_to_move("e4~").unwrap().move_type, MoveType::PhaseShift);
+        assert_eq!(
+            board.uci_to_move("e4^").unwrap().move_type,
+            MoveType::ThrowSwitch { switch: sq("e4") }
+        );
+        let remote = GameMove {
+            from: sq("e4"),
+            move_type: MoveType::ThrowSwitch { switch: sq("e5") },
+        };
+        assert_eq!(board.uci_to_move(&board.move_to_uci(&remote)), Ok(remote));
+        assert_eq!(
+            board.uci_to_move("g2*h3").unwrap().move_type,
+            MoveType::PlaceTornado { target: sq("h3") }
+        );
+        assert_uci_roundtrips(&board);
+    }
+
+    #[test]
+    fn test_uci_rejects_malformed() {
+        use crate::board::uci::UciError;
+
+        let board = fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap();
+        assert_eq!(board.uci_to_move(" "), Err(UciError::EmptyInput));
+        assert!(matches!(board.uci_to_move("i2e4"), Err(UciError::BadSquare(_))));
+        assert!(matches!(board.uci_to_move("e2e9"), Err(UciError::BadSquare(_))));
+        assert!(matches!(board.uci_to_move("e2"), Err(UciError::BadSquare(_))));
+        assert_eq!(
+            board.uci_to_move("e7e8k"),
+            Err(UciError::BadPromotion("k".to_string()))
+        );
+        assert!(matches!(
+            board.uci_to_move("d4/x:d5"),
+            Err(UciError::BadPassengerIndex(_))
+        ));
+        assert_eq!(
+            board.uci_to_move("e2e4 "),
+            board.uci_to_move("e2e4"),
+            "surrounding whitespace is ignored"
+        );
+        assert_eq!(
+            board.uci_to_move("e2e4+"),
+            Err(UciError::TrailingInput("+".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_coord_inverts_format_coord() {
+        let board = empty_board_sized(10, 12);
+        for rank in 0..12 {
+            for file in 0..10 {
+                let c = Coord { file, rank };
+                assert_eq!(board.parse_coord(&board.format_coord(&c)), Some(c));
+            }
+        }
+        assert_eq!(board.parse_coord("k1"), None);
+        assert_eq!(board.parse_coord("a13"), None);
+        assert_eq!(board.parse_coord("a0"), None);
+        assert_eq!(board.parse_coord("a"), None);
+    }
+
+    // ---------------------------------------------------------------
+    // Insufficient material
+    // ---------------------------------------------------------------
+
+    fn dead(fen: &str) -> bool {
+        let board = fen_to_board(fen).unwrap();
+        let dead = board.is_insufficient_material();
+        if dead {
+            assert_eq!(
+                board.status(),
+                GameStatus::Draw { reason: DrawReason::InsufficientMaterial },
+                "{fen}"
+            );
+        }
+        dead
+    }
+
+    #[test]
+    fn test_insufficient_material_standard_cases() {
+        assert!(dead("4k3/8/8/8/8/8/8/4K3 w - -"));
+        assert!(dead("4k3/8/8/8/8/8/8/2B1K3 w - -"));
+        assert!(dead("4k3/8/8/8/8/8/8/1N2K3 b - -"));
+        // Bishops all on dark squares, split between the sides.
+        assert!(dead("4kb2/8/8/8/8/B7/8/2B1K3 w - -"));
+
+        assert!(!dead("4k3/8/8/8/8/8/8/2B1KB2 w - -"), "opposite-colour bishops");
+        assert!(!dead("1n2k3/8/8/8/8/8/8/1N2K3 w - -"), "knight each");
+        assert!(!dead("4k3/8/8/8/8/8/8/1NB1K3 w - -"));
+        assert!(!dead("4k3/8/8/8/8/8/4P3/4K3 w - -"), "a pawn can promote");
+        assert!(!dead("4k3/8/8/8/8/8/8/R3K3 w - -"));
+    }
+
+    #[test]
+    fn test_insufficient_material_fairy_pieces() {
+        // Inert pieces can't attack a king.
+        assert!(dead("4k3/8/8/8/8/8/8/(P=BUS)3K3 w - -"));
+        assert!(dead("4k3/8/8/8/8/8/2W5/4K3 w - -"));
+        // …but a passenger can, and a Monkey or a train can kill.
+        assert!(!dead("4k3/8/8/8/8/8/8/(P=BUS(P=(Q)))3K3 w - -"));
+        assert!(!dead("4k3/8/8/8/8/8/8/M3K3 w - -"));
+        assert!(!dead("4k3/8/8/8/8/8/8/(P=LOCO(ID=1,H=F))3K3 w - -"));
+        // A Skibidi can't give check but can win by Brainrot.
+        assert!(!dead("4k3/8/3s4/8/8/8/8/4K3 w - -"));
+        // An inert piece can box a king in for a lone minor.
+        assert!(!dead("4k3/8/8/8/8/8/8/(P=BUS)N2K3 w - -"));
+    }
+
+    #[test]
+    fn test_insufficient_material_needs_a_plain_board_for_minors() {
+        let mut board = fen_to_board("4k3/8/8/8/8/8/8/1N2K3 w - -").unwrap();
+        assert!(board.is_insufficient_material());
+        board.grid[0][3] = Square::new().set_square_type(SquareType::Block);
+        assert!(!board.is_insufficient_material(), "a wall can stand in for a blocker");
+
+        let mut bare = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+        bare.grid[0][3] = Square::new().set_square_type(SquareType::Block);
+        assert!(bare.is_insufficient_material(), "nothing can give check at all");
+    }
+
+    // ---------------------------------------------------------------
+    // Brainrot win
+    // ---------------------------------------------------------------
+
+    /// Leaf count `depth` plies down, over every piece of the side to
+    /// move. Passenger moves of neutral carts aren't needed here.
+    fn perft(board: &Board, depth: u32) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+        let color = board.flags.side_to_move;
+        board
+            .move_sources(color)
+            .iter()
+            .flat_map(|c| board.legal_moves(c))
+            .map(|m| {
+                let mut next = board.clone();
+                next.make_move(m).expect("legal move applies");
+                perft(&next, depth - 1)
+            })
+            .sum()
+    }
+
+    /// Black's king on a8 is frozen by the phase-2 White Skibidi on a7
+    /// and has nothing else to move.
+    const BRAINROT_WIN_FEN: &str =
+        "(P=k,C=BRAINROT)7/(P=S(PHASE=2),C=BRAINROT)7/8/8/8/8/8/7K b - - 0 1";
+
+    #[test]
+    fn test_brainrot_win_status() {
+        let board = fen_to_board(BRAINROT_WIN_FEN).unwrap();
+        let expected = GameStatus::BrainrotWin {
+            winner: Color::White,
+        };
+        assert_eq!(board.status(), expected);
+        assert!(board.is_brainrotted_by(&sq("a8"), Color::White));
+        assert!(!board.is_brainrotted_by(&sq("a8"), Color::Black));
+        assert_eq!(perft(&board, 1), 0);
+
+        let reparsed = fen_to_board(&board_to_fen(&board)).unwrap();
+        assert_eq!(reparsed.status(), expected);
+
+        let json = serde_json::to_value(&expected).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"status": "BrainrotWin", "data": {"winner": "White"}})
+        );
+        assert_eq!(serde_json::from_value::<GameStatus>(json).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_stalemate_stays_distinct_from_brainrot_win() {
+        let status = |fen: &str| fen_to_board(fen).unwrap().status();
+        assert_eq!(status("k7/8/1Q6/8/8/8/8/7K b - -"), GameStatus::Stalemate);
+        // Brainrot with no Skibidi behind it has no caster.
+        assert_eq!(
+            status("(P=k,C=BRAINROT)7/8/8/8/8/8/8/7K b - -"),
+            GameStatus::Stalemate
+        );
+        // A phase-1 Skibidi radiates nothing.
+        assert_eq!(
+            status("(P=k,C=BRAINROT)7/S7/8/8/8/8/8/7K b - -"),
+            GameStatus::Stalemate
+        );
+        // Out of range of the phase-2 Skibidi on h1.
+        assert_eq!(
+            status("(P=k,C=BRAINROT)7/8/8/8/8/8/8/K6(P=S(PHASE=2)) b - -"),
+            GameStatus::Stalemate
+        );
+        // A king frozen by a Skibidi but with no legal move because
+        // it's mated is still checkmate.
+        assert_eq!(
+            status("(P=k,C=BRAINROT)6R/(P=S(PHASE=2),C=BRAINROT)7/8/8/8/8/8/7K b - -"),
+            GameStatus::Checkmate {
+                winner: Color::White
+            }
+        );
+    }
+
+    /// Phase-shifting the Skibidi next to the king wins on the spot;
+    /// neither the status probe nor the unmake disturbs the move tree.
+    #[test]
+    fn test_brainrot_win_by_phase_shift_is_perft_stable() {
+        let mut board = fen_to_board("k7/S7/8/8/8/8/8/7K w - - 0 1").unwrap();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        let nodes = perft(&board, 2);
+
+        let shift = legal_move_matching(&board, sq("a7"), |m| {
+            matches!(m, MoveType::PhaseShift)
+        });
+        assert_eq!(board.move_to_san(&shift).unwrap(), "Sa7~");
+        let mut after = board.clone();
+        after.make_move(shift.clone()).unwrap();
+        assert_eq!(
+            after.status(),
+            GameStatus::BrainrotWin {
+                winner: Color::White
+            }
+        );
+        assert_eq!(perft(&after, 1), 0);
+
+        assert_unmake_restores(&mut board, shift);
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        assert_eq!(perft(&board, 2), nodes);
+    }
+
+    // ---------------------------------------------------------------
+    // Extra moves (Goblin "taker moves again")
+    // ---------------------------------------------------------------
+
+    /// Black to move, with a black rook on d8 lined up on a white Goblin
+    /// on d5 that is kidnapping a black pawn. White's king sits on
+    /// `white_king`.
+    fn kidnapping_goblin_under_rook(white_king: Coord) -> Board {
+        let mut board = board_with_idle_kings();
+        board.grid[7][0] = Square::new();
+        board.grid[white_king.rank as usize][white_king.file as usize] =
+            Square::new().set_piece(PieceType::new_king(Color::White));
+        board.grid[3][3] = Square::new().set_piece(PieceType::Goblin(Goblin {
+            color: Color::White,
+            home_square: Coord { file: 0, rank: 7 },
+            state: GoblinState::Kidnapping {
+                piece: std::sync::Arc::new(PieceType::new_pawn(Color::Black)),
+            },
+        }));
+        board.grid[0][3] = Square::new().set_piece(PieceType::new_rook(Color::Black));
+        board.flags.side_to_move = Color::Black;
+        board
+    }
+
+    fn rook_takes_goblin() -> GameMove {
+        GameMove {
+            from: sq("d8"),
+            move_type: MoveType::MoveTo(sq("d5")),
+        }
+    }
+
+    #[test]
+    fn test_capturing_kidnapping_goblin_grants_extra_move() {
+        let mut board = kidnapping_goblin_under_rook(sq("a1"));
+        board.make_move(rook_takes_goblin()).unwrap();
+
+        assert_eq!(board.flags.side_to_move, Color::Black, "Black moves again");
+        assert_eq!(board.flags.extra_moves, 1);
+        assert_eq!(board.flags.ply_count, 0, "the turn hasn't passed");
+        assert_eq!(board.flags.fullmove_number, 1);
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        let last = board.flags.last_move.clone().expect("last_move stamped");
+        assert_eq!(last.mover_color, Color::Black);
+
+        let fen = board_to_fen(&board);
+        assert!(fen.contains(" em=1"), "{fen}");
+        let reparsed = fen_to_board(&fen).unwrap();
+        assert_eq!(reparsed, board);
+        let mut spent = reparsed.clone();
+        spent.flags.extra_moves = 0;
+        assert_ne!(spent.position_hash(), reparsed.position_hash());
+        assert_eq!(reparsed.position_hash(), board.position_hash());
+
+        // The bonus move spends the credit and passes the turn.
+        king_step(&mut board, Color::Black, -1);
+        assert_eq!(board.flags.side_to_move, Color::White);
+        assert_eq!(board.flags.extra_moves, 0);
+        assert_eq!(board.flags.ply_count, 1);
+        assert_eq!(board.flags.fullmove_number, 2);
+        assert_eq!(board.flags.last_move.unwrap().from, sq("h8"));
+    }
+
+    #[test]
+    fn test_extra_move_forfeited_by_check() {
+        let mut board = kidnapping_goblin_under_rook(sq("d1"));
+        board.make_move(rook_takes_goblin()).unwrap();
+        assert_eq!(board.flags.extra_moves, 0);
+        assert_eq!(board.flags.side_to_move, Color::White);
+        assert_eq!(
+            board.status(),
+            GameStatus::Check {
+                side_to_move: Color::White
+            }
+        );
+    }
+
+    #[test]
+    fn test_extra_move_unmake_restores() {
+        let mut board = kidnapping_goblin_under_rook(sq("a1"));
+        assert_unmake_restores(&mut board, rook_takes_goblin());
+        assert_eq!(board.flags.extra_moves, 0);
+    }
+
+    #[test]
+    fn test_fen_extra_moves_field() {
+        let board = fen_to_board("7k/8/8/8/8/8/8/K7 w - - 0 1 tr=full p=0 em=2").unwrap();
+        assert_eq!(board.flags.extra_moves, 2);
+        assert!(board_to_fen(&board).contains(" p=0 em=2"));
+        let bad = fen_to_board("7k/8/8/8/8/8/8/K7 w - - 0 1 em=x").unwrap();
+        assert_eq!(bad.flags.extra_moves, 0);
+        let plain = fen_to_board("7k/8/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        assert!(!board_to_fen(&plain).contains("em="));
+    }
+
+    // ---------------------------------------------------------------
+    // Duck Chess
+    // ---------------------------------------------------------------
+
+    const DUCK_START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 \
+                              tr=full p=0 variants=duck_chess";
+
+    fn place_duck(at: Coord) -> GameMove {
+        GameMove {
+            from: at.clone(),
+            move_type: MoveType::PlaceDuck { to: at },
+        }
+    }
+
+    fn move_duck(from: Coord, to: Coord) -> GameMove {
+        GameMove {
+            from,
+            move_type: MoveType::MoveDuck { to },
+        }
+    }
+
+    #[test]
+    fn test_fen_duck_round_trip() {
+        let fen = "rnbqkbnr/pppppppp/8/4(DUCK)3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1 \
+                   tr=full p=0 variants=duck_chess duck_phase=placing";
+        let board = fen_to_board(fen).unwrap();
+        assert_eq!(board.flags.variants, vec![VariantId::DuckChess]);
+        assert_eq!(board.flags.duck_phase, DuckPhase::DuckPlacement);
+        assert_eq!(board.duck_square(), Some(sq("e5")));
+        assert_eq!(board_to_fen(&board), fen);
+
+        let plain = fen_to_board("7k/8/8/8/8/8/8/K7 w - - 0 1 variants=nope duck_phase=x")
+            .unwrap();
+        assert!(plain.flags.variants.is_empty());
+        assert_eq!(plain.flags.duck_phase, DuckPhase::PieceMove);
+        let out = board_to_fen(&plain);
+        assert!(!out.contains("variants=") && !out.contains("duck_phase="), "{out}");
+    }
+
+    #[test]
+    fn test_fen_rejects_duck_on_piece_or_blocked_terrain() {
+        use crate::board::fen::FenError;
+        for fen in ["(P=N,DUCK)7/8/8/8/8/8/8/8", "(T=BLOCK,DUCK)7/8/8/8/8/8/8/8"] {
+            assert!(
+                matches!(fen_to_board(fen), Err(FenError::BadExtendedSquare { .. })),
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_duck_turn_is_piece_move_then_duck_move() {
+        let mut board = fen_to_board(DUCK_START).unwrap();
+        board
+            .make_move(GameMove {
+                from: sq("e2"),
+                move_type: MoveType::MoveTo(sq("e4")),
+            })
+            .unwrap();
+        assert_eq!(board.flags.side_to_move, Color::White, "White still owes the duck");
+        assert_eq!(board.flags.duck_phase, DuckPhase::DuckPlacement);
+        assert_eq!(board.flags.ply_count, 0);
+        assert_eq!(board.flags.en_passant_target, Some(sq("e3")));
+
+        // Every empty square takes the first duck; no piece may move.
+        let sources = board.move_sources(Color::White);
+        assert_eq!(sources.len(), 32);
+        assert!(board.legal_moves(&sq("g1")).is_empty());
+        assert!(matches!(
+            board.make_move(GameMove {
+                from: sq("g1"),
+                move_type: MoveType::MoveTo(sq("f3")),
+            }),
+            Err(MoveError::MustMoveDuck { .. })
+        ));
+
+        board.make_move(place_duck(sq("e5"))).unwrap();
+        assert_eq!(board.flags.side_to_move, Color::Black);
+        assert_eq!(board.flags.duck_phase, DuckPhase::PieceMove);
+        assert_eq!(board.flags.ply_count, 1);
+        assert_eq!(board.flags.fullmove_number, 1);
+        assert_eq!(board.flags.en_passant_target, Some(sq("e3")));
+        assert_eq!(board.flags.last_move.as_ref().unwrap().from, sq("e2"));
+        // The duck blocks the e-pawn and can't be moved out of turn.
+        assert!(board.legal_moves(&sq("e7")).iter().all(|m| m.move_type
+            != MoveType::MoveTo(sq("e5"))));
+        assert!(matches!(
+            board.make_move(move_duck(sq("e5"), sq("d5"))),
+            Err(MoveError::IllegalDuckMove { .. })
+        ));
+
+        board
+            .make_move(GameMove {
+                from: sq("d7"),
+                move_type: MoveType::MoveTo(sq("d6")),
+            })
+            .unwrap();
+        // From now on the duck must move — somewhere else.
+        assert_eq!(board.move_sources(Color::Black), vec![sq("e5")]);
+        assert!(matches!(
+            board.make_move(place_duck(sq("a6"))),
+            Err(MoveError::IllegalDuckMove { .. })
+        ));
+        assert!(board.make_move(move_duck(sq("e5"), sq("e5"))).is_err());
+        board.make_move(move_duck(sq("e5"), sq("d5"))).unwrap();
+        assert_eq!(board.flags.side_to_move, Color::White);
+        assert_eq!(board.flags.fullmove_number, 2);
+        assert_eq!(board.duck_square(), Some(sq("d5")));
+        assert!(!board.get_square_at(&sq("e5")).unwrap().duck);
+    }
+
+    #[test]
+    fn test_duck_blocks_sliders_and_attacks() {
+        let board = fen_to_board(
+            "7k/8/8/8/R2(DUCK)3r/8/8/K7 w - - 0 1 tr=full p=0 variants=duck_chess",
+        )
+        .unwrap();
+        let rook_targets: Vec<Coord> = board
+            .legal_moves(&sq("a4"))
+            .into_iter()
+            .filter_map(|m| match m.move_type {
+                MoveType::MoveTo(c) if c.rank == sq("a4").rank => Some(c),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rook_targets, vec![sq("b4"), sq("c4")]);
+        assert!(!board.is_attacked_by(&sq("e4"), Color::White));
+        assert!(!board.is_attacked_by(&sq("d4"), Color::White));
+        assert!(!board.is_attacked_by(&sq("d4"), Color::Black));
+    }
+
+    #[test]
+    fn test_duck_chess_king_may_be_left_en_prise_and_captured() {
+        let mut board = fen_to_board(
+            "3r3k/8/8/(DUCK)7/8/8/8/4K3 w - - 0 1 tr=full p=0 variants=duck_chess",
+        )
+        .unwrap();
+        king_step(&mut board, Color::White, -1);
+        assert_eq!(board.flags.halfmove_clock, 1);
+        assert_eq!(board.status(), GameStatus::Ongoing, "no check in Duck Chess");
+        board.make_move(move_duck(sq("a5"), sq("a6"))).unwrap();
+        assert_eq!(board.flags.halfmove_clock, 1, "the duck doesn't tick the clock");
+        board
+            .make_move(GameMove {
+                from: sq("d8"),
+                move_type: MoveType::MoveTo(sq("d1")),
+            })
+            .unwrap();
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::Black,
+                reason: WinReason::KingCapture,
+            }
+        );
+    }
+
+    #[test]
+    fn test_duck_chess_castles_through_attack_but_not_the_duck() {
+        let castles = |fen: &str| {
+            let board = fen_to_board(fen).unwrap();
+            board
+                .legal_moves(&sq("e1"))
+                .iter()
+                .any(|m| matches!(m.move_type, MoveType::Castle { .. }))
+        };
+        assert!(!castles("4kr2/8/8/8/8/8/8/4K2R w K - 0 1"));
+        assert!(castles(
+            "4kr2/8/8/8/8/8/8/4K2R w K - 0 1 tr=full p=0 variants=duck_chess"
+        ));
+        assert!(!castles(
+            "4kr2/8/8/8/8/8/8/4K(DUCK)1R w K - 0 1 tr=full p=0 variants=duck_chess"
+        ));
+    }
+
+    #[test]
+    fn test_duck_chess_stuck_side_to_move_wins() {
+        let board = fen_to_board(
+            "k(T=BLOCK)6/(T=BLOCK)(T=BLOCK)6/8/8/8/8/8/7K b - - 0 1 tr=full p=0 \
+             variants=duck_chess",
+        )
+        .unwrap();
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::Black,
+                reason: WinReason::Stalemate,
+            }
+        );
+    }
+
+    #[test]
+    fn test_duck_moves_unmake_restore() {
+        let mut board = fen_to_board(DUCK_START).unwrap();
+        assert_unmake_restores(
+            &mut board,
+            GameMove {
+                from: sq("g1"),
+                move_type: MoveType::MoveTo(sq("f3")),
+            },
+        );
+        board
+            .make_move(GameMove {
+                from: sq("g1"),
+                move_type: MoveType::MoveTo(sq("f3")),
+            })
+            .unwrap();
+        assert_unmake_restores(&mut board, place_duck(sq("d5")));
+    }
+
+    #[test]
+    fn test_duck_san_and_uci() {
+        let mut board = fen_to_board(DUCK_START).unwrap();
+        let e4 = board.san_to_move("e4").unwrap();
+        assert_eq!(board.move_to_san(&e4).unwrap(), "e4");
+        board.make_move(e4).unwrap();
+        let place = board.san_to_move("&e5").unwrap();
+        assert_eq!(place, place_duck(sq("e5")));
+        assert_eq!(board.move_to_uci(&place), "&e5");
+        assert_eq!(board.uci_to_move("&e5"), Ok(place.clone()));
+        board.make_move(place).unwrap();
+        board.make_move(board.san_to_move("d5").unwrap()).unwrap();
+        let hop = move_duck(sq("e5"), sq("h3"));
+        assert_eq!(board.move_to_san(&hop).unwrap(), "&h3");
+        assert_eq!(board.move_to_uci(&hop), "e5&h3");
+        assert_eq!(board.uci_to_move("e5&h3"), Ok(hop));
+        assert_san_roundtrips(&board);
+    }
+
+    // ---------------------------------------------------------------
+    // Win conditions
+    // ---------------------------------------------------------------
+
+    fn play(board: &mut Board, from: &str, to: &str) {
+        board
+            .make_move(GameMove {
+                from: sq(from),
+                move_type: MoveType::MoveTo(sq(to)),
+            })
+            .unwrap_or_else(|e| panic!("{from}-{to}: {e}"));
+    }
+
+    #[test]
+    fn test_win_conditions_fen_roundtrip() {
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 win=checkmate,3check checks=1+2";
+        let board = fen_to_board(fen).unwrap();
+        assert_eq!(
+            board.flags.win_conditions,
+            vec![WinConditionId::Checkmate, WinConditionId::NChecks(3)]
+        );
+        assert_eq!(board.flags.checks_given(Color::White), 1);
+        assert_eq!(board.flags.checks_given(Color::Black), 2);
+        assert_eq!(board_to_fen(&board), fen);
+
+        let sloppy = fen_to_board(
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1 win=koth,bogus,koth,0check checks=x",
+        )
+        .unwrap();
+        assert_eq!(sloppy.flags.win_conditions, vec![WinConditionId::KingOfTheHill]);
+        assert_eq!((sloppy.flags.white_checks, sloppy.flags.black_checks), (0, 0));
+
+        let plain = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(plain.flags.win_conditions.is_empty());
+        let out = board_to_fen(&plain);
+        assert!(!out.contains("win=") && !out.contains("checks="));
+    }
+
+    #[test]
+    fn test_king_of_the_hill_win() {
+        assert_eq!(
+            hill_squares(&empty_board()),
+            vec![sq("d5"), sq("e5"), sq("d4"), sq("e4")]
+        );
+        assert_eq!(
+            hill_squares(&empty_board_sized(5, 5)),
+            vec![Coord { file: 2, rank: 2 }]
+        );
+        let mut board =
+            fen_to_board("4k3/8/8/8/8/3K4/8/8 w - - 0 1 tr=full p=0 win=checkmate,koth").unwrap();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        play(&mut board, "d3", "e4");
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::KingOfTheHill,
+            }
+        );
+    }
+
+    #[test]
+    fn test_three_check_counts_checks_and_wins() {
+        let mut board = fen_to_board(
+            "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 tr=full p=0 win=checkmate,3check checks=1+0",
+        )
+        .unwrap();
+        assert_unmake_restores(
+            &mut board,
+            GameMove {
+                from: sq("d1"),
+                move_type: MoveType::MoveTo(sq("a4")),
+            },
+        );
+        play(&mut board, "d1", "a4");
+        assert_eq!(board.flags.checks_given(Color::White), 2);
+        assert_eq!(
+            board.status(),
+            GameStatus::Check {
+                side_to_move: Color::Black
+            }
+        );
+        play(&mut board, "e8", "e7");
+        assert_eq!(board.flags.checks_given(Color::Black), 0);
+        play(&mut board, "a4", "a3");
+        assert!(board_to_fen(&board).contains(" checks=3+0"));
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::NChecks,
+            }
+        );
+    }
+
+    #[test]
+    fn test_king_of_the_hill_variant() {
+        let fen = "4k3/8/8/8/8/8/8/6K(T=HILL) w - - 0 1 tr=full p=0 variants=king_of_the_hill";
+        let mut board = fen_to_board(fen).unwrap();
+        assert_eq!(board_to_fen(&board), fen);
+        assert_eq!(
+            board.ruleset().win_conditions(),
+            vec![WinConditionId::Checkmate, WinConditionId::KingOfTheHill]
+        );
+        // The marked square replaces the centre, and a bare king is
+        // still playing for it.
+        assert_eq!(hill_squares(&board), vec![sq("h1")]);
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        play(&mut board, "g1", "h1");
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::KingOfTheHill,
+            }
+        );
+    }
+
+    #[test]
+    fn test_passenger_king_on_the_hill_wins() {
+        let board = fen_to_board(
+            "4k3/8/8/8/3(P=BUS(P=(K)))4/8/8/8 b - - 0 1 tr=full p=0 variants=king_of_the_hill",
+        )
+        .unwrap();
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::KingOfTheHill,
+            }
+        );
+    }
+
+    #[test]
+    fn test_three_check_variant() {
+        let mut board = fen_to_board(
+            "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 tr=full p=0 variants=three_check checks=2+0",
+        )
+        .unwrap();
+        assert_eq!(
+            board.ruleset().win_conditions(),
+            vec![WinConditionId::Checkmate, WinConditionId::NChecks(3)]
+        );
+        play(&mut board, "d1", "a4");
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::NChecks,
+            }
+        );
+
+        // A check on a king riding in a Bus counts.
+        let mut board = fen_to_board(
+            "4(P=bus(P=(k)))3/8/8/8/8/8/8/3QK3 w - - 0 1 tr=full p=0 variants=three_check",
+        )
+        .unwrap();
+        play(&mut board, "d1", "a4");
+        assert_eq!(board.flags.checks_given(Color::White), 1);
+    }
+
+    #[test]
+    fn test_extinction_win() {
+        let mut board = fen_to_board(
+            "rbqk4/p7/8/8/8/3n4/P7/RBQKN3 w - - 0 1 tr=full p=0 win=extinction",
+        )
+        .unwrap();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        play(&mut board, "e1", "d3");
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::Extinction,
+            }
+        );
+    }
+
+    #[test]
+    fn test_king_capture_without_check() {
+        let mut board =
+            fen_to_board("4k3/8/8/8/8/8/r7/4K3 w - - 0 1 tr=full p=0 win=king_capture").unwrap();
+        play(&mut board, "e1", "e2");
+        assert!(board.is_in_check(Color::White));
+        assert_eq!(board.status(), GameStatus::Ongoing, "no check without mate");
+        play(&mut board, "a2", "e2");
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::Black,
+                reason: WinReason::KingCapture,
+            }
+        );
+    }
+
+    // ---------------------------------------------------------------
+    // Atomic
+    // ---------------------------------------------------------------
+
+    fn atomic(fen: &str) -> Board {
+        fen_to_board(&format!("{fen} w - - 0 1 tr=full p=0 variants=atomic")).unwrap()
+    }
+
+    fn occupied(board: &Board, name: &str) -> bool {
+        board.get_square_at(&sq(name)).unwrap().piece.is_some()
+    }
+
+    #[test]
+    fn test_atomic_capture_explodes_captor_and_non_pawns() {
+        let mut board = atomic("4k3/8/2p1n3/3b4/2B1P3/2N5/8/6K1");
+        let capture = GameMove {
+            from: sq("c3"),
+            move_type: MoveType::MoveTo(sq("d5")),
+        };
+        assert_unmake_restores(&mut board, capture.clone());
+        board.make_move(capture).unwrap();
+        for gone in ["c3", "d5", "e6", "c4"] {
+            assert!(!occupied(&board, gone), "{gone} should be empty");
+        }
+        for kept in ["c6", "e4", "e8", "g1"] {
+            assert!(occupied(&board, kept), "{kept} should survive");
+        }
+        assert_eq!(board.flags.halfmove_clock, 0);
+        assert_eq!(board.flags.side_to_move, Color::Black);
+    }
+
+    #[test]
+    fn test_atomic_kings_cannot_capture_and_touching_kings_are_safe() {
+        let board = atomic("8/8/8/3kn3/r2K4/8/8/8");
+        assert!(!board.is_in_check(Color::White), "the rook can't take d4 next to d5");
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        let targets: Vec<Coord> = board
+            .legal_moves(&sq("d4"))
+            .into_iter()
+            .filter_map(|m| match m.move_type {
+                MoveType::MoveTo(c) => Some(c),
+                _ => None,
+            })
+            .collect();
+        assert!(!targets.contains(&sq("e5")), "kings can't capture");
+        assert!(targets.contains(&sq("c4")), "the rook can't take c4 next to d5 either");
+    }
+
+    #[test]
+    fn test_atomic_blowing_up_kings() {
+        let mut board = atomic("4k3/3b4/8/8/8/8/8/3R2K1");
+        board
+            .make_move(GameMove {
+                from: sq("d1"),
+                move_type: MoveType::MoveTo(sq("d7")),
+            })
+            .unwrap();
+        assert_eq!(board.find_king(Color::Black), None);
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::KingCapture,
+            }
+        );
+
+        let mut board = atomic("4k3/8/8/8/8/8/5b2/5RK1");
+        assert!(board.is_in_check(Color::White));
+        let own_goal = GameMove {
+            from: sq("f1"),
+            move_type: MoveType::MoveTo(sq("f2")),
+        };
+        assert!(matches!(
+            board.make_move(own_goal),
+            Err(MoveError::WouldLeaveKingInCheck { .. })
+        ));
+    }
+
+    #[test]
+    fn test_atomic_blast_takes_buses_but_spares_train_carts() {
+        let mut board = atomic(
+            "4k3/8/8/3p4/2(P=bus(P=(p,n)))1(P=CART(ID=1,I=1,P=(N,P)))3/2N5/8/6K1",
+        );
+        board
+            .make_move(GameMove {
+                from: sq("c3"),
+                move_type: MoveType::MoveTo(sq("d5")),
+            })
+            .unwrap();
+        assert!(!occupied(&board, "c4"), "the bus goes up with its pawn");
+        let cart = board.get_square_at(&sq("e4")).unwrap().piece.clone().unwrap();
+        assert!(cart.is_train_cart());
+        let riders: Vec<String> = cart.passengers().unwrap().iter().map(|p| p.symbol().into_owned()).collect();
+        assert_eq!(riders, vec!["P".to_string()]);
+    }
+
+    #[test]
+    fn test_atomic_goblin_hostage_dies_with_it() {
+        let mut board = atomic("7k/8/8/3(P=g(H=3-0,P=N))4/8/8/8/3R2K1");
+        board
+            .make_move(GameMove {
+                from: sq("d1"),
+                move_type: MoveType::MoveTo(sq("d5")),
+            })
+            .unwrap();
+        assert!(!occupied(&board, "d5"));
+        assert!(!occupied(&board, "d1"), "no hostage dropped");
+        assert_eq!(board.flags.extra_moves, 0);
+        assert_eq!(board.flags.side_to_move, Color::Black);
+    }
+
+    // ---------------------------------------------------------------
+    // Crazyhouse
+    // ---------------------------------------------------------------
+
+    fn crazyhouse(fen: &str, pockets: &str) -> Board {
+        fen_to_board(&format!("{fen} w - - 0 1 tr=full p=0 variants=crazyhouse {pockets}")).unwrap()
+    }
+
+    fn symbols(pocket: &[PieceType]) -> Vec<String> {
+        pocket.iter().map(|p| p.symbol().into_owned()).collect()
+    }
+
+    #[test]
+    fn test_crazyhouse_pockets_fen_roundtrip() {
+        let board = crazyhouse("4k3/8/8/8/8/8/8/4K3", "pk=(W=PN(BUS)P,B=q)");
+        assert_eq!(symbols(&board.flags.white_pocket), vec!["BUS", "N", "P", "P"]);
+        assert_eq!(symbols(&board.flags.black_pocket), vec!["q"]);
+        let fen = board_to_fen(&board);
+        assert!(fen.contains(" pk=(W=(BUS)NPP,B=q)"), "{fen}");
+        assert_eq!(fen_to_board(&fen).unwrap(), board);
+
+        let empty = crazyhouse("4k3/8/8/8/8/8/8/4K3", "");
+        assert!(!board_to_fen(&empty).contains("pk="));
+        let black_only = crazyhouse("4k3/8/8/8/8/8/8/4K3", "pk=(B=nk)");
+        assert!(black_only.flags.white_pocket.is_empty());
+        assert_eq!(symbols(&black_only.flags.black_pocket), vec!["n"], "kings aren't pocketed");
+    }
+
+    #[test]
+    fn test_crazyhouse_capture_fills_the_takers_pocket() {
+        let mut board = crazyhouse("4k3/8/8/3p4/8/2N5/8/4K3", "");
+        let capture = GameMove {
+            from: sq("c3"),
+            move_type: MoveType::MoveTo(sq("d5")),
+        };
+        assert_unmake_restores(&mut board, capture.clone());
+        board.make_move(capture).unwrap();
+        assert_eq!(symbols(&board.flags.white_pocket), vec!["P"], "recoloured");
+        assert!(board.flags.black_pocket.is_empty());
+        assert_eq!(board.status(), GameStatus::Ongoing, "a pocket keeps the game alive");
+    }
+
+    #[test]
+    fn test_crazyhouse_drop_places_the_piece_and_empties_the_pocket() {
+        let mut board = crazyhouse("4k3/8/8/8/8/8/8/4K3", "pk=(W=N,B=n)");
+        let drop = board.san_to_move("N@e4").unwrap();
+        assert_eq!(
+            drop,
+            GameMove {
+                from: sq("e4"),
+                move_type: MoveType::Drop {
+                    piece: PieceType::new_knight(Color::White),
+                    target: sq("e4"),
+                },
+            }
+        );
+        assert_eq!(board.move_to_san(&drop).unwrap(), "N@e4");
+        assert_eq!(board.move_to_uci(&drop), "N@e4");
+        assert_eq!(board.uci_to_move("N@e4").unwrap(), drop);
+        assert_unmake_restores(&mut board, drop.clone());
+        board.make_move(drop).unwrap();
+        assert_eq!(
+            board.get_square_at(&sq("e4")).unwrap().piece,
+            Some(PieceType::new_knight(Color::White))
+        );
+        assert!(board.flags.white_pocket.is_empty());
+        assert_eq!(board.flags.side_to_move, Color::Black);
+
+        // White's turn is over and its pocket empty; Black drops its own.
+        assert!(matches!(
+            board.make_move(GameMove {
+                from: sq("a3"),
+                move_type: MoveType::Drop {
+                    piece: PieceType::new_knight(Color::White),
+                    target: sq("a3"),
+                },
+            }),
+            Err(MoveError::IllegalDrop { .. })
+        ));
+        assert!(board.san_to_move("N@e4").is_err(), "e4 is taken");
+        let black_drop = board.san_to_move("N@a3").unwrap();
+        assert!(matches!(
+            black_drop.move_type,
+            MoveType::Drop { piece: PieceType::Knight(ref n), .. } if n.color == Color::Black
+        ));
+    }
+
+    #[test]
+    fn test_crazyhouse_no_pawn_drops_on_the_back_ranks() {
+        let board = crazyhouse("4k3/8/8/8/8/8/8/4K3", "pk=(W=P)");
+        assert!(board.legal_moves(&sq("a8")).is_empty());
+        assert!(board.legal_moves(&sq("a1")).is_empty());
+        assert_eq!(board.legal_moves(&sq("a2")).len(), 1);
+        assert_eq!(board.san_to_move("P@a7").unwrap().from, sq("a7"));
+        let plain = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 pk=(W=P)").unwrap();
+        assert!(plain.legal_moves(&sq("a2")).is_empty(), "no drops outside Crazyhouse");
+    }
+
+    #[test]
+    fn test_crazyhouse_drop_can_block_check() {
+        let grid = "4k3/8/8/8/8/8/3PPP2/r3K3";
+        let standard = fen_to_board(&format!("{grid} w - - 0 1")).unwrap();
+        assert_eq!(
+            standard.status(),
+            GameStatus::Checkmate {
+                winner: Color::Black
+            }
+        );
+
+        let board = crazyhouse(grid, "pk=(W=N)");
+        assert_eq!(
+            board.status(),
+            GameStatus::Check {
+                side_to_move: Color::White
+            }
+        );
+        let mut legal: Vec<String> = board
+            .move_sources(Color::White)
+            .iter()
+            .flat_map(|c| board.legal_moves(c))
+            .map(|m| board.move_to_uci(&m))
+            .collect();
+        legal.sort();
+        assert_eq!(legal, vec!["N@b1", "N@c1", "N@d1"]);
+        let stray = board.uci_to_move("N@h5").unwrap();
+        assert!(board.clone().make_move(stray).is_err());
+    }
+
+    #[test]
+    fn test_crazyhouse_fairy_pieces_are_pocketed_fresh() {
+        let take_d5 = |victim: &str| {
+            let mut board = crazyhouse(&format!("4k3/8/8/3{victim}4/8/2N5/8/4K3"), "");
+            board
+                .make_move(GameMove {
+                    from: sq("c3"),
+                    move_type: MoveType::MoveTo(sq("d5")),
+                })
+                .unwrap();
+            board.flags.white_pocket
+        };
+        let pocket = take_d5("(P=bus(P=(n)))");
+        assert_eq!(symbols(&pocket), vec!["BUS", "N"], "the passenger comes too");
+        assert_eq!(pocket[0].passengers().map(|p| p.len()), Some(0));
+        assert_eq!(take_d5("(P=s(PHASE=3))"), vec![PieceType::Skibidi(Skibidi::new(Color::White))]);
+        assert!(matches!(
+            &take_d5("(P=g(H=3-0))")[..],
+            [PieceType::Goblin(Goblin { state: GoblinState::Free, color: Color::White, .. })]
+        ));
+
+        let mut board = crazyhouse("4k3/8/8/8/8/8/8/4K3", "pk=(W=G)");
+        let drop = board.san_to_move("G@e4").unwrap();
+        board.make_move(drop).unwrap();
+        let Some(PieceType::Goblin(goblin)) = &board.get_square_at(&sq("e4")).unwrap().piece else {
+            panic!("goblin not dropped");
+        };
+        assert_eq!(goblin.home_square, sq("e4"), "a dropped goblin lives where it lands");
+    }
+
+    // ---------------------------------------------------------------
+    // Antichess
+    // ---------------------------------------------------------------
+
+    fn antichess(fen: &str) -> Board {
+        fen_to_board(&format!("{fen} w - - 0 1 tr=full p=0 variants=antichess")).unwrap()
+    }
+
+    fn white_legal_moves(board: &Board) -> Vec<GameMove> {
+        board
+            .move_sources(Color::White)
+            .iter()
+            .flat_map(|c| board.legal_moves(c))
+            .collect()
+    }
+
+    #[test]
+    fn test_antichess_captures_are_compulsory() {
+        let mut board = antichess("4k3/8/8/3p4/4P3/8/8/4K3");
+        let capture = GameMove {
+            from: sq("e4"),
+            move_type: MoveType::MoveTo(sq("d5")),
+        };
+        assert_eq!(white_legal_moves(&board), vec![capture.clone()]);
+        assert!(matches!(
+            board.clone().make_move(GameMove {
+                from: sq("e1"),
+                move_type: MoveType::MoveTo(sq("e2")),
+            }),
+            Err(MoveError::MustCapture { .. })
+        ));
+        board.make_move(capture).unwrap();
+        assert!(
+            board.legal_moves(&sq("e8")).len() > 1,
+            "no capture for Black, so nothing is compelled"
+        );
+    }
+
+    #[test]
+    fn test_antichess_king_is_an_ordinary_piece() {
+        let board = antichess("4k3/8/8/8/8/8/3q4/4K3");
+        assert_eq!(board.status(), GameStatus::Ongoing, "no check in Antichess");
+        assert_eq!(
+            white_legal_moves(&board),
+            vec![GameMove {
+                from: sq("e1"),
+                move_type: MoveType::MoveTo(sq("d2")),
+            }],
+            "the king must take"
+        );
+
+        let mut board = antichess("8/8/8/8/8/8/3q4/4K2R");
+        board
+            .make_move(GameMove {
+                from: sq("h1"),
+                move_type: MoveType::MoveTo(sq("h2")),
+            })
+            .unwrap_err();
+        board
+            .make_move(GameMove {
+                from: sq("e1"),
+                move_type: MoveType::MoveTo(sq("d2")),
+            })
+            .unwrap();
+        assert_eq!(board.find_king(Color::White), Some(sq("d2")));
+    }
+
+    #[test]
+    fn test_antichess_losing_every_piece_or_being_stuck_wins() {
+        let mut board = antichess("8/8/8/8/8/8/3p4/4K3");
+        board
+            .make_move(GameMove {
+                from: sq("e1"),
+                move_type: MoveType::MoveTo(sq("d2")),
+            })
+            .unwrap();
+        assert_eq!(
+            board.status(),
+            GameStatus::Won {
+                winner: Color::Black,
+                reason: WinReason::LostAllPieces,
+            }
+        );
+
+        let stuck = antichess("8/8/8/8/8/4p3/4P3/8");
+        assert_eq!(
+            stuck.status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::Stalemate,
+            }
+        );
+    }
+
+    #[test]
+    fn test_antichess_binds_non_capturing_fairy_pieces() {
+        let board = antichess("4k3/8/8/3p4/4P3/8/8/W3K3");
+        assert!(
+            board.legal_moves(&sq("a1")).is_empty(),
+            "no tornado while a capture is on"
+        );
+        let free = antichess("4k3/8/8/4p3/4P3/8/8/W3K3");
+        assert!(
+            free.legal_moves(&sq("a1"))
+                .iter()
+                .any(|m| matches!(m.move_type, MoveType::PlaceTornado { .. }))
+        );
+    }
+
+    // ---------------------------------------------------------------
+    // Fog of war
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn test_fog_start_position_shows_each_side_its_own_half() {
+        let board = fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let white = board_to_fen(&board.visible_to(Color::White));
+        assert!(white.starts_with("8/8/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq"), "{white}");
+        assert_eq!(board.visible_squares(Color::White).len(), 32, "ranks 1-4");
+        let black = board_to_fen(&board.visible_to(Color::Black));
+        assert!(black.starts_with("rnbqkbnr/pppppppp/8/8/8/8/8/8 w"), "{black}");
+        assert!(board.visible_squares(Color::Neutral).is_empty());
+    }
+
+    #[test]
+    fn test_fog_hides_enemy_passengers_but_not_your_own() {
+        let board = fen_to_board(
+            "4k3/8/8/(P=bus(P=(n)))7/8/8/8/R(P=g(H=0-0,P=N))2K3 w - - 0 1 tr=full p=0",
+        )
+        .unwrap();
+        let view = board.visible_to(Color::White);
+        let bus = view.get_square_at(&sq("a5")).unwrap().piece.clone().unwrap();
+        assert!(matches!(bus, PieceType::Bus(_)), "the bus itself is in sight");
+        assert_eq!(bus.passengers().map(|p| p.len()), Some(0));
+        assert!(
+            matches!(
+                &view.get_square_at(&sq("b1")).unwrap().piece,
+                Some(PieceType::Goblin(Goblin { state: GoblinState::Kidnapping { .. }, .. }))
+            ),
+            "White knows which of its pieces the goblin holds"
+        );
+        assert!(view.get_square_at(&sq("e8")).unwrap().piece.is_none());
+    }
+
+    #[test]
+    fn test_fog_blanks_conditions_and_signal_state_out_of_sight() {
+        let board = fen_to_board(
+            "(C=TORNADO:3)3k2(T=GATE,ID=4,OPEN=0)/8/8/8/8/8/(C=TORNADO:2)7/K7 w - - 0 1 tr=full p=0",
+        )
+        .unwrap();
+        let view = board.visible_to(Color::White);
+        assert!(view.get_square_at(&sq("a8")).unwrap().conditions.is_empty());
+        assert_eq!(
+            view.get_square_at(&sq("h8")).unwrap().square_type,
+            SquareType::Gate { id: 0, open: true }
+        );
+        assert_eq!(
+            view.get_square_at(&sq("a2")).unwrap().conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 2 }],
+            "a tornado in sight keeps its countdown"
+        );
+    }
+
+    // ---------------------------------------------------------------
+    // Chess960 castling
+    // ---------------------------------------------------------------
+
+    use crate::board::chess960::{shuffled_back_rank, shuffled_start};
+
+    #[test]
+    fn test_chess960_castling_field_reads_shredder_and_writes_x_fen() {
+        let shredder =
+            fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1").unwrap();
+        let standard =
+            fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(shredder, standard);
+
+        // Two rooks on the kingside: `G` names the inner one, which `K`
+        // wouldn't.
+        let inner = fen_to_board("r3k1rr/8/8/8/8/8/8/R3K1RR w GAga - 0 1").unwrap();
+        assert_eq!(inner.castle_rook_square(Color::White, CastleSide::Kingside), Some(sq("g1")));
+        assert_eq!(inner.castle_rook_square(Color::Black, CastleSide::Queenside), Some(sq("a8")));
+        let fen = board_to_fen(&inner);
+        assert!(fen.contains(" w GQgq - "), "{fen}");
+        assert_eq!(fen_to_board(&fen).unwrap(), inner);
+    }
+
+    #[test]
+    fn test_chess960_castle_where_king_and_rook_overlap() {
+        // King b1, rook a1: queenside the king steps one file right
+        // and the rook jumps over it.
+        let mut board = fen_to_board("4k3/8/8/8/8/8/8/RK6 w Q - 0 1").unwrap();
+        let castle = GameMove {
+            from: sq("b1"),
+            move_type: MoveType::Castle {
+                side: CastleSide::Queenside,
+            },
+        };
+        assert!(board.legal_moves(&sq("b1")).contains(&castle));
+        assert_eq!(board.move_to_uci(&castle), "b1a1");
+        assert_eq!(board.uci_to_move("b1a1").unwrap(), castle);
+        board.make_move(castle).unwrap();
+        assert!(matches!(board.get_square_at(&sq("c1")).unwrap().piece, Some(PieceType::King(_))));
+        assert!(matches!(board.get_square_at(&sq("d1")).unwrap().piece, Some(PieceType::Rook(_))));
+        assert!(!board.flags.white_can_castle_queenside);
+
+        // King g1, rook h1: the king stays put and the rook lands on f1.
+        let mut board = fen_to_board("4k3/8/8/8/8/8/8/6KR w K - 0 1").unwrap();
+        let castle = board.uci_to_move("g1h1").unwrap();
+        assert_eq!(castle.move_type, MoveType::Castle { side: CastleSide::Kingside });
+        board.make_move(castle).unwrap();
+        assert!(matches!(board.get_square_at(&sq("g1")).unwrap().piece, Some(PieceType::King(_))));
+        assert!(matches!(board.get_square_at(&sq("f1")).unwrap().piece, Some(PieceType::Rook(_))));
+    }
+
+    #[test]
+    fn test_chess960_castle_needs_a_clear_path_and_a_safe_king_path() {
+        // King b1, rook e1: castling kingside takes the king across
+        // d1, where a knight stands.
+        let blocked = fen_to_board("4k3/8/8/8/8/8/8/1K1NR3 w E - 0 1").unwrap();
+        assert_eq!(blocked.castle_rook_square(Color::White, CastleSide::Kingside), Some(sq("e1")));
+        assert!(
+            !blocked
+                .legal_moves(&sq("b1"))
+                .iter()
+                .any(|m| matches!(m.move_type, MoveType::Castle { .. }))
+        );
+        // King b1 castling kingside to g1 crosses f1, attacked by the
+        // rook on f8.
+        let attacked = fen_to_board("4kr2/8/8/8/8/8/8/1K5R w K - 0 1").unwrap();
+        assert!(
+            !attacked
+                .legal_moves(&sq("b1"))
+                .iter()
+                .any(|m| matches!(m.move_type, MoveType::Castle { .. }))
+        );
+        let safe = fen_to_board("4k3/8/8/8/8/8/8/1K5R w K - 0 1").unwrap();
+        assert_eq!(safe.move_to_uci(&safe.uci_to_move("b1g1").unwrap()), "b1g1");
+    }
+
+    #[test]
+    fn test_chess960_only_the_named_rook_carries_the_right() {
+        let board = fen_to_board("4k3/8/8/8/8/8/8/4K1RR w G - 0 1").unwrap();
+        let mut outer = board.clone();
+        outer.make_move(outer.uci_to_move("h1h2").unwrap()).unwrap();
+        assert!(outer.flags.white_can_castle_kingside, "the h-rook isn't the castling rook");
+        let mut inner = board.clone();
+        inner.make_move(inner.uci_to_move("g1g2").unwrap()).unwrap();
+        assert!(!inner.flags.white_can_castle_kingside);
+        assert_eq!(inner.flags.castle_rook_files, Default::default());
+    }
+
+    #[test]
+    fn test_shuffled_start_keeps_the_chess960_constraints() {
+        let army = [
+            PieceType::new_rook(Color::White),
+            PieceType::new_knight(Color::White),
+            PieceType::new_bishop(Color::White),
+            PieceType::Bus(Bus::new(Color::White)),
+            PieceType::new_king(Color::White),
+            PieceType::new_bishop(Color::White),
+            PieceType::Goblin(Goblin::new(Color::White, Coord { file: 0, rank: 0 })),
+            PieceType::new_rook(Color::White),
+            PieceType::Skibidi(Skibidi::new(Color::White)),
+            PieceType::new_queen(Color::White),
+        ];
+        for seed in 0..32 {
+            let rank = shuffled_back_rank(&army, seed);
+            assert_eq!(rank, shuffled_back_rank(&army, seed), "seeded, so repeatable");
+            assert_eq!(rank.len(), army.len());
+            let files = |pick: fn(&PieceType) -> bool| -> Vec<usize> {
+                rank.iter().enumerate().filter(|(_, p)| pick(p)).map(|(f, _)| f).collect()
+            };
+            let bishops = files(|p| matches!(p, PieceType::Bishop(_)));
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "seed {seed}: {rank:?}");
+            let rooks = files(|p| matches!(p, PieceType::Rook(_)));
+            let king = files(|p| matches!(p, PieceType::King(_)))[0];
+            assert!(rooks[0] < king && king < rooks[1], "seed {seed}: {rank:?}");
+
+            let board = shuffled_start(&army, 8, seed);
+            assert!(board.flags.white_can_castle_kingside && board.flags.black_can_castle_queenside);
+            assert_eq!(fen_to_board(&board_to_fen(&board)).unwrap(), board);
+            assert!(matches!(board.status(), GameStatus::Ongoing));
+        }
+        assert_ne!(shuffled_back_rank(&army, 1), shuffled_back_rank(&army, 2));
+    }
+
+    // ---------------------------------------------------------------
+    // Fairy promotion
+    // ---------------------------------------------------------------
+
+    fn promotions_from(board: &Board, from: &str) -> Vec<PromotionTarget> {
+        board
+            .legal_moves(&sq(from))
+            .into_iter()
+            .filter_map(|m| match m.move_type {
+                MoveType::Promotion { into, .. } => Some(into),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_promotion_targets_follow_the_promo_flag() {
+        let standard = fen_to_board("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(promotions_from(&standard, "a7"), PromotionTarget::STANDARD.to_vec());
+
+        let fairy = fen_to_board("4k3/P7/8/8/8/8/8/4K3 w - - 0 1 promo=queen,goblin,bus").unwrap();
+        assert_eq!(
+            promotions_from(&fairy, "a7"),
+            vec![PromotionTarget::Queen, PromotionTarget::Goblin, PromotionTarget::Bus]
+        );
+        assert!(board_to_fen(&fairy).contains(" promo=queen,goblin,bus"));
+
+        let knight = GameMove {
+            from: sq("a7"),
+            move_type: MoveType::Promotion {
+                target: sq("a8"),
+                into: PromotionTarget::Knight,
+            },
+        };
+        assert!(fairy.clone().make_move(knight).is_err(), "not on this game's list");
+    }
+
+    #[test]
+    fn test_fairy_promotions_come_out_fresh() {
+        let board = fen_to_board(
+            "4k3/P7/8/8/8/8/8/4K3 w - - 0 1 promo=goblin,skibidi,monkey,bus,stormcaller",
+        )
+        .unwrap();
+        for into in board.flags.promotion_choices().to_vec() {
+            let mut after = board.clone();
+            after
+                .make_move(GameMove {
+                    from: sq("a7"),
+                    move_type: MoveType::Promotion {
+                        target: sq("a8"),
+                        into: into.clone(),
+                    },
+                })
+                .unwrap();
+            let piece = after.get_square_at(&sq("a8")).unwrap().piece.clone().unwrap();
+            assert_eq!(piece, into.piece(Color::White, &sq("a8")));
+            assert_eq!(after.flags.last_move.as_ref().unwrap().primary_symbol, piece.symbol());
+            assert_eq!(fen_to_board(&board_to_fen(&after)).unwrap(), after, "{into}");
+        }
+        assert_eq!(
+            PromotionTarget::Goblin.piece(Color::Black, &sq("c1")),
+            PieceType::Goblin(Goblin::new(Color::Black, sq("c1")))
+        );
+    }
+
+    #[test]
+    fn test_fairy_promotion_notation() {
+        let board = fen_to_board("4k3/P7/8/8/8/8/8/4K3 w - - 0 1 promo=bishop,bus,goblin").unwrap();
+        let bus = board.uci_to_move("a7a8bus").unwrap();
+        assert!(matches!(bus.move_type, MoveType::Promotion { into: PromotionTarget::Bus, .. }));
+        assert_eq!(board.move_to_uci(&bus), "a7a8bus");
+        assert_eq!(board.move_to_san(&bus).unwrap(), "a8=BUS");
+        let bishop = board.uci_to_move("a7a8b").unwrap();
+        assert!(matches!(bishop.move_type, MoveType::Promotion { into: PromotionTarget::Bishop, .. }));
+        assert_eq!(board.san_to_move("a8=G").unwrap(), board.uci_to_move("a7a8g").unwrap());
+    }
+
+    // ---------------------------------------------------------------
+    // Side-wide legal moves
+    // ---------------------------------------------------------------
+
+    fn per_square_legal_moves(board: &Board, color: Color) -> Vec<GameMove> {
+        board
+            .move_sources(color)
+            .iter()
+            .flat_map(|c| board.legal_moves(c))
+            .collect()
+    }
+
+    #[test]
+    fn test_all_legal_moves_matches_per_square_path() {
+        let fens = [
+            // A pinned pawn, a pinned knight and a checking rook.
+            "4k3/8/8/8/1b6/8/3P4/4K3 w - - 0 1",
+            "4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/R3K2r w Q - 0 1",
+            // En passant that would expose the king along the rank.
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+            "8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1",
+            // Fairy pieces and tiles go through the probe.
+            "4k3/8/8/8/3(P=BUS(P=(K)))4/8/8/4r3 w - - 0 1",
+            "4k3/8/8/8/8/2(C=TORNADO:3)5/8/R3K3 w - - 0 1",
+            "4k3/8/8/8/8/8/3(P=N,C=FROZEN)4/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 variants=crazyhouse pk=(W=N,B=q)",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 variants=antichess",
+        ];
+        for fen in fens {
+            let board = fen_to_board(fen).unwrap();
+            for color in [Color::White, Color::Black] {
+                let all = board.all_legal_moves(color);
+                assert_eq!(all, per_square_legal_moves(&board, color), "{fen}");
+                assert_eq!(board.has_legal_move(color), !all.is_empty(), "{fen}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_legal_moves_respects_pins_and_check() {
+        let pinned = fen_to_board("4k3/8/8/8/1b6/8/3P4/4K3 w - - 0 1").unwrap();
+        assert!(
+            pinned
+                .all_legal_moves(Color::White)
+                .iter()
+                .all(|m| m.from != sq("d2"))
+        );
+        let checked = fen_to_board("4k3/8/8/8/8/8/8/R3K2r w Q - 0 1").unwrap();
+        let moves = checked.all_legal_moves(Color::White);
+        assert!(moves.iter().all(|m| m.from == sq("e1")), "{moves:?}");
+    }
+
+    // ---------------------------------------------------------------
+    // Flat grid
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn test_flat_grid_matches_nested_layout() {
+        let mut board = empty_board_sized(5, 3);
+        board.grid[2][4] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        board.grid[0][1] = Square::new().set_piece(PieceType::new_knight(Color::Black));
+
+        let corner = Coord { file: 4, rank: 2 };
+        assert_eq!(board.get_square_at(&corner), Some(&board.grid[2][4]));
+        assert!(board.get_square_at(&Coord { file: 5, rank: 0 }).is_none());
+        assert!(board.get_square_at(&Coord { file: 0, rank: 3 }).is_none());
+
+        let pieces: Vec<(Coord, String)> = board
+            .iter_pieces()
+            .map(|(c, p)| (c, p.symbol().into_owned()))
+            .collect();
+        assert_eq!(
+            pieces,
+            vec![
+                (Coord { file: 1, rank: 0 }, "n".to_string()),
+                (corner, "R".to_string()),
+            ]
+        );
+
+        // JSON still sees a list of ranks, top first.
+        let json = serde_json::to_value(&board.grid).unwrap();
+        let ranks = json.as_array().unwrap();
+        assert_eq!(ranks.len(), 3);
+        assert!(ranks.iter().all(|r| r.as_array().unwrap().len() == 5));
+        let back: Board = serde_json::from_str(&serde_json::to_string(&board).unwrap()).unwrap();
+        assert_eq!(back, board);
+    }
+
+    #[test]
+    fn test_ragged_grid_is_rejected() {
+        let ragged = vec![vec![Square::new(); 3], vec![Square::new(); 2]];
+        let err = crate::board::grid::Grid::from_ranks(ragged).unwrap_err();
+        assert_eq!((err.rank, err.expected, err.found), (1, 3, 2));
+
+        let mut json = serde_json::to_value(empty_board_sized(3, 2)).unwrap();
+        json["grid"][1].as_array_mut().unwrap().pop();
+        assert!(serde_json::from_value::<Board>(json).is_err());
+    }
+
+    // ---------------------------------------------------------------
+    // Attack maps
+    // ---------------------------------------------------------------
+
+    /// Every cached answer on `board` agrees with the threat path run
+    /// on a copy whose maps were dropped. Warms `board`'s maps.
+    fn assert_attacks_match_fresh(board: &Board, context: &str) {
+        let mut fresh = board.clone();
+        fresh.grid.take_attacks();
+        for rank in 0..board.grid.len() as u8 {
+            for file in 0..board.grid.width() as u8 {
+                let at = Coord { file, rank };
+                for color in [Color::White, Color::Black] {
+                    assert_eq!(
+                        board.is_attacked_by(&at, color),
+                        fresh.is_attacked_by(&at, color),
+                        "{context}: {at:?} by {color:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_attack_maps_follow_make_and_unmake() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+            "8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1",
+            "4k3/8/8/8/3(P=BUS(P=(K)))4/8/8/4r3 w - - 0 1",
+            "4k3/8/3s4/2p5/5P2/4S3/1M6/(P=BUS)3K2(P=G(H=7-7)) w - -",
+            BRAINROT_WIN_FEN,
+            "4k3/8/2p1n3/3b4/2B1P3/2N5/8/6K1 w - - 0 1 tr=full p=0 variants=atomic",
+            "8/8/8/3kq3/8/3K4/8/7R w - - 0 1 tr=full p=0 variants=atomic",
+        ];
+        for fen in fens {
+            let board = fen_to_board(fen).unwrap();
+            for m in board.all_legal_moves(board.flags.side_to_move) {
+                let mut after = board.clone();
+                assert_attacks_match_fresh(&after, fen);
+                let undo = after.make_move_unchecked(m.clone()).unwrap();
+                assert_attacks_match_fresh(&after, &format!("{fen} after {m:?}"));
+                after.unmake_move(undo);
+                assert_eq!(after, board);
+                assert_attacks_match_fresh(&after, &format!("{fen} undoing {m:?}"));
+            }
+        }
+    }
+
+    /// A quiet move on a plain board forgets only the squares it could
+    /// have reached; a hand edit of the grid forgets everything.
+    #[test]
+    fn test_attack_maps_forget_locally_then_wholesale() {
+        let mut board =
+            fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_attacks_match_fresh(&board, "start");
+        let g1 = Coord { file: 6, rank: 7 };
+        board
+            .make_move_unchecked(GameMove {
+                from: g1.clone(),
+                move_type: MoveType::MoveTo(Coord { file: 5, rank: 5 }),
+            })
+            .unwrap();
+        let known = |board: &Board, file: usize, rank: usize| {
+            let sq = board.grid.index_of(file, rank).unwrap();
+            board.grid.attacks().get(sq, Color::White).is_some()
+        };
+        // a5 shares no line with g1 or f3 and is no knight's jump away.
+        assert!(known(&board, 0, 3));
+        assert!(!known(&board, 6, 3), "g5 is on g1's file");
+        assert!(!known(&board, 4, 3), "e5 is a knight's jump from f3");
+        assert_attacks_match_fresh(&board, "after Nf3");
+
+        board.grid[0][0] = Square::new();
+        assert!(!known(&board, 0, 3));
+    }
+
+    /// Opening a gate unblocks a rook: the gate's lines are forgotten
+    /// and the new one is seen, while squares off them stay known.
+    #[test]
+    fn test_attack_maps_see_a_gate_open() {
+        use crate::board::square::PressureTrigger;
+
+        let mut board = empty_board();
+        board.grid[2][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        board.grid[2][3] = Square::new().set_square_type(SquareType::Gate { id: 1, open: false });
+        board.grid[6][5] = Square::new().set_piece(PieceType::new_pawn(Color::White));
+        board.grid[5][5] = Square::new().set_square_type(SquareType::PressurePlate {
+            targets: vec![1],
+            fires_for: PressureTrigger::AnyPiece,
+        });
+        let beyond = Coord { file: 7, rank: 2 };
+        assert!(!board.is_attacked_by(&beyond, Color::White));
+        assert_attacks_match_fresh(&board, "gate closed");
+
+        board
+            .make_move_unchecked(GameMove {
+                from: Coord { file: 5, rank: 6 },
+                move_type: MoveType::MoveTo(Coord { file: 5, rank: 5 }),
+            })
+            .unwrap();
+        // b1 shares no line with the gate, f2 or f3.
+        let b1 = board.grid.index_of(1, 7).unwrap();
+        assert!(board.grid.attacks().get(b1, Color::White).is_some());
+        assert!(board.is_attacked_by(&beyond, Color::White));
+        assert_attacks_match_fresh(&board, "gate open");
+    }
+
+    /// A train rolling down its track each ply moves its threat along
+    /// with it, without the rest of the map being thrown away.
+    #[test]
+    fn test_attack_maps_follow_a_train() {
+        let mut board = board_with_idle_kings();
+        board.flags.train_tick_rate = TrainTickRate::EveryPly;
+        lay_east_track(&mut board, 3, 1, 6);
+        board.grid[3][1] = board.grid[3][1]
+            .clone()
+            .set_piece(PieceType::Locomotive(Locomotive::new(
+                1,
+                TrainHeading::Forward,
+            )));
+        assert_attacks_match_fresh(&board, "start");
+        for ply in 0..3 {
+            let king = board.find_king(board.flags.side_to_move).unwrap();
+            let file = if king.file == 0 { 1 } else { king.file - 1 };
+            board
+                .make_move_unchecked(GameMove {
+                    from: king.clone(),
+                    move_type: MoveType::MoveTo(Coord { file, rank: king.rank }),
+                })
+                .unwrap();
+            let kept = (0..64).any(|sq| board.grid.attacks().get(sq, Color::White).is_some());
+            assert!(kept, "ply {ply} forgot the whole map");
+            assert_attacks_match_fresh(&board, &format!("ply {ply}"));
+        }
+    }
+
+    /// Switching Atomic on by hand changes what a king attacks; maps
+    /// filled before the switch don't answer for the new rules.
+    #[test]
+    fn test_attack_maps_answer_for_their_ruleset() {
+        let mut board = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let d2 = Coord { file: 3, rank: 6 };
+        assert!(board.is_attacked_by(&d2, Color::White));
+        board.flags.variants.push(VariantId::Atomic);
+        assert!(!board.is_attacked_by(&d2, Color::White));
+        assert_attacks_match_fresh(&board, "atomic");
+    }
+
+    /// A probe hands `inspect` the board a validation apply would give,
+    /// with attack answers true of it, and puts the scratch back so the
+    /// next candidate starts from the original position.
+    #[test]
+    fn test_probe_move_reverts_between_candidates() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "4k3/8/3s4/2p5/5P2/4S3/1M6/(P=BUS)3K2(P=G(H=7-7)) w - -",
+            "8/8/8/3kq3/8/3K4/8/7R w - - 0 1 tr=full p=0 variants=atomic",
+        ];
+        for fen in fens {
+            let board = fen_to_board(fen).unwrap();
+            assert_attacks_match_fresh(&board, fen);
+            for m in board.all_legal_moves(board.flags.side_to_move) {
+                let mut expected = board.clone();
+                expected.apply_move_for_validation(&board, m.clone()).unwrap();
+                board.probe_move(&m, |probed| {
+                    let probed = probed.unwrap();
+                    assert_eq!(probed, &expected, "{fen} probing {m:?}");
+                    assert!(probed.history.hash.is_none());
+                    assert_attacks_match_fresh(probed, &format!("{fen} probing {m:?}"));
+                });
+            }
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use crate::board::Board;
-use crate::board::square::SquareCondition;
+use crate::board::square::{Square, SquareCondition};
 
 impl Board {
     /// Tick every `SquareCondition::Tornado` on the board down by one,
@@ -28,25 +28,22 @@ impl Board {
         if !crate::movement::stack::tornado::any_tornado(self) {
             return;
         }
-        for row in self.grid.iter_mut() {
-            for sq in row.iter_mut() {
-                // Frozen suspends the countdown for this square.
-                if sq.conditions.contains(&SquareCondition::Frozen) {
-                    continue;
-                }
-                let mut ticked = false;
-                for c in sq.conditions.iter_mut() {
-                    if let SquareCondition::Tornado { remaining } = c {
-                        *remaining = remaining.saturating_sub(1);
-                        ticked = true;
-                    }
-                }
-                if ticked {
-                    sq.conditions.retain(
-                        |c| !matches!(c, SquareCondition::Tornado { remaining: 0 }),
-                    );
+        // Only squares with a countdown to run are written.
+        let ticks = |sq: &Square| {
+            !sq.conditions.contains(&SquareCondition::Frozen)
+                && sq
+                    .conditions
+                    .iter()
+                    .any(|c| matches!(c, SquareCondition::Tornado { .. }))
+        };
+        self.grid.edit_matching(ticks, |sq| {
+            for c in sq.conditions.iter_mut() {
+                if let SquareCondition::Tornado { remaining } = c {
+                    *remaining = remaining.saturating_sub(1);
                 }
             }
-        }
+            sq.conditions
+                .retain(|c| !matches!(c, SquareCondition::Tornado { remaining: 0 }));
+        });
     }
 }
@@ -17,7 +17,7 @@ use crate::{
         Board, Coord, MoveType, TrainTickRate,
         square::{SquareType, TrackDir},
     },
-    movement::stack::capture::{ResolutionEvent, default_capture_stack},
+    movement::stack::capture::ResolutionEvent,
     pieces::{
         fairy::locomotive::TrainHeading,
         piecetype::PieceType,
@@ -249,7 +249,15 @@ impl Board {
     /// Returns `true` iff this call actually ticked the trains (i.e.
     /// the rate gate fired). Callers can use this to skip downstream
     /// recomputations like `recalc_brainrot` when nothing moved.
+    ///
+    /// A move that keeps the turn (the mover has a bonus move banked,
+    /// see `BoardFlags::extra_moves`) neither bumps `ply_count` nor
+    /// ticks: the trains see a turn with bonus moves as one ply.
     pub fn maybe_advance_trains(&mut self) -> bool {
+        if self.flags.extra_moves > 0 {
+            trace!(extra_moves = self.flags.extra_moves, "train tick gate: turn not over");
+            return false;
+        }
         // `saturating_add` means once `ply_count` hits `u32::MAX` it
         // stays there forever, and the modulo gate below may then
         // never satisfy the rate again (e.g. EveryFullTurn at
@@ -708,9 +716,9 @@ impl Board {
         // Forward-compat note: a handler emitting `BoardOp::RemovePiece`
         // on `captor_coord` would delete the just-placed locomotive,
         // desyncing the train chain tracked by `iter_pieces()`. No
-        // currently-registered handler does this — add a debug guard
-        // when the first BoardOp-emitting handler for train captures
-        // lands.
+        // currently-registered handler does this — Atomic's blast
+        // spares rolling stock for exactly this reason — add a debug
+        // guard if one ever needs to.
         for (victim_coord, captor, victim) in pending_captures {
             let event = ResolutionEvent::Capture {
                 captor_coord: victim_coord.clone(),
@@ -720,7 +728,7 @@ impl Board {
                 victim,
                 move_type: MoveType::MoveTo(victim_coord),
             };
-            for op in default_capture_stack().resolve_capture(self, &event) {
+            for op in self.rules().capture.resolve_capture(self, &event) {
                 op.apply(self);
             }
         }
@@ -0,0 +1,327 @@
+//! Long-algebraic move strings, UCI style: `e2e4`, `e7e8q`, `e1g1`.
+//!
+//! The machine-facing counterpart to `board::san`: no piece letters, no
+//! disambiguation, no check marks — just the from-square and what
+//! happens. Castling is written as the king's step to its castled
+//! square and en passant as the pawn's diagonal, as in UCI; a Chess960
+//! castle where the king moves less than two files is written as the
+//! king taking its own rook (`f1h1`), as in UCI_Chess960, and either
+//! form parses. The parser recognises castling and en passant from the
+//! piece on `from` (and the castling rights and en passant target),
+//! which with the side to move (for drops) is the only board state it
+//! reads besides the dimensions.
+//!
+//! The fairy move types extend the grammar after the from-square:
+//!
+//! | `MoveType`        | string       |
+//! |-------------------|--------------|
+//! | `MoveIntoCarrier` | `b1>c3`      |
+//! | `PieceInCarrier`  | `d4/2:d5`    |
+//! | nested            | `d4/2/0:>e5` |
+//! | `PhaseShift`      | `e4~`        |
+//! | `ThrowSwitch`     | `e4^`        |
+//! | `PlaceTornado`    | `g2*h3`      |
+//! | `MoveDuck`        | `d4&e5`      |
+//! | `PlaceDuck`       | `&e5`        |
+//! | `Drop`            | `N@e4`       |
+//!
+//! After the `:` of a passenger move comes the passenger's own action,
+//! without a from-square. A `ThrowSwitch` whose switch isn't the
+//! from-square names it after the `^` (`e4^e5`). `PlaceDuck` is the one
+//! move with no from-square: the duck isn't on the board yet, so the
+//! string starts at the `&` and parses back with `from` = the target.
+//! A `Drop` is written the same way, as the Crazyhouse `P@e4`: the
+//! piece's glyph, uppercased, then `@` and the target. It parses back
+//! as a piece of the side to move's colour, in its pocketed form. A
+//! promotion into a fairy piece ends in its glyph, lowercased, as the
+//! standard ones do: `e7e8g`, `e7e8bus`.
+
+use crate::{
+    board::crazyhouse::pocket_form,
+    board::chess960::castle_target_files,
+    board::{Board, CastleSide, Coord, GameMove, MoveType, PromotionTarget},
+    pieces::piecetype::PieceType,
+};
+
+/// Why `Board::uci_to_move` couldn't parse a string.
+#[derive(PartialEq, Debug, Clone)]
+pub enum UciError {
+    /// Nothing but whitespace.
+    EmptyInput,
+    /// Expected a square on this board at the start of this text.
+    BadSquare(String),
+    /// A promotion suffix that names no `PromotionTarget` (see
+    /// `promotion_suffix`).
+    BadPromotion(String),
+    /// A passenger index that isn't a `u8`.
+    BadPassengerIndex(String),
+    /// A drop of something that isn't a piece that can be pocketed.
+    BadDropPiece(String),
+    /// Text left over after a complete move.
+    TrailingInput(String),
+}
+
+impl std::fmt::Display for UciError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UciError::EmptyInput => write!(f, "Move string is empty."),
+            UciError::BadSquare(s) => write!(f, "Expected a square on this board at '{s}'."),
+            UciError::BadPromotion(s) => write!(f, "Unknown promotion piece '{s}'."),
+            UciError::BadPassengerIndex(s) => write!(f, "Bad passenger index '{s}'."),
+            UciError::BadDropPiece(s) => write!(f, "Can't drop '{s}'."),
+            UciError::TrailingInput(s) => write!(f, "Unexpected trailing input '{s}'."),
+        }
+    }
+}
+
+impl std::error::Error for UciError {}
+
+/// What follows a promotion's target square: the promoted piece's
+/// glyph, lowercased — `bus` for the Bus, one letter for the rest.
+fn promotion_suffix(into: &PromotionTarget) -> &'static str {
+    match into {
+        PromotionTarget::Queen => "q",
+        PromotionTarget::Rook => "r",
+        PromotionTarget::Bishop => "b",
+        PromotionTarget::Knight => "n",
+        PromotionTarget::Goblin => "g",
+        PromotionTarget::Skibidi => "s",
+        PromotionTarget::Monkey => "m",
+        PromotionTarget::Bus => "bus",
+        PromotionTarget::Stormcaller => "w",
+    }
+}
+
+impl Board {
+    /// Render `game_move` as a long-algebraic string. Purely
+    /// syntactic — the move need not be legal.
+    pub fn move_to_uci(&self, game_move: &GameMove) -> String {
+        if let MoveType::PlaceDuck { to } = &game_move.move_type {
+            return format!("&{}", self.format_coord(to));
+        }
+        if let MoveType::Drop { target, .. } = &game_move.move_type {
+            return self.uci_action(target, &game_move.move_type);
+        }
+        let mut out = self.format_coord(&game_move.from);
+        let mut move_type = &game_move.move_type;
+        if let MoveType::PieceInCarrier { .. } = move_type {
+            while let MoveType::PieceInCarrier {
+                piece_index,
+                move_type: inner,
+            } = move_type
+            {
+                out.push_str(&format!("/{piece_index}"));
+                move_type = inner.as_ref();
+            }
+            out.push(':');
+        }
+        out.push_str(&self.uci_action(&game_move.from, move_type));
+        out
+    }
+
+    /// What follows the from-square.
+    fn uci_action(&self, from: &Coord, move_type: &MoveType) -> String {
+        match move_type {
+            MoveType::MoveTo(to) | MoveType::EnPassant { target: to, .. } => self.format_coord(to),
+            MoveType::Promotion { target, into } => {
+                format!("{}{}", self.format_coord(target), promotion_suffix(into))
+            }
+            MoveType::Castle { side } => {
+                let king_file = castle_target_files(*side, self.width()).0;
+                let rook = self
+                    .get_square_at(from)
+                    .and_then(|sq| sq.piece.as_ref())
+                    .and_then(|king| self.castle_rook_square(king.get_color(), *side));
+                match rook {
+                    Some(rook) if king_file.abs_diff(from.file) < 2 => self.format_coord(&rook),
+                    _ => self.format_coord(&Coord {
+                        file: king_file,
+                        rank: from.rank,
+                    }),
+                }
+            }
+            MoveType::MoveIntoCarrier(to) => format!(">{}", self.format_coord(to)),
+            MoveType::PhaseShift => "~".to_string(),
+            MoveType::ThrowSwitch { switch } if switch == from => "^".to_string(),
+            MoveType::ThrowSwitch { switch } => format!("^{}", self.format_coord(switch)),
+            MoveType::PlaceTornado { target } => format!("*{}", self.format_coord(target)),
+            MoveType::PlaceDuck { to } | MoveType::MoveDuck { to } => {
+                format!("&{}", self.format_coord(to))
+            }
+            // The whole string; there's no from-square before it.
+            MoveType::Drop { piece, target } => {
+                let symbol = piece.symbol();
+                let glyph = symbol.split('(').next().unwrap_or_default();
+                format!("{}@{}", glyph.to_uppercase(), self.format_coord(target))
+            }
+            // Only reachable for a nested passenger move rendered on
+            // its own; `move_to_uci` unrolls the chain itself.
+            MoveType::PieceInCarrier { .. } => String::new(),
+        }
+    }
+
+    /// Parse a long-algebraic string back to the `GameMove` that
+    /// `move_to_uci` renders it from. Like the renderer it doesn't
+    /// check legality.
+    pub fn uci_to_move(&self, s: &str) -> Result<GameMove, UciError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(UciError::EmptyInput);
+        }
+        if let Some(rest) = s.strip_prefix('&') {
+            let (to, rest) = self.take_square(rest)?;
+            if !rest.is_empty() {
+                return Err(UciError::TrailingInput(rest.to_string()));
+            }
+            return Ok(GameMove {
+                from: to.clone(),
+                move_type: MoveType::PlaceDuck { to },
+            });
+        }
+        if let Some((glyph, rest)) = s.split_once('@') {
+            let piece = PieceType::symbol_to_piece(glyph)
+                .and_then(|p| pocket_form(&p, self.flags.side_to_move))
+                .ok_or_else(|| UciError::BadDropPiece(glyph.to_string()))?;
+            let (target, rest) = self.take_square(rest)?;
+            if !rest.is_empty() {
+                return Err(UciError::TrailingInput(rest.to_string()));
+            }
+            return Ok(GameMove {
+                from: target.clone(),
+                move_type: MoveType::Drop { piece, target },
+            });
+        }
+        let (from, rest) = self.take_square(s)?;
+        let (move_type, rest) = match rest.strip_prefix('/') {
+            Some(path) => {
+                let (indices, inner) = path
+                    .split_once(':')
+                    .ok_or_else(|| UciError::BadPassengerIndex(path.to_string()))?;
+                let (mut move_type, rest) = self.parse_action(&from, inner, false)?;
+                for index in indices.rsplit('/') {
+                    let piece_index = index
+                        .parse()
+                        .map_err(|_| UciError::BadPassengerIndex(index.to_string()))?;
+                    move_type = MoveType::PieceInCarrier {
+                        piece_index,
+                        move_type: std::sync::Arc::new(move_type),
+                    };
+                }
+                (move_type, rest)
+            }
+            None => self.parse_action(&from, rest, true)?,
+        };
+        if !rest.is_empty() {
+            return Err(UciError::TrailingInput(rest.to_string()));
+        }
+        Ok(GameMove { from, move_type })
+    }
+
+    /// Split a leading square off `s`.
+    fn take_square<'a>(&self, s: &'a str) -> Result<(Coord, &'a str), UciError> {
+        let len = s
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| !c.is_ascii_digit())
+            .map_or(s.len(), |(i, _)| i);
+        let coord = self
+            .parse_coord(&s[..len])
+            .ok_or_else(|| UciError::BadSquare(s.to_string()))?;
+        Ok((coord, &s[len..]))
+    }
+
+    /// Parse the action after `from`, returning the unconsumed rest.
+    /// `top_level` moves get the castling / en passant reading of a
+    /// plain destination; a passenger's never do.
+    fn parse_action<'a>(
+        &self,
+        from: &Coord,
+        s: &'a str,
+        top_level: bool,
+    ) -> Result<(MoveType, &'a str), UciError> {
+        if let Some(rest) = s.strip_prefix('~') {
+            return Ok((MoveType::PhaseShift, rest));
+        }
+        if let Some(rest) = s.strip_prefix('^') {
+            if rest.is_empty() {
+                return Ok((
+                    MoveType::ThrowSwitch {
+                        switch: from.clone(),
+                    },
+                    rest,
+                ));
+            }
+            let (switch, rest) = self.take_square(rest)?;
+            return Ok((MoveType::ThrowSwitch { switch }, rest));
+        }
+        if let Some(rest) = s.strip_prefix('*') {
+            let (target, rest) = self.take_square(rest)?;
+            return Ok((MoveType::PlaceTornado { target }, rest));
+        }
+        if let Some(rest) = s.strip_prefix('>') {
+            let (to, rest) = self.take_square(rest)?;
+            return Ok((MoveType::MoveIntoCarrier(to), rest));
+        }
+        if let Some(rest) = s.strip_prefix('&') {
+            let (to, rest) = self.take_square(rest)?;
+            return Ok((MoveType::MoveDuck { to }, rest));
+        }
+
+        let (to, rest) = self.take_square(s)?;
+        // `bus` before `b`, which it starts with.
+        let promotion = [PromotionTarget::Bus]
+            .into_iter()
+            .chain(PromotionTarget::STANDARD)
+            .chain([
+                PromotionTarget::Goblin,
+                PromotionTarget::Skibidi,
+                PromotionTarget::Monkey,
+                PromotionTarget::Stormcaller,
+            ])
+            .find_map(|into| {
+                let after = rest.strip_prefix(promotion_suffix(&into))?;
+                Some((into, after))
+            });
+        if let Some((into, after)) = promotion {
+            return Ok((MoveType::Promotion { target: to, into }, after));
+        }
+        if let Some(c) = rest.chars().next().filter(|c| c.is_ascii_alphabetic()) {
+            return Err(UciError::BadPromotion(c.to_string()));
+        }
+        if !top_level {
+            return Ok((MoveType::MoveTo(to), rest));
+        }
+
+        let piece = self.get_square_at(from).and_then(|sq| sq.piece.as_ref());
+        let move_type = match piece {
+            Some(PieceType::King(king)) if to.rank == from.rank && to.file != from.file => {
+                let side = if to.file > from.file {
+                    CastleSide::Kingside
+                } else {
+                    CastleSide::Queenside
+                };
+                let onto_rook = self.castle_rook_square(king.color, side).as_ref() == Some(&to);
+                let two_step = to.file.abs_diff(from.file) >= 2
+                    && castle_target_files(side, self.width()).0 == to.file;
+                if onto_rook || two_step {
+                    MoveType::Castle { side }
+                } else {
+                    MoveType::MoveTo(to)
+                }
+            }
+            Some(PieceType::Pawn(_))
+                if to.file != from.file && self.flags.en_passant_target.as_ref() == Some(&to) =>
+            {
+                MoveType::EnPassant {
+                    captured: Coord {
+                        file: to.file,
+                        rank: from.rank,
+                    },
+                    target: to,
+                }
+            }
+            _ => MoveType::MoveTo(to),
+        };
+        Ok((move_type, rest))
+    }
+}
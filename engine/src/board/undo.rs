@@ -0,0 +1,166 @@
+//! Reversible moves.
+//!
+//! `make_move` returns a `MoveUndo`; `unmake_move` consumes it and puts
+//! the board back exactly as it was. The record is a list of
+//! `BoardOp::RestoreSquare` ops (one per square the move changed) plus
+//! the pre-move `BoardFlags`. The ops come from the grid's journal
+//! (see `board::grid`): while the move applies, every square is saved
+//! the first time anything writes it — relocation, capture-stack ops,
+//! signal and gate flips, brainrot recalculation, tornado ticks, train
+//! advances — so nothing needs a per-site hook and nothing is diffed
+//! afterwards. The same journal advances the position hash
+//! (`Board::record_position`); the record keeps the hash it replaced
+//! and, when the move wiped it, the repetition history. The pre-move
+//! attack maps ride along too, so an unmade move leaves the cache as
+//! warm as it found it.
+//!
+//! `Board::probe_move`, the validation-phase hypothetical behind
+//! `validate_move` and `KingSafetyFilter`, applies the move to a
+//! reusable per-thread scratch board, journaling the writes as
+//! `make_move` does, and reverts it by writing back only the squares
+//! the journal saved, instead of cloning the whole grid for every
+//! candidate. Its attack
+//! maps are set aside for the probe and put back after it, so what
+//! they know about the position survives from one candidate to the
+//! next.
+
+use std::cell::RefCell;
+
+use crate::board::{
+    Board, BoardFlags, Coord, GameMove, attacks::AttackMaps, square::Square, zobrist::HistoryUndo,
+};
+use crate::movement::stack::capture::BoardOp;
+
+thread_local! {
+    /// Scratch board for `Board::probe_move`. Kept equal to the last
+    /// board it probed, so a run of probes against the same position
+    /// (every candidate of a `legal_moves` query) re-syncs by reading
+    /// the squares once and writing none. Taken out of the cell for
+    /// the duration of a probe: a probe nested inside another's
+    /// `inspect` finds it empty and falls back to a fresh clone.
+    static SCRATCH: RefCell<Option<Board>> = const { RefCell::new(None) };
+}
+
+/// Undo record for one `make_move`. Opaque: the only thing to do with
+/// it is hand it back to `Board::unmake_move` on the board that
+/// produced it, before any other move is made on that board (or after
+/// the later moves have been unmade in reverse order).
+#[derive(Debug, Clone)]
+pub struct MoveUndo {
+    flags: BoardFlags,
+    restore: Vec<BoardOp>,
+    history: HistoryUndo,
+    attacks: AttackMaps,
+}
+
+impl MoveUndo {
+    /// Build the record for a move just applied to `board`, from its
+    /// pre-move `flags` and attack maps, the grid journal's saved
+    /// squares and what recording the position changed.
+    pub(crate) fn new(
+        board: &Board,
+        flags: BoardFlags,
+        journal: Vec<(usize, Square)>,
+        history: HistoryUndo,
+        attacks: AttackMaps,
+    ) -> Self {
+        let restore = journal
+            .into_iter()
+            .map(|(index, square)| {
+                let (file, rank) = board.grid.file_rank(index);
+                BoardOp::RestoreSquare {
+                    at: Coord {
+                        file: file as u8,
+                        rank: rank as u8,
+                    },
+                    square,
+                }
+            })
+            .collect();
+        MoveUndo {
+            flags,
+            restore,
+            history,
+            attacks,
+        }
+    }
+
+    /// Number of squares the move changed.
+    pub fn squares_touched(&self) -> usize {
+        self.restore.len()
+    }
+}
+
+impl Board {
+    /// Revert the move `undo` was returned for. Squares go back first,
+    /// then the flags are restored wholesale — castle rights, en
+    /// passant, clocks, `last_move` included — the position hash and
+    /// repetition history are stepped back, and the attack maps put
+    /// back as they stood before the move.
+    pub fn unmake_move(&mut self, undo: MoveUndo) {
+        for op in &undo.restore {
+            op.apply(self);
+        }
+        self.flags = undo.flags;
+        self.unrecord_position(undo.history);
+        self.grid.set_attacks(undo.attacks);
+    }
+
+    /// Apply `game_move` through the validation phases (relocation,
+    /// capture stack, piece post-effects — no env reactions) to a
+    /// scratch copy of `self`, and hand the outcome to `inspect`. The
+    /// scratch is reverted afterwards, whatever `inspect` returned:
+    /// the journaled squares are written back, the pre-move attack maps
+    /// reinstalled and the position hash restored. `inspect` sees empty
+    /// maps, and the hash unseeded: the probe doesn't record the
+    /// position, so `position_hash` computes it afresh.
+    pub(crate) fn probe_move<R>(
+        &self,
+        game_move: &GameMove,
+        inspect: impl FnOnce(Result<&Board, String>) -> R,
+    ) -> R {
+        let mut scratch = match SCRATCH.with(|s| s.borrow_mut().take()) {
+            Some(mut scratch) => {
+                scratch.sync_from(self);
+                scratch
+            }
+            None => self.clone(),
+        };
+        let flags = scratch.flags.clone();
+        let hash = scratch.history.hash.take();
+        let attacks = scratch.grid.take_attacks();
+        scratch.grid.start_journal();
+        let applied = scratch.apply_move_for_validation(self, game_move.clone());
+        let journal = scratch.grid.take_journal();
+        let out = inspect(applied.map(|()| &scratch));
+        scratch.grid.rewind(journal, attacks);
+        scratch.flags = flags;
+        scratch.history.hash = hash;
+        SCRATCH.with(|s| *s.borrow_mut() = Some(scratch));
+        out
+    }
+
+    /// Make `self` equal to `source`. Squares are compared first and
+    /// only those that differ are written, so a scratch already equal
+    /// to `source` keeps its attack maps; otherwise it takes
+    /// `source`'s.
+    fn sync_from(&mut self, source: &Board) {
+        if self.grid != source.grid {
+            let same_shape = self.grid.width() == source.grid.width()
+                && self.grid.len() == source.grid.len();
+            if same_shape {
+                let squares = self.grid.squares_mut();
+                for (square, src) in squares.iter_mut().zip(source.grid.squares()) {
+                    if square != src {
+                        square.clone_from(src);
+                    }
+                }
+                self.grid.set_attacks(source.grid.attacks().clone());
+            } else {
+                self.grid.clone_from(&source.grid);
+            }
+        }
+        self.flags.clone_from(&source.flags);
+        self.history.clone_from(&source.history);
+    }
+}
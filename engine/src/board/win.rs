@@ -0,0 +1,430 @@
+//! Pluggable win conditions.
+//!
+//! `Board::status()` used to hard-code its decisive results: mate in
+//! standard chess, king capture and the stalemate win in Duck Chess. A
+//! game now picks its win conditions with the FEN `win=` field
+//! (`BoardFlags::win_conditions`), and its ruleset builds them into a
+//! `WinConditionRegistry` (`Rules::win`, see `movement::rules`).
+//! `status()` asks the registry first; the first condition that calls
+//! the game ends it, and only then do the stalemate and draw rules get
+//! a say.
+//!
+//! An empty `win=` list means the variant's default: checkmate, king
+//! capture under Duck Chess, either under Atomic, losing every piece
+//! under Antichess, with the hill added under King of the Hill and
+//! three checks under Three-check. An explicit list replaces the
+//! default rather than adding to it, so `variants=king_of_the_hill`
+//! plays as `win=checkmate,koth`. A game without `checkmate` in its list has no
+//! check either — kings may walk into attack and be taken, as in Duck
+//! Chess (see `RuleSet::enforces_check`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::square::SquareType;
+use crate::board::{Board, Coord, GameStatus, VariantId};
+use crate::movement::rules::{RuleSet, THREE_CHECK};
+use crate::pieces::{Color, piecetype::PieceType};
+
+/// A win condition a game can be played with. Listed in
+/// `BoardFlags::win_conditions`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum WinConditionId {
+    /// Mate the opposing king. Reported as `GameStatus::Checkmate`.
+    Checkmate,
+    /// Capture the opposing king.
+    KingCapture,
+    /// Capture every piece of one of the opponent's standard types —
+    /// all its pawns, say, or its only queen. Meant for full-army
+    /// starts: a side that begins without one of the six types has
+    /// already lost.
+    Extinction,
+    /// Bring your king to the hill (see `hill_squares`).
+    KingOfTheHill,
+    /// Give check this many times. The running counts are
+    /// `BoardFlags::white_checks` / `black_checks`.
+    NChecks(u8),
+    /// Lose every piece you have — the Antichess goal.
+    LoseAllPieces,
+}
+
+impl WinConditionId {
+    /// Identifier used in the FEN `win=` field: `checkmate`,
+    /// `king_capture`, `extinction`, `koth`, `<n>check` (`3check`), or
+    /// `lose_all`.
+    pub fn fen_tag(&self) -> String {
+        match self {
+            WinConditionId::Checkmate => "checkmate".to_string(),
+            WinConditionId::KingCapture => "king_capture".to_string(),
+            WinConditionId::Extinction => "extinction".to_string(),
+            WinConditionId::KingOfTheHill => "koth".to_string(),
+            WinConditionId::NChecks(n) => format!("{n}check"),
+            WinConditionId::LoseAllPieces => "lose_all".to_string(),
+        }
+    }
+
+    /// Inverse of `fen_tag`. `0check` is rejected — it would be won
+    /// before the first move.
+    pub fn parse_tag(s: &str) -> Option<Self> {
+        match s {
+            "checkmate" => Some(WinConditionId::Checkmate),
+            "king_capture" => Some(WinConditionId::KingCapture),
+            "extinction" => Some(WinConditionId::Extinction),
+            "koth" => Some(WinConditionId::KingOfTheHill),
+            "lose_all" => Some(WinConditionId::LoseAllPieces),
+            _ => s
+                .strip_suffix("check")
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| *n > 0)
+                .map(WinConditionId::NChecks),
+        }
+    }
+}
+
+/// Why a `GameStatus::Won` game was won.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum WinReason {
+    KingCapture,
+    Extinction,
+    KingOfTheHill,
+    NChecks,
+    LostAllPieces,
+    /// Duck Chess and Antichess: the side to move had no legal move,
+    /// which wins.
+    Stalemate,
+}
+
+/// One way a game can end decisively. `decide` is only asked about the
+/// current position and returns the finished status, or `None` when
+/// this condition hasn't ended the game.
+pub trait WinCondition: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn priority(&self) -> u32;
+
+    /// `stuck` says whether the side to move has no legal move at all,
+    /// computed once by `status()` for every condition.
+    fn decide(&self, board: &Board, stuck: bool) -> Option<GameStatus>;
+}
+
+/// The win conditions one ruleset plays by, in priority order. Built by
+/// `build_win_conditions`; tests can construct custom registries.
+pub struct WinConditionRegistry {
+    conditions: Vec<Box<dyn WinCondition>>,
+}
+
+impl Default for WinConditionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WinConditionRegistry {
+    pub fn new() -> Self {
+        Self {
+            conditions: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, c: Box<dyn WinCondition>) {
+        self.conditions.push(c);
+        // Stable sort by priority; same-priority preserves
+        // registration order.
+        self.conditions.sort_by_key(|c| c.priority());
+    }
+
+    pub fn len(&self) -> usize {
+        self.conditions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    pub fn condition_ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.conditions.iter().map(|c| c.id())
+    }
+
+    /// The first decisive result, in priority order.
+    pub fn decide(&self, board: &Board, stuck: bool) -> Option<GameStatus> {
+        self.conditions.iter().find_map(|c| c.decide(board, stuck))
+    }
+}
+
+/// Constructor for the win conditions `rules` plays by: one per entry
+/// of `RuleSet::win_conditions`, plus the stalemate win of Duck Chess
+/// and Antichess.
+pub(crate) fn build_win_conditions(rules: RuleSet) -> WinConditionRegistry {
+    let mut r = WinConditionRegistry::new();
+    for id in rules.win_conditions() {
+        match id {
+            WinConditionId::Checkmate => r.register(Box::new(CheckmateWin)),
+            WinConditionId::KingCapture => r.register(Box::new(KingCaptureWin)),
+            WinConditionId::Extinction => r.register(Box::new(ExtinctionWin)),
+            WinConditionId::KingOfTheHill => r.register(Box::new(KingOfTheHillWin)),
+            WinConditionId::NChecks(_) => r.register(Box::new(NChecksWin)),
+            WinConditionId::LoseAllPieces => r.register(Box::new(LoseAllPiecesWin)),
+        }
+    }
+    if rules.has(VariantId::DuckChess) || rules.has(VariantId::Antichess) {
+        r.register(Box::new(StalemateWin));
+    }
+    r
+}
+
+/// The side to move is stuck and in check.
+pub struct CheckmateWin;
+
+impl WinCondition for CheckmateWin {
+    fn id(&self) -> &'static str {
+        "win.checkmate"
+    }
+    fn priority(&self) -> u32 {
+        100
+    }
+    fn decide(&self, board: &Board, stuck: bool) -> Option<GameStatus> {
+        let to_move = board.flags.side_to_move;
+        (stuck && board.is_in_check(to_move)).then(|| GameStatus::Checkmate {
+            winner: to_move.opposite(),
+        })
+    }
+}
+
+/// Exactly one side still has a king. A king riding in a carrier
+/// counts, as it does for `find_king`.
+pub struct KingCaptureWin;
+
+impl WinCondition for KingCaptureWin {
+    fn id(&self) -> &'static str {
+        "win.king_capture"
+    }
+    fn priority(&self) -> u32 {
+        10
+    }
+    fn decide(&self, board: &Board, _stuck: bool) -> Option<GameStatus> {
+        let winner = match (
+            board.find_king(Color::White).is_some(),
+            board.find_king(Color::Black).is_some(),
+        ) {
+            (true, false) => Color::White,
+            (false, true) => Color::Black,
+            _ => return None,
+        };
+        Some(GameStatus::Won {
+            winner,
+            reason: WinReason::KingCapture,
+        })
+    }
+}
+
+/// A side has lost every piece of one standard type — to a capture, or
+/// by promoting its last pawn. Should one move leave both sides short,
+/// the side that made it wins.
+pub struct ExtinctionWin;
+
+impl ExtinctionWin {
+    fn extinct(board: &Board, color: Color) -> bool {
+        // King, queen, rook, bishop, knight, pawn — in that order.
+        let mut seen = [false; 6];
+        let mut mark = |piece: &PieceType| {
+            if piece.get_color() != color {
+                return;
+            }
+            let slot = match piece {
+                PieceType::King(_) => 0,
+                PieceType::Queen(_) => 1,
+                PieceType::Rook(_) => 2,
+                PieceType::Bishop(_) => 3,
+                PieceType::Knight(_) => 4,
+                PieceType::Pawn(_) => 5,
+                _ => return,
+            };
+            seen[slot] = true;
+        };
+        for (_, piece) in board.iter_pieces() {
+            mark(piece);
+            for passenger in piece.passengers().into_iter().flatten() {
+                mark(passenger);
+            }
+        }
+        !seen.iter().all(|s| *s)
+    }
+}
+
+impl WinCondition for ExtinctionWin {
+    fn id(&self) -> &'static str {
+        "win.extinction"
+    }
+    fn priority(&self) -> u32 {
+        20
+    }
+    fn decide(&self, board: &Board, _stuck: bool) -> Option<GameStatus> {
+        let to_move = board.flags.side_to_move;
+        [to_move, to_move.opposite()]
+            .into_iter()
+            .find(|c| Self::extinct(board, *c))
+            .map(|loser| GameStatus::Won {
+                winner: loser.opposite(),
+                reason: WinReason::Extinction,
+            })
+    }
+}
+
+/// A king stands on one of the hill squares. The side that just moved
+/// is checked first. A king riding in a carrier stands where
+/// `find_king` puts it, on the carrier's square: a Bus or train cart
+/// that stops on the hill with a king aboard wins for the king's side,
+/// whoever drove it there.
+pub struct KingOfTheHillWin;
+
+impl KingOfTheHillWin {
+    /// Does `piece` hold a `color` king, itself or as a passenger?
+    fn holds_king(piece: &PieceType, color: Color) -> bool {
+        let is_king = |p: &PieceType| matches!(p, PieceType::King(k) if k.color == color);
+        is_king(piece) || piece.passengers().into_iter().flatten().any(is_king)
+    }
+}
+
+impl WinCondition for KingOfTheHillWin {
+    fn id(&self) -> &'static str {
+        "win.king_of_the_hill"
+    }
+    fn priority(&self) -> u32 {
+        30
+    }
+    fn decide(&self, board: &Board, _stuck: bool) -> Option<GameStatus> {
+        let hill = hill_squares(board);
+        let to_move = board.flags.side_to_move;
+        [to_move.opposite(), to_move]
+            .into_iter()
+            .find(|c| {
+                hill.iter().any(|sq| {
+                    board
+                        .get_square_at(sq)
+                        .and_then(|s| s.piece.as_ref())
+                        .is_some_and(|p| Self::holds_king(p, *c))
+                })
+            })
+            .map(|winner| GameStatus::Won {
+                winner,
+                reason: WinReason::KingOfTheHill,
+            })
+    }
+}
+
+/// The hill: every `SquareType::Hill` square, rank by rank, or when the
+/// board has none its centre — the middle two files and ranks (d4, e4,
+/// d5 and e5 on 8×8), or the single middle file or rank when that
+/// dimension is odd.
+pub fn hill_squares(board: &Board) -> Vec<Coord> {
+    let marked: Vec<Coord> = (0..board.height())
+        .flat_map(|rank| (0..board.width()).map(move |file| Coord { file, rank }))
+        .filter(|c| {
+            board
+                .get_square_at(c)
+                .is_some_and(|s| matches!(s.square_type, SquareType::Hill))
+        })
+        .collect();
+    if !marked.is_empty() {
+        return marked;
+    }
+    let middle = |n: u8| {
+        let mut v = vec![n.saturating_sub(1) / 2, n / 2];
+        v.dedup();
+        v
+    };
+    let (files, ranks) = (middle(board.width()), middle(board.height()));
+    ranks
+        .iter()
+        .flat_map(|&rank| files.iter().map(move |&file| Coord { file, rank }))
+        .collect()
+}
+
+/// A side has given `Board::check_limit` checks. The counts are kept
+/// by `CheckCounterHandler`, registered alongside this condition.
+pub struct NChecksWin;
+
+impl WinCondition for NChecksWin {
+    fn id(&self) -> &'static str {
+        "win.n_checks"
+    }
+    fn priority(&self) -> u32 {
+        40
+    }
+    fn decide(&self, board: &Board, _stuck: bool) -> Option<GameStatus> {
+        [Color::White, Color::Black]
+            .into_iter()
+            .find(|c| board.flags.checks_given(*c) >= board.check_limit())
+            .map(|winner| GameStatus::Won {
+                winner,
+                reason: WinReason::NChecks,
+            })
+    }
+}
+
+impl Board {
+    /// The checks that win an `NChecks` game: the count of the last
+    /// `NChecks` in `flags.win_conditions`, or `THREE_CHECK` when none
+    /// is listed (Three-check's default) or it lists 0.
+    pub fn check_limit(&self) -> u8 {
+        let listed = self.flags.win_conditions.iter().rev().find_map(|w| match w {
+            WinConditionId::NChecks(n) => Some(*n),
+            _ => None,
+        });
+        match listed {
+            Some(0) | None => THREE_CHECK,
+            Some(n) => n,
+        }
+    }
+}
+
+/// A side that has no piece left — on the board, riding in a carrier,
+/// or in a Crazyhouse pocket — wins. Should one move leave both sides
+/// bare, the side to move wins.
+pub struct LoseAllPiecesWin;
+
+impl LoseAllPiecesWin {
+    fn bare(board: &Board, color: Color) -> bool {
+        let owns = |p: &PieceType| p.get_color() == color;
+        board.flags.pocket(color).is_empty()
+            && !board.iter_pieces().any(|(_, piece)| {
+                owns(piece) || piece.passengers().into_iter().flatten().any(owns)
+            })
+    }
+}
+
+impl WinCondition for LoseAllPiecesWin {
+    fn id(&self) -> &'static str {
+        "win.lose_all_pieces"
+    }
+    fn priority(&self) -> u32 {
+        25
+    }
+    fn decide(&self, board: &Board, _stuck: bool) -> Option<GameStatus> {
+        let to_move = board.flags.side_to_move;
+        [to_move, to_move.opposite()]
+            .into_iter()
+            .find(|c| Self::bare(board, *c))
+            .map(|winner| GameStatus::Won {
+                winner,
+                reason: WinReason::LostAllPieces,
+            })
+    }
+}
+
+/// Duck Chess and Antichess: a side to move with no legal move wins.
+/// Registered by those rulesets whatever their `win=` list says.
+pub struct StalemateWin;
+
+impl WinCondition for StalemateWin {
+    fn id(&self) -> &'static str {
+        "variant.stalemate_win"
+    }
+    fn priority(&self) -> u32 {
+        110
+    }
+    fn decide(&self, board: &Board, stuck: bool) -> Option<GameStatus> {
+        stuck.then_some(GameStatus::Won {
+            winner: board.flags.side_to_move,
+            reason: WinReason::Stalemate,
+        })
+    }
+}
@@ -0,0 +1,251 @@
+//! Position hashing for repetition detection.
+//!
+//! Zobrist-style: the position hash is the XOR of one pseudo-random
+//! 64-bit key per square plus one key for the hashed board flags. A
+//! classic Zobrist table indexes a precomputed random array by
+//! `(square, piece)`, which can't enumerate this engine's payload-
+//! carrying state — Goblin kidnap payloads, passenger lists, train IDs,
+//! Skibidi phases, Junction states, Tornado countdowns. Instead each
+//! key is derived on demand by running the square's full contents
+//! through a fixed-seed hasher: effectively an unbounded, lazily-
+//! evaluated Zobrist table. The XOR structure is what keeps the hash
+//! incremental — `make_move` re-keys only the squares whose contents
+//! changed (see `Board::record_position`).
+//!
+//! Deliberately NOT hashed: `ply_count` (monotonic — it would defeat
+//! repetition), the halfmove clock / fullmove number, and `last_move`.
+//! The train tick *phase* (`ply_count` modulo the tick rate) is hashed,
+//! since two otherwise-identical positions whose trains tick on
+//! different plies are different positions.
+
+use std::hash::{Hash, Hasher};
+
+use crate::board::{Board, BoardFlags, Coord, TrainTickRate, square::Square};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Key-space separators so a square key can never collide with the
+/// flags key (or a condition sub-key) by construction of the input.
+const DOMAIN_SQUARE: u8 = 1;
+const DOMAIN_CONDITION: u8 = 2;
+const DOMAIN_FLAGS: u8 = 3;
+
+/// FNV-1a accumulator with a splitmix64 finaliser. Deterministic across
+/// runs and processes (unlike `std`'s `RandomState`), so a hash is
+/// stable for the lifetime of a build. The finaliser avalanches FNV's
+/// weak low bits so XOR-combining keys stays well distributed.
+struct KeyHasher(u64);
+
+impl KeyHasher {
+    fn new(domain: u8) -> Self {
+        let mut h = KeyHasher(FNV_OFFSET);
+        h.write_u8(domain);
+        h
+    }
+}
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        splitmix64(self.0)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Key contributed by the square at `coord`. A pristine square (empty,
+/// `Standard`, no conditions, no duck) contributes 0, so the hash of a
+/// sparse board only pays for its occupied or decorated squares.
+///
+/// Conditions are combined order-independently: `recalc_brainrot`
+/// rebuilds `Brainrot` entries at the end of the list, so the same
+/// logical square can carry `[Frozen, Brainrot]` or `[Brainrot,
+/// Frozen]` depending on history.
+pub fn square_key(coord: &Coord, square: &Square) -> u64 {
+    if square.piece.is_none()
+        && square.square_type == crate::board::square::SquareType::Standard
+        && square.conditions.is_empty()
+        && !square.duck
+    {
+        return 0;
+    }
+    let conditions = square.conditions.iter().fold(0u64, |acc, c| {
+        let mut h = KeyHasher::new(DOMAIN_CONDITION);
+        c.hash(&mut h);
+        acc.wrapping_add(h.finish())
+    });
+    let mut h = KeyHasher::new(DOMAIN_SQUARE);
+    coord.hash(&mut h);
+    square.piece.hash(&mut h);
+    square.square_type.hash(&mut h);
+    square.duck.hash(&mut h);
+    conditions.hash(&mut h);
+    h.finish()
+}
+
+/// Key contributed by the position-relevant board flags.
+pub fn flags_key(flags: &BoardFlags) -> u64 {
+    let mut h = KeyHasher::new(DOMAIN_FLAGS);
+    flags.side_to_move.hash(&mut h);
+    flags.white_can_castle_kingside.hash(&mut h);
+    flags.white_can_castle_queenside.hash(&mut h);
+    flags.black_can_castle_kingside.hash(&mut h);
+    flags.black_can_castle_queenside.hash(&mut h);
+    flags.castle_rook_files.hash(&mut h);
+    flags.promotion_targets.hash(&mut h);
+    flags.en_passant_target.hash(&mut h);
+    flags.extra_moves.hash(&mut h);
+    flags.variants.hash(&mut h);
+    flags.duck_phase.hash(&mut h);
+    flags.win_conditions.hash(&mut h);
+    flags.white_checks.hash(&mut h);
+    flags.black_checks.hash(&mut h);
+    flags.white_pocket.hash(&mut h);
+    flags.black_pocket.hash(&mut h);
+    let (rate_tag, phase) = match flags.train_tick_rate {
+        TrainTickRate::EveryPly => (0u32, 0),
+        TrainTickRate::EveryFullTurn => (1, flags.ply_count % 2),
+        // Same `max(1)` clamp as the tick gate in `maybe_advance_trains`.
+        TrainTickRate::EveryNPly(n) => {
+            let n = u32::from(n).max(1);
+            (1 + n, flags.ply_count % n)
+        }
+    };
+    rate_tag.hash(&mut h);
+    phase.hash(&mut h);
+    h.finish()
+}
+
+/// A board's cached position hash and repetition history. Kept on
+/// `Board` beside the position rather than in `BoardFlags`: neither is
+/// part of the position, so neither belongs in the FEN, serde or
+/// equality.
+#[derive(Debug, Clone, Default)]
+pub struct PositionHistory {
+    /// The position hash. `None` until the first `make_move` seeds it;
+    /// advanced incrementally from then on. Read it through
+    /// `Board::position_hash`, which falls back to a full computation
+    /// while unseeded.
+    pub(crate) hash: Option<u64>,
+    /// Hashes of the positions reached since the last irreversible
+    /// move, oldest first, excluding the current one. Feeds the
+    /// threefold-repetition draw in `status()`.
+    pub(crate) seen: Vec<u64>,
+}
+
+/// What `Board::record_position` changed, for `unmake_move` to put
+/// back: the hash before the move and, if the move wiped the
+/// repetition history, the history it wiped.
+#[derive(Debug, Clone)]
+pub(crate) struct HistoryUndo {
+    hash: Option<u64>,
+    wiped: Option<Vec<u64>>,
+}
+
+impl Board {
+    /// The position hash: the cached incremental value once a move has
+    /// seeded it, a full computation before that.
+    pub fn position_hash(&self) -> u64 {
+        self.history
+            .hash
+            .unwrap_or_else(|| self.compute_position_hash())
+    }
+
+    /// Hash the whole position from scratch. The incremental value
+    /// cached in the board's history always equals this.
+    pub fn compute_position_hash(&self) -> u64 {
+        let mut h = flags_key(&self.flags);
+        for (rank, row) in self.grid.iter().enumerate() {
+            for (file, square) in row.iter().enumerate() {
+                let coord = Coord {
+                    file: file as u8,
+                    rank: rank as u8,
+                };
+                h ^= square_key(&coord, square);
+            }
+        }
+        h
+    }
+
+    /// Drop the cached hash. Call after editing `grid` or `flags`
+    /// directly between moves, so the next `make_move` re-seeds from
+    /// the actual contents instead of advancing a stale value.
+    pub fn invalidate_position_hash(&mut self) {
+        self.history.hash = None;
+    }
+
+    /// How many times the current position has occurred since the last
+    /// irreversible move, counting the current occurrence.
+    pub fn repetition_count(&self) -> usize {
+        let h = self.position_hash();
+        1 + self.history.seen.iter().filter(|&&seen| seen == h).count()
+    }
+
+    /// Advance the hash from `before_hash`, the position's hash before
+    /// the move, to `self`, and extend the repetition history. Called
+    /// once per real move, after env reactions and the side flip, so
+    /// the hash covers everything the move (and any train tick it
+    /// triggered) touched. Returns what `unrecord_position` needs to
+    /// take it back.
+    ///
+    /// `before_flags` are the pre-move flags, and `journal` the grid
+    /// journal's saved squares (see `board::grid`): every square the
+    /// move changed, as it was. Each is keyed out and its current
+    /// contents keyed in; no other square is looked at.
+    pub(crate) fn record_position(
+        &mut self,
+        before_hash: u64,
+        before_flags: &BoardFlags,
+        journal: &[(usize, Square)],
+    ) -> HistoryUndo {
+        let mut hash = before_hash ^ flags_key(before_flags) ^ flags_key(&self.flags);
+        for (index, saved) in journal {
+            let (file, rank) = self.grid.file_rank(*index);
+            let at = Coord {
+                file: file as u8,
+                rank: rank as u8,
+            };
+            hash ^= square_key(&at, saved) ^ square_key(&at, &self.grid.squares()[*index]);
+        }
+        let undo = HistoryUndo {
+            hash: self.history.hash.replace(hash),
+            wiped: None,
+        };
+
+        // Nothing before an irreversible move (pawn move or capture)
+        // can recur, so the history only spans the current halfmove
+        // clock.
+        if self.flags.halfmove_clock == 0 {
+            HistoryUndo {
+                wiped: Some(std::mem::take(&mut self.history.seen)),
+                ..undo
+            }
+        } else {
+            self.history.seen.push(before_hash);
+            undo
+        }
+    }
+
+    /// Take back the `record_position` that returned `undo`.
+    pub(crate) fn unrecord_position(&mut self, undo: HistoryUndo) {
+        self.history.hash = undo.hash;
+        match undo.wiped {
+            Some(seen) => self.history.seen = seen,
+            None => {
+                self.history.seen.pop();
+            }
+        }
+    }
+}
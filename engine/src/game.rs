@@ -0,0 +1,315 @@
+//! A game: a starting position plus the ordered moves played from it.
+//!
+//! `Board` only knows the position it is in — `BoardFlags::last_move`
+//! remembers a single move back. `Game` owns the whole line: every
+//! `GameMove`, the `LastMove` snapshot it produced and the status it
+//! left the game in, with undo/redo over the line and random access to
+//! any earlier position.
+//!
+//! Undo is `Board::unmake_move` on the current board, so stepping back
+//! costs only the squares the move touched. Redo replays the stored
+//! move. Pushing a new move after an undo discards the undone tail,
+//! like an editor's undo stack.
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, GameMove, GameStatus, LastMove, MoveError, undo::MoveUndo};
+
+/// One move of a `Game`, with what it produced.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct PlayedMove {
+    pub game_move: GameMove,
+    /// `BoardFlags::last_move` right after the move applied.
+    pub last_move: Option<LastMove>,
+    /// `Board::status()` of the position the move led to.
+    pub status: GameStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    start: Board,
+    current: Board,
+    /// The recorded line. `moves[..cursor]` have been played on
+    /// `current`; `moves[cursor..]` were undone and can be redone.
+    moves: Vec<PlayedMove>,
+    cursor: usize,
+    /// One undo record per played move, parallel to `moves[..cursor]`.
+    undos: Vec<MoveUndo>,
+}
+
+impl Game {
+    pub fn new(start: Board) -> Self {
+        Game {
+            current: start.clone(),
+            start,
+            moves: Vec::new(),
+            cursor: 0,
+            undos: Vec::new(),
+        }
+    }
+
+    /// The position the game started from.
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    /// The current position.
+    pub fn board(&self) -> &Board {
+        &self.current
+    }
+
+    /// Number of moves played to reach the current position.
+    pub fn ply(&self) -> usize {
+        self.cursor
+    }
+
+    /// The moves played to reach the current position, oldest first.
+    /// Undone moves waiting for `redo` are not included.
+    pub fn moves(&self) -> &[PlayedMove] {
+        &self.moves[..self.cursor]
+    }
+
+    /// Status of the current position.
+    pub fn status(&self) -> GameStatus {
+        match self.cursor.checked_sub(1) {
+            Some(last) => self.moves[last].status.clone(),
+            None => self.current.status(),
+        }
+    }
+
+    /// Play `game_move` on the current position. Discards any undone
+    /// moves that were waiting for `redo`. On error the game is
+    /// unchanged.
+    pub fn push_move(&mut self, game_move: GameMove) -> Result<&PlayedMove, MoveError> {
+        let undo = self.current.make_move(game_move.clone())?;
+        self.moves.truncate(self.cursor);
+        self.undos.push(undo);
+        self.moves.push(PlayedMove {
+            game_move,
+            last_move: self.current.flags.last_move.clone(),
+            status: self.current.status(),
+        });
+        self.cursor += 1;
+        Ok(&self.moves[self.cursor - 1])
+    }
+
+    /// Take back the last played move. Returns it, or `None` at the
+    /// start of the game.
+    pub fn undo(&mut self) -> Option<&PlayedMove> {
+        let undo = self.undos.pop()?;
+        self.current.unmake_move(undo);
+        self.cursor -= 1;
+        Some(&self.moves[self.cursor])
+    }
+
+    /// Replay the most recently undone move. Returns it, or `None` when
+    /// there is nothing to redo.
+    pub fn redo(&mut self) -> Option<&PlayedMove> {
+        let game_move = self.moves.get(self.cursor)?.game_move.clone();
+        match self.current.make_move(game_move) {
+            Ok(undo) => {
+                self.undos.push(undo);
+                self.cursor += 1;
+                Some(&self.moves[self.cursor - 1])
+            }
+            Err(err) => {
+                // Unreachable while `unmake_move` restores exactly: the
+                // move applied from this very position before. Drop
+                // the stale tail rather than leave it redoable.
+                tracing::warn!(%err, "redo failed; discarding the undone moves");
+                self.moves.truncate(self.cursor);
+                None
+            }
+        }
+    }
+
+    /// The position after the first `ply` recorded moves — `0` is the
+    /// start, `ply()` the current position. Undone moves still count as
+    /// recorded until a `push_move` discards them. `None` past the end
+    /// of the record.
+    pub fn position_at(&self, ply: usize) -> Option<Board> {
+        if ply == self.cursor {
+            return Some(self.current.clone());
+        }
+        if ply > self.moves.len() {
+            return None;
+        }
+        let mut board = self.start.clone();
+        for played in &self.moves[..ply] {
+            board.make_move(played.game_move.clone()).ok()?;
+        }
+        Some(board)
+    }
+
+    /// The played moves paired with the position each was made from,
+    /// oldest first.
+    pub fn iter(&self) -> GameIter<'_> {
+        GameIter {
+            board: self.start.clone(),
+            moves: self.moves().iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Game {
+    type Item = (Board, &'a GameMove);
+    type IntoIter = GameIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator returned by `Game::iter`. Replays the line on its own
+/// board, so each step costs one move application plus a clone of the
+/// yielded position.
+pub struct GameIter<'a> {
+    board: Board,
+    moves: std::slice::Iter<'a, PlayedMove>,
+}
+
+impl<'a> Iterator for GameIter<'a> {
+    type Item = (Board, &'a GameMove);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let played = self.moves.next()?;
+        let before = self.board.clone();
+        if let Err(err) = self.board.make_move(played.game_move.clone()) {
+            tracing::warn!(%err, "recorded move failed to replay; ending iteration");
+            self.moves = [].iter();
+            return None;
+        }
+        Some((before, &played.game_move))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Coord, MoveType, fen::fen_to_board};
+    use crate::pieces::Color;
+
+    fn start() -> Board {
+        fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap()
+    }
+
+    fn mv(from: (u8, u8), to: (u8, u8)) -> GameMove {
+        GameMove {
+            from: Coord {
+                file: from.0,
+                rank: from.1,
+            },
+            move_type: MoveType::MoveTo(Coord {
+                file: to.0,
+                rank: to.1,
+            }),
+        }
+    }
+
+    /// Fool's mate: f3 e5 g4 Qh4#.
+    fn fools_mate() -> [GameMove; 4] {
+        [
+            mv((5, 6), (5, 5)),
+            mv((4, 1), (4, 3)),
+            mv((6, 6), (6, 4)),
+            mv((3, 0), (7, 4)),
+        ]
+    }
+
+    #[test]
+    fn push_records_last_move_and_status() {
+        let mut game = Game::new(start());
+        for m in fools_mate() {
+            game.push_move(m).expect("legal");
+        }
+        assert_eq!(game.ply(), 4);
+        assert_eq!(
+            game.status(),
+            GameStatus::Checkmate {
+                winner: Color::Black
+            }
+        );
+        let played = &game.moves()[0];
+        assert_eq!(played.status, GameStatus::Ongoing);
+        let last = played.last_move.as_ref().expect("last_move recorded");
+        assert_eq!(last.mover_color, Color::White);
+        assert_eq!(last.primary_symbol, "P");
+    }
+
+    #[test]
+    fn illegal_push_leaves_game_unchanged() {
+        let mut game = Game::new(start());
+        game.push_move(mv((4, 6), (4, 4))).unwrap();
+        assert!(game.push_move(mv((4, 4), (4, 3))).is_err(), "black to move");
+        assert_eq!(game.ply(), 1);
+        assert_eq!(game.moves().len(), 1);
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_line() {
+        let mut game = Game::new(start());
+        let mut positions = vec![game.board().clone()];
+        for m in fools_mate() {
+            game.push_move(m).unwrap();
+            positions.push(game.board().clone());
+        }
+        for ply in (0..4).rev() {
+            let undone = game.undo().expect("a move to undo").game_move.clone();
+            assert_eq!(undone, fools_mate()[ply]);
+            assert_eq!(game.board(), &positions[ply]);
+        }
+        assert!(game.undo().is_none());
+        assert_eq!(game.status(), GameStatus::Ongoing);
+        for (ply, position) in positions.iter().enumerate().skip(1) {
+            game.redo().expect("a move to redo");
+            assert_eq!(game.ply(), ply);
+            assert_eq!(game.board(), position);
+        }
+        assert!(game.redo().is_none());
+    }
+
+    #[test]
+    fn push_after_undo_discards_the_redo_tail() {
+        let mut game = Game::new(start());
+        for m in fools_mate() {
+            game.push_move(m).unwrap();
+        }
+        game.undo();
+        game.undo();
+        game.push_move(mv((3, 6), (3, 4))).unwrap();
+        assert_eq!(game.ply(), 3);
+        assert!(game.redo().is_none());
+        assert_eq!(game.moves()[2].game_move, mv((3, 6), (3, 4)));
+    }
+
+    #[test]
+    fn position_at_replays_from_the_start() {
+        let mut game = Game::new(start());
+        let mut positions = vec![game.board().clone()];
+        for m in fools_mate() {
+            game.push_move(m).unwrap();
+            positions.push(game.board().clone());
+        }
+        game.undo();
+        for (ply, position) in positions.iter().enumerate() {
+            assert_eq!(game.position_at(ply).as_ref(), Some(position), "ply {ply}");
+        }
+        assert!(game.position_at(5).is_none());
+    }
+
+    #[test]
+    fn iter_pairs_each_move_with_its_position() {
+        let mut game = Game::new(start());
+        let mut positions = vec![game.board().clone()];
+        for m in fools_mate() {
+            game.push_move(m).unwrap();
+            positions.push(game.board().clone());
+        }
+        let pairs: Vec<_> = game.iter().collect();
+        assert_eq!(pairs.len(), 4);
+        for (i, (board, game_move)) in pairs.into_iter().enumerate() {
+            assert_eq!(board, positions[i]);
+            assert_eq!(*game_move, fools_mate()[i]);
+        }
+    }
+}
@@ -1,3 +1,6 @@
 pub mod board;
+pub mod game;
 mod movement;
+pub mod pgn;
 pub mod pieces;
+pub mod search;
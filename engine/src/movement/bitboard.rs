@@ -0,0 +1,727 @@
+//! Bitboard move generation for plain standard chess.
+//!
+//! Most positions the engine sees — the test corpus, opening play,
+//! perft — are ordinary chess on an 8×8 board, where the modifier
+//! pipeline does a lot of work to arrive at the answer a few shifts and
+//! masks give directly. `Position` is that answer: one `u64` per colour
+//! and piece kind, indexed like the flat grid (`rank * 8 + file`, rank
+//! 0 at the top).
+//!
+//! `MovementStack` hands its legal-move queries here when both its
+//! ruleset and the board allow (`rules_allow`, `Position::from_board`):
+//! standard pieces only, `SquareType::Standard` floor with no
+//! conditions and no duck, every promotion choice a standard piece, at
+//! most one king a side, and a game with check but none of the variants
+//! that change what a move or capture does. Anything else falls back to
+//! the pipeline.
+//!
+//! The rules are the pipeline's, not the textbook's where the two could
+//! differ on a hand-built position: castling follows `King::
+//! castle_moves` (a right, its rook on its side of the king, clear and
+//! unattacked path), any double push sets the en passant square, and a
+//! move is legal when the mover's king — if it has one — isn't attacked
+//! once it's made. The moves come back in square order rather than the
+//! pipeline's piece-by-piece order; the set is the same.
+
+use crate::board::chess960::castle_target_files;
+use crate::board::square::SquareType;
+use crate::board::win::WinConditionId;
+use crate::board::{Board, CastleSide, Coord, GameMove, MoveType, PromotionTarget, VariantId};
+use crate::movement::rules::RuleSet;
+use crate::pieces::Color;
+use crate::pieces::piecetype::PieceType;
+
+type Bitboard = u64;
+
+const PAWN: usize = 0;
+const KNIGHT: usize = 1;
+const BISHOP: usize = 2;
+const ROOK: usize = 3;
+const QUEEN: usize = 4;
+const KING: usize = 5;
+
+const WHITE: usize = 0;
+const BLACK: usize = 1;
+
+const SIDES: [CastleSide; 2] = [CastleSide::Kingside, CastleSide::Queenside];
+
+/// `(file, rank)` steps, rank 0 at the top. The order of the eight
+/// rays; even indices are the rook's.
+const DIRS: [(i8, i8); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+const KNIGHT_STEPS: [(i8, i8); 8] = [
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+    (1, -2),
+    (2, -1),
+];
+
+const fn step_table(steps: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [0; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        let mut i = 0;
+        while i < steps.len() {
+            let f = (sq % 8) as i8 + steps[i].0;
+            let r = (sq / 8) as i8 + steps[i].1;
+            if f >= 0 && f < 8 && r >= 0 && r < 8 {
+                table[sq] |= 1 << (r * 8 + f);
+            }
+            i += 1;
+        }
+        sq += 1;
+    }
+    table
+}
+
+const fn ray_table() -> [[Bitboard; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut d = 0;
+    while d < 8 {
+        let mut sq = 0;
+        while sq < 64 {
+            let mut f = (sq % 8) as i8 + DIRS[d].0;
+            let mut r = (sq / 8) as i8 + DIRS[d].1;
+            while f >= 0 && f < 8 && r >= 0 && r < 8 {
+                table[d][sq] |= 1 << (r * 8 + f);
+                f += DIRS[d].0;
+                r += DIRS[d].1;
+            }
+            sq += 1;
+        }
+        d += 1;
+    }
+    table
+}
+
+static KNIGHT_ATTACKS: [Bitboard; 64] = step_table(&KNIGHT_STEPS);
+static KING_ATTACKS: [Bitboard; 64] = step_table(&DIRS);
+/// The squares a pawn of each colour on a square attacks: white
+/// captures towards rank 0, black away from it.
+static PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    step_table(&[(-1, -1), (1, -1)]),
+    step_table(&[(-1, 1), (1, 1)]),
+];
+static RAYS: [[Bitboard; 64]; 8] = ray_table();
+
+/// The squares along ray `d` from `sq` up to and including the first
+/// occupied one.
+fn ray_attacks(d: usize, sq: usize, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[d][sq];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        return ray;
+    }
+    // Rays heading to higher indices meet their nearest blocker at the
+    // lowest set bit, the others at the highest.
+    let (df, dr) = DIRS[d];
+    let nearest = if dr * 8 + df > 0 {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+    ray ^ RAYS[d][nearest as usize]
+}
+
+fn rook_attacks(sq: usize, occupied: Bitboard) -> Bitboard {
+    (0..8)
+        .step_by(2)
+        .fold(0, |bb, d| bb | ray_attacks(d, sq, occupied))
+}
+
+fn bishop_attacks(sq: usize, occupied: Bitboard) -> Bitboard {
+    (1..8)
+        .step_by(2)
+        .fold(0, |bb, d| bb | ray_attacks(d, sq, occupied))
+}
+
+fn bit(sq: usize) -> Bitboard {
+    1 << sq
+}
+
+fn coord(sq: usize) -> Coord {
+    Coord {
+        file: (sq % 8) as u8,
+        rank: (sq / 8) as u8,
+    }
+}
+
+/// Iterate the set squares of `bb`, lowest first.
+fn squares(mut bb: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (bb != 0).then(|| {
+            let sq = bb.trailing_zeros() as usize;
+            bb &= bb - 1;
+            sq
+        })
+    })
+}
+
+fn colour_index(color: Color) -> Option<usize> {
+    match color {
+        Color::White => Some(WHITE),
+        Color::Black => Some(BLACK),
+        Color::Neutral => None,
+    }
+}
+
+fn colour_of(index: usize) -> Color {
+    if index == WHITE {
+        Color::White
+    } else {
+        Color::Black
+    }
+}
+
+fn back_rank(color: usize) -> usize {
+    if color == WHITE { 7 } else { 0 }
+}
+
+fn promotion_kind(target: &PromotionTarget) -> Option<usize> {
+    match target {
+        PromotionTarget::Queen => Some(QUEEN),
+        PromotionTarget::Rook => Some(ROOK),
+        PromotionTarget::Bishop => Some(BISHOP),
+        PromotionTarget::Knight => Some(KNIGHT),
+        _ => None,
+    }
+}
+
+/// Can a game played by `rules` use the fast path at all? It needs
+/// check, and none of the variants that add moves (drops, the duck) or
+/// change what a capture does (Atomic, Antichess, king capture).
+pub(crate) fn rules_allow(rules: RuleSet) -> bool {
+    rules.enforces_check()
+        && !rules.has_win(WinConditionId::KingCapture)
+        && ![
+            VariantId::DuckChess,
+            VariantId::Atomic,
+            VariantId::Crazyhouse,
+            VariantId::Antichess,
+        ]
+        .into_iter()
+        .any(|v| rules.has(v))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MoveKind {
+    Quiet,
+    DoublePush,
+    EnPassant,
+    /// Promotes into the given piece kind.
+    Promotion(usize),
+    Castle(CastleSide),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Move {
+    from: usize,
+    to: usize,
+    kind: MoveKind,
+}
+
+/// A plain standard-chess position as bitboards, with the flags its
+/// moves depend on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Position {
+    /// `[colour][kind]`.
+    pieces: [[Bitboard; 6]; 2],
+    colours: [Bitboard; 2],
+    side_to_move: usize,
+    /// `[colour][kingside, queenside]`: the file of the rook that side
+    /// castles with, while it holds the right.
+    castle_rooks: [[Option<u8>; 2]; 2],
+    en_passant: Option<usize>,
+    /// Bit per piece kind a pawn may promote into.
+    promotions: u8,
+}
+
+impl Position {
+    /// `board` as a `Position`, or `None` if it has anything the fast
+    /// path doesn't model (see the module docs). Doesn't look at the
+    /// ruleset; that's `rules_allow`.
+    pub(crate) fn from_board(board: &Board) -> Option<Position> {
+        if board.width() != 8 || board.grid.len() != 8 {
+            return None;
+        }
+        let mut pos = Position {
+            pieces: [[0; 6]; 2],
+            colours: [0; 2],
+            side_to_move: colour_index(board.flags.side_to_move)?,
+            castle_rooks: [[None; 2]; 2],
+            en_passant: None,
+            promotions: 0,
+        };
+        for (sq, square) in board.grid.squares().iter().enumerate() {
+            if square.square_type != SquareType::Standard
+                || !square.conditions.is_empty()
+                || square.duck
+            {
+                return None;
+            }
+            let Some(piece) = &square.piece else {
+                continue;
+            };
+            let kind = match piece {
+                PieceType::Pawn(_) => PAWN,
+                PieceType::Knight(_) => KNIGHT,
+                PieceType::Bishop(_) => BISHOP,
+                PieceType::Rook(_) => ROOK,
+                PieceType::Queen(_) => QUEEN,
+                PieceType::King(_) => KING,
+                _ => return None,
+            };
+            let color = colour_index(piece.get_color())?;
+            pos.pieces[color][kind] |= bit(sq);
+            pos.colours[color] |= bit(sq);
+        }
+        if pos.pieces.iter().any(|p| p[KING].count_ones() > 1) {
+            return None;
+        }
+        for color in [WHITE, BLACK] {
+            for (i, side) in SIDES.into_iter().enumerate() {
+                pos.castle_rooks[color][i] = board
+                    .castle_rook_square(colour_of(color), side)
+                    .map(|c| c.file);
+            }
+        }
+        pos.en_passant = board
+            .flags
+            .en_passant_target
+            .as_ref()
+            .filter(|c| c.file < 8 && c.rank < 8)
+            .map(|c| c.rank as usize * 8 + c.file as usize);
+        for target in board.flags.promotion_choices() {
+            pos.promotions |= 1 << promotion_kind(target)?;
+        }
+        Some(pos)
+    }
+
+    fn occupied(&self) -> Bitboard {
+        self.colours[WHITE] | self.colours[BLACK]
+    }
+
+    fn piece_at(&self, sq: usize) -> Option<(usize, usize)> {
+        let color = if self.colours[WHITE] & bit(sq) != 0 {
+            WHITE
+        } else if self.colours[BLACK] & bit(sq) != 0 {
+            BLACK
+        } else {
+            return None;
+        };
+        let kind = (PAWN..=KING).find(|&k| self.pieces[color][k] & bit(sq) != 0)?;
+        Some((color, kind))
+    }
+
+    /// Is `sq` attacked by a piece of colour `by`?
+    fn attacked(&self, sq: usize, by: usize) -> bool {
+        let theirs = &self.pieces[by];
+        let occupied = self.occupied();
+        // A pawn of `by` attacks `sq` from the squares a pawn of the
+        // other colour on `sq` would attack.
+        PAWN_ATTACKS[by ^ 1][sq] & theirs[PAWN] != 0
+            || KNIGHT_ATTACKS[sq] & theirs[KNIGHT] != 0
+            || KING_ATTACKS[sq] & theirs[KING] != 0
+            || bishop_attacks(sq, occupied) & (theirs[BISHOP] | theirs[QUEEN]) != 0
+            || rook_attacks(sq, occupied) & (theirs[ROOK] | theirs[QUEEN]) != 0
+    }
+
+    fn in_check(&self, color: usize) -> bool {
+        let king = self.pieces[color][KING];
+        king != 0 && self.attacked(king.trailing_zeros() as usize, color ^ 1)
+    }
+
+    fn remove(&mut self, sq: usize) -> Option<(usize, usize)> {
+        let (color, kind) = self.piece_at(sq)?;
+        self.pieces[color][kind] &= !bit(sq);
+        self.colours[color] &= !bit(sq);
+        Some((color, kind))
+    }
+
+    fn put(&mut self, sq: usize, color: usize, kind: usize) {
+        self.pieces[color][kind] |= bit(sq);
+        self.colours[color] |= bit(sq);
+    }
+
+    /// Drop whichever castling right names the rook square `sq` of
+    /// `color`.
+    fn clear_castle_rook(&mut self, color: usize, sq: usize) {
+        if sq / 8 != back_rank(color) {
+            return;
+        }
+        for right in &mut self.castle_rooks[color] {
+            if *right == Some((sq % 8) as u8) {
+                *right = None;
+            }
+        }
+    }
+
+    /// The position after `mv`, which must be pseudo-legal here.
+    fn make(&self, mv: Move) -> Position {
+        let mut next = *self;
+        let Some((color, kind)) = next.remove(mv.from) else {
+            return next;
+        };
+        let captured_at = match mv.kind {
+            MoveKind::EnPassant => Some((mv.from / 8) * 8 + mv.to % 8),
+            MoveKind::Castle(_) => None,
+            _ => Some(mv.to),
+        };
+        if let Some(at) = captured_at
+            && let Some((victim, ROOK)) = next.remove(at)
+        {
+            next.clear_castle_rook(victim, at);
+        }
+        match mv.kind {
+            MoveKind::Castle(side) => {
+                let (king_file, rook_file) = castle_target_files(side, 8);
+                let rank = back_rank(color) * 8;
+                let rook_from =
+                    rank + next.castle_rooks[color][side as usize].unwrap_or(0) as usize;
+                next.remove(rook_from);
+                next.put(rank + king_file as usize, color, KING);
+                next.put(rank + rook_file as usize, color, ROOK);
+            }
+            MoveKind::Promotion(into) => next.put(mv.to, color, into),
+            _ => next.put(mv.to, color, kind),
+        }
+        match kind {
+            KING => next.castle_rooks[color] = [None; 2],
+            ROOK => next.clear_castle_rook(color, mv.from),
+            _ => {}
+        }
+        next.en_passant = (mv.kind == MoveKind::DoublePush).then(|| (mv.from + mv.to) / 2);
+        next.side_to_move ^= 1;
+        next
+    }
+
+    /// Does `mv` leave its mover's king, if it has one, unattacked?
+    fn is_legal(&self, mv: Move, color: usize) -> bool {
+        !self.make(mv).in_check(color)
+    }
+
+    /// Push the pseudo-legal moves of the piece on `from`, one per
+    /// promotion kind.
+    fn pseudo_moves_from(&self, from: usize, out: &mut Vec<Move>) {
+        let Some((color, kind)) = self.piece_at(from) else {
+            return;
+        };
+        let occupied = self.occupied();
+        let not_own = !self.colours[color];
+        let targets = match kind {
+            PAWN => {
+                self.pawn_moves(from, color, out);
+                return;
+            }
+            KNIGHT => KNIGHT_ATTACKS[from],
+            BISHOP => bishop_attacks(from, occupied),
+            ROOK => rook_attacks(from, occupied),
+            QUEEN => bishop_attacks(from, occupied) | rook_attacks(from, occupied),
+            _ => KING_ATTACKS[from],
+        };
+        out.extend(squares(targets & not_own).map(|to| Move {
+            from,
+            to,
+            kind: MoveKind::Quiet,
+        }));
+        if kind == KING {
+            self.castle_moves(from, color, out);
+        }
+    }
+
+    fn pawn_moves(&self, from: usize, color: usize, out: &mut Vec<Move>) {
+        let occupied = self.occupied();
+        let (promotion_rank, start_rank) = if color == WHITE { (0, 6) } else { (7, 1) };
+        let push = |to: usize, out: &mut Vec<Move>| {
+            if to / 8 == promotion_rank {
+                out.extend(
+                    [QUEEN, ROOK, BISHOP, KNIGHT]
+                        .into_iter()
+                        .filter(|k| self.promotions & (1 << k) != 0)
+                        .map(|k| Move {
+                            from,
+                            to,
+                            kind: MoveKind::Promotion(k),
+                        }),
+                );
+            } else {
+                out.push(Move {
+                    from,
+                    to,
+                    kind: MoveKind::Quiet,
+                });
+            }
+        };
+        let forward = |sq: usize| {
+            if color == WHITE {
+                sq.checked_sub(8)
+            } else {
+                Some(sq + 8).filter(|&s| s < 64)
+            }
+        };
+        if let Some(one) = forward(from).filter(|&s| occupied & bit(s) == 0) {
+            push(one, out);
+            if from / 8 == start_rank
+                && let Some(two) = forward(one).filter(|&s| occupied & bit(s) == 0)
+            {
+                out.push(Move {
+                    from,
+                    to: two,
+                    kind: MoveKind::DoublePush,
+                });
+            }
+        }
+        for to in squares(PAWN_ATTACKS[color][from]) {
+            if self.colours[color ^ 1] & bit(to) != 0 {
+                push(to, out);
+            } else if self.en_passant == Some(to) && occupied & bit(to) == 0 {
+                out.push(Move {
+                    from,
+                    to,
+                    kind: MoveKind::EnPassant,
+                });
+            }
+        }
+    }
+
+    /// `King::castle_moves` on bitboards.
+    fn castle_moves(&self, from: usize, color: usize, out: &mut Vec<Move>) {
+        let rank = back_rank(color);
+        if from / 8 != rank || self.in_check(color) {
+            return;
+        }
+        let king_file = (from % 8) as u8;
+        let on_rank = |file: u8| rank * 8 + file as usize;
+        let span = |a: u8, b: u8| a.min(b)..=a.max(b);
+        for (i, side) in SIDES.into_iter().enumerate() {
+            let Some(rook_file) = self.castle_rooks[color][i] else {
+                continue;
+            };
+            let (king_to, rook_to) = castle_target_files(side, 8);
+            let on_its_side = match side {
+                CastleSide::Kingside => rook_file > king_file,
+                CastleSide::Queenside => rook_file < king_file,
+            };
+            if !on_its_side || self.pieces[color][ROOK] & bit(on_rank(rook_file)) == 0 {
+                continue;
+            }
+            let clear = span(king_file, king_to)
+                .chain(span(rook_file, rook_to))
+                .filter(|&f| f != king_file && f != rook_file)
+                .all(|f| self.occupied() & bit(on_rank(f)) == 0);
+            let path_safe = span(king_file, king_to)
+                .filter(|&f| f != king_file)
+                .all(|f| !self.attacked(on_rank(f), color ^ 1));
+            if clear && path_safe {
+                out.push(Move {
+                    from,
+                    to: on_rank(king_to),
+                    kind: MoveKind::Castle(side),
+                });
+            }
+        }
+    }
+
+    /// The legal moves of the piece on `from`, as `GameMove`s, with
+    /// promotions in `choices` order.
+    pub(crate) fn legal_moves_from(
+        &self,
+        from: &Coord,
+        choices: &[PromotionTarget],
+        out: &mut Vec<GameMove>,
+    ) {
+        if from.file >= 8 || from.rank >= 8 {
+            return;
+        }
+        let sq = from.rank as usize * 8 + from.file as usize;
+        let Some((color, _)) = self.piece_at(sq) else {
+            return;
+        };
+        let mut moves = Vec::new();
+        self.pseudo_moves_from(sq, &mut moves);
+        for mv in moves {
+            // The promoted piece doesn't change whether the move is
+            // legal, so test the first choice and emit them all from it.
+            let promotion = matches!(mv.kind, MoveKind::Promotion(_));
+            if promotion
+                && choices
+                    .first()
+                    .and_then(promotion_kind)
+                    .map(MoveKind::Promotion)
+                    != Some(mv.kind)
+            {
+                continue;
+            }
+            if !self.is_legal(mv, color) {
+                continue;
+            }
+            let target = coord(mv.to);
+            if promotion {
+                out.extend(choices.iter().map(|into| GameMove {
+                    from: from.clone(),
+                    move_type: MoveType::Promotion {
+                        target: target.clone(),
+                        into: into.clone(),
+                    },
+                }));
+                continue;
+            }
+            let move_type = match mv.kind {
+                MoveKind::EnPassant => MoveType::EnPassant {
+                    captured: Coord {
+                        file: target.file,
+                        rank: from.rank,
+                    },
+                    target,
+                },
+                MoveKind::Castle(side) => MoveType::Castle { side },
+                _ => MoveType::MoveTo(target),
+            };
+            out.push(GameMove {
+                from: from.clone(),
+                move_type,
+            });
+        }
+    }
+
+    /// Does the piece on `from` have a legal move?
+    pub(crate) fn has_legal_move_from(&self, from: &Coord) -> bool {
+        if from.file >= 8 || from.rank >= 8 {
+            return false;
+        }
+        let sq = from.rank as usize * 8 + from.file as usize;
+        let Some((color, _)) = self.piece_at(sq) else {
+            return false;
+        };
+        let mut moves = Vec::new();
+        self.pseudo_moves_from(sq, &mut moves);
+        moves.into_iter().any(|mv| self.is_legal(mv, color))
+    }
+
+    /// Leaf positions `depth` plies of legal play below this one.
+    pub(crate) fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let color = self.side_to_move;
+        let mut moves = Vec::with_capacity(64);
+        for from in squares(self.colours[color]) {
+            self.pseudo_moves_from(from, &mut moves);
+        }
+        moves
+            .into_iter()
+            .filter_map(|mv| {
+                let next = self.make(mv);
+                (!next.in_check(color)).then_some(next)
+            })
+            .map(|next| if depth == 1 { 1 } else { next.perft(depth - 1) })
+            .sum()
+    }
+}
+
+impl Board {
+    /// Perft: the number of leaf positions `depth` plies of legal play
+    /// below this one. A position the bitboard fast path covers is
+    /// counted entirely on bitboards; any other walks the tree with
+    /// `make_move` / `unmake_move` on a copy.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if rules_allow(self.ruleset())
+            && let Some(pos) = Position::from_board(self)
+        {
+            return pos.perft(depth);
+        }
+        fn walk(board: &mut Board, depth: u32) -> u64 {
+            if depth == 0 {
+                return 1;
+            }
+            let mut count = 0;
+            for m in board.all_legal_moves(board.flags.side_to_move) {
+                let undo = board
+                    .make_move(m)
+                    .expect("all_legal_moves output must apply cleanly via make_move");
+                count += walk(board, depth - 1);
+                board.unmake_move(undo);
+            }
+            count
+        }
+        walk(&mut self.clone(), depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::fen::fen_to_board;
+    use crate::movement::stack::build_stack;
+
+    /// The stack `board` plays by, with the fast path switched off.
+    fn pipeline_moves(board: &Board) -> Vec<GameMove> {
+        let mut stack = build_stack(board.ruleset());
+        stack.bitboard = false;
+        let sources = board.move_sources(board.flags.side_to_move);
+        stack.resolve_all_legal_moves(board, &sources)
+    }
+
+    fn sorted(mut moves: Vec<GameMove>) -> Vec<String> {
+        let mut out: Vec<String> = moves.drain(..).map(|m| format!("{m:?}")).collect();
+        out.sort();
+        out
+    }
+
+    fn assert_matches_pipeline(board: &Board) {
+        assert!(Position::from_board(board).is_some());
+        assert_eq!(
+            sorted(board.all_legal_moves(board.flags.side_to_move)),
+            sorted(pipeline_moves(board)),
+            "{}",
+            crate::board::fen::board_to_fen(board)
+        );
+    }
+
+    /// Each position, and every one a move into it, gets the same moves
+    /// from the bitboards as from the pipeline. The last position is
+    /// full of promotions, pins and castling through attack.
+    #[test]
+    fn fast_path_matches_pipeline() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - -",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ] {
+            let mut board = fen_to_board(fen).unwrap();
+            assert_matches_pipeline(&board);
+            for m in board.all_legal_moves(board.flags.side_to_move) {
+                let undo = board.make_move(m).unwrap();
+                assert_matches_pipeline(&board);
+                board.unmake_move(undo);
+            }
+        }
+    }
+
+    #[test]
+    fn fairy_positions_fall_back() {
+        let fairy = fen_to_board("4k3/8/8/8/8/8/1M6/4K3 w - -").unwrap();
+        assert!(Position::from_board(&fairy).is_none());
+        let atomic =
+            fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 variants=atomic").unwrap();
+        assert!(Position::from_board(&atomic).is_some());
+        assert!(!rules_allow(atomic.ruleset()));
+        let wide = fen_to_board("4k5/10/10/10/10/10/10/4K5 w - -").unwrap();
+        assert!(Position::from_board(&wide).is_none());
+    }
+}
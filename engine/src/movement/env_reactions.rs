@@ -24,9 +24,10 @@
 // reading them.
 #![allow(dead_code)]
 
-use std::sync::OnceLock;
-
 use crate::board::Board;
+use crate::board::win::WinConditionId;
+use crate::movement::rules::RuleSet;
+use crate::pieces::Color;
 
 /// When in the per-move pipeline a handler fires. Names follow the
 /// "from the player's perspective" convention — `PreMover` happens
@@ -35,13 +36,15 @@ use crate::board::Board;
 /// player's piece relocates and post-effects fire, but BEFORE the
 /// environment ticks.
 ///
-/// The order is: PostMover → TickGate → PostTick. PreMover currently
-/// has no handlers and is reserved for the "start of opponent's
-/// turn" handlers Magnet et al want.
+/// The order is: PostMover → TickGate → PostTick, then PreMover once
+/// the turn has passed. PreMover's only handler so far is the
+/// three-check counter; it's also where the "start of opponent's
+/// turn" handlers Magnet et al want will go.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum EnvPhase {
-    /// Before the current side's turn officially begins. Unused in
-    /// v1; reserved for Magnet, Bell-Ringer, etc.
+    /// Before the current side's turn officially begins. Counts
+    /// checks for `NChecks` games; reserved for Magnet, Bell-Ringer,
+    /// etc.
     PreMover,
     /// After the player's piece move applied + piece post-effects
     /// fired, but before the train tick. Reserved for Boy Who
@@ -88,9 +91,9 @@ pub trait EnvReactionHandler: Send + Sync {
     fn apply(&self, board: &mut Board, ctx: &mut EnvReactionCtx);
 }
 
-/// Registry holding all environment-reaction handlers. Built at
-/// engine init via `default_registry()`; tests can construct
-/// custom registries.
+/// Registry holding all environment-reaction handlers. Built once per
+/// ruleset by `build_registry` (see `movement::rules`); tests can
+/// construct custom registries.
 pub struct EnvReactionRegistry {
     handlers: Vec<Box<dyn EnvReactionHandler>>,
 }
@@ -150,19 +153,28 @@ impl EnvReactionRegistry {
     }
 }
 
-/// Process-wide default registry. Currently registers the train tick
-/// and the post-tick brainrot recalc; future plans add Magnet,
-/// Bell-Ringer, etc.
+/// The registry for a board with no variant (`RuleSet::STANDARD`).
+/// Production code goes through `Board::rules()`.
 pub fn default_registry() -> &'static EnvReactionRegistry {
-    static REG: OnceLock<EnvReactionRegistry> = OnceLock::new();
-    REG.get_or_init(build_default_registry)
+    &RuleSet::STANDARD.rules().env
 }
 
-fn build_default_registry() -> EnvReactionRegistry {
+/// Constructor for the registry `rules` plays by. Every ruleset
+/// registers the train tick, the tornado countdown and the post-tick
+/// brainrot recalc; one won by `NChecks` also counts checks. Future
+/// plans add Magnet, Bell-Ringer, etc.
+pub(crate) fn build_registry(rules: RuleSet) -> EnvReactionRegistry {
     let mut r = EnvReactionRegistry::new();
     r.register(Box::new(TrainTickHandler));
     r.register(Box::new(TornadoTickHandler));
     r.register(Box::new(BrainrotRecalcHandler));
+    if rules
+        .win_conditions()
+        .iter()
+        .any(|w| matches!(w, WinConditionId::NChecks(_)))
+    {
+        r.register(Box::new(CheckCounterHandler));
+    }
     r
 }
 
@@ -272,10 +284,41 @@ impl EnvReactionHandler for TornadoTickHandler {
     }
 }
 
+/// Counts checks for the `NChecks` win condition. Runs at `PreMover`,
+/// after the side flip, so the side to move is the one that may have
+/// been checked: if it is, the side that just moved gets a check. A
+/// move that banks a bonus move never gives check (the bonus is
+/// forfeited instead), so every check reaches this phase.
+pub struct CheckCounterHandler;
+
+impl EnvReactionHandler for CheckCounterHandler {
+    fn id(&self) -> &'static str {
+        "env.check_counter"
+    }
+    fn phase(&self) -> EnvPhase {
+        EnvPhase::PreMover
+    }
+    fn priority(&self) -> u32 {
+        100
+    }
+    fn apply(&self, board: &mut Board, _ctx: &mut EnvReactionCtx) {
+        let checked = board.flags.side_to_move;
+        if !board.is_in_check(checked) {
+            return;
+        }
+        let checks = match checked.opposite() {
+            Color::White => &mut board.flags.white_checks,
+            Color::Black => &mut board.flags.black_checks,
+            Color::Neutral => return,
+        };
+        *checks = checks.saturating_add(1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::board::{BoardFlags, TrainTickRate};
+    use crate::board::{BoardFlags, DuckPhase, TrainTickRate};
     use crate::board::square::{Square, SquareCondition};
     use crate::pieces::Color;
     use std::sync::Arc;
@@ -285,20 +328,32 @@ mod tests {
         let grid = (0..8)
             .map(|_| (0..8).map(|_| Square::new()).collect())
             .collect();
-        Board {
+        Board::new(
             grid,
-            flags: BoardFlags {
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     /// Counting handler with PER-INSTANCE atomic. Per-test counters
@@ -505,8 +560,8 @@ mod tests {
         let mut ctx = EnvReactionCtx::default();
         TornadoTickHandler.apply(&mut board, &mut ctx);
         assert_eq!(
-            board.grid[0][0].conditions,
-            vec![SquareCondition::Tornado { remaining: 2 }]
+            board.grid[0][0].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 2 }]
         );
     }
 
@@ -537,8 +592,8 @@ mod tests {
         let mut ctx = EnvReactionCtx::default();
         TornadoTickHandler.apply(&mut board, &mut ctx);
         assert_eq!(
-            board.grid[1][1].conditions,
-            vec![
+            board.grid[1][1].conditions.as_slice(),
+            [
                 SquareCondition::Frozen,
                 SquareCondition::Tornado { remaining: 2 }
             ],
@@ -613,8 +668,8 @@ mod tests {
 
         // The :1 decremented to 0 and was removed; the :3 → :2 survives.
         assert_eq!(
-            board.grid[1][1].conditions,
-            vec![SquareCondition::Tornado { remaining: 2 }],
+            board.grid[1][1].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 2 }],
             "each Tornado ticks independently; zero-reaching ones are dropped"
         );
     }
@@ -59,7 +59,7 @@ pub fn generate_glider_moves(
             // an actual blocker rather than visual decoration.
             let sq = board.get_square_at(&coord);
             if !sq
-                .map(|s| s.square_type.is_walkable())
+                .map(|s| s.is_walkable())
                 .unwrap_or(false)
             {
                 break;
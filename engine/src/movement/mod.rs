@@ -1,3 +1,5 @@
+pub mod bitboard;
 pub mod env_reactions;
 pub mod glider;
+pub mod rules;
 pub mod stack;
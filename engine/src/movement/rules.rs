@@ -0,0 +1,339 @@
+//! Per-board rule profiles.
+//!
+//! The three registries a move runs through — the `MovementStack`, the
+//! `CaptureStack` and the `EnvReactionRegistry` — used to be one
+//! process-wide `OnceLock` each, so every board in the process played
+//! by the same rules. A `RuleSet` names the rule modules a board plays
+//! by, read off its flags (`BoardFlags::variants` and
+//! `win_conditions`), and `Rules` is the registries built for it, plus
+//! the `WinConditionRegistry` `status()` consults. Each distinct ruleset is
+//! built once and cached for the life of the process, so a Duck Chess
+//! board and a standard one can be queried side by side without either
+//! paying for the other's modifiers.
+//!
+//! A variant plugs in by checking `RuleSet::has` in the `build_*`
+//! constructors of the registries it touches — registering its own
+//! modifiers, or leaving out ones that don't apply (Duck Chess has no
+//! `KingSafetyFilter`). Behaviour that only differs inside an existing
+//! modifier can still read the board's flags directly.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use crate::board::win::{WinConditionId, WinConditionRegistry, build_win_conditions};
+use crate::board::{Board, BoardFlags, VariantId};
+use crate::movement::env_reactions::{EnvReactionRegistry, build_registry};
+use crate::movement::stack::capture::{CaptureStack, build_capture_stack};
+use crate::movement::stack::{MovementStack, build_stack};
+
+/// The number of checks that wins Three-check.
+pub const THREE_CHECK: u8 = 3;
+
+/// The rule variants and win conditions a board plays by. Bitsets over
+/// `VariantId` and `WinConditionId`, so it's `Copy` and
+/// order-insensitive: `variants=a,b` and `variants=b,a` share one
+/// cached `Rules`. An `NChecks` count isn't part of it — games to three
+/// checks and to five share one `Rules`, and `NChecksWin` reads the
+/// count off the board (`Board::check_limit`) — so there are at most
+/// 2^6 × 2^6 distinct rulesets.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct RuleSet {
+    variants: u32,
+    /// Explicitly listed win conditions; 0 leaves the variants'
+    /// default in force (see `win_conditions`).
+    win: u32,
+}
+
+impl RuleSet {
+    /// Standard chess plus every fairy piece and tile — no variant,
+    /// won by checkmate.
+    pub const STANDARD: RuleSet = RuleSet {
+        variants: 0,
+        win: 0,
+    };
+
+    /// The ruleset a position with these flags plays by.
+    pub fn for_flags(flags: &BoardFlags) -> Self {
+        let rs = flags
+            .variants
+            .iter()
+            .fold(Self::STANDARD, |rs, v| rs.with(*v));
+        flags
+            .win_conditions
+            .iter()
+            .fold(rs, |rs, w| rs.with_win(*w))
+    }
+
+    /// This ruleset with `variant` switched on.
+    pub fn with(self, variant: VariantId) -> Self {
+        RuleSet {
+            variants: self.variants | variant_bit(variant),
+            ..self
+        }
+    }
+
+    pub fn has(self, variant: VariantId) -> bool {
+        self.variants & variant_bit(variant) != 0
+    }
+
+    /// This ruleset with `win` added to its explicit win conditions.
+    /// An `NChecks` count is dropped; see the type docs.
+    pub fn with_win(self, win: WinConditionId) -> Self {
+        RuleSet {
+            win: self.win | win_bit(win),
+            ..self
+        }
+    }
+
+    /// The win conditions in force: the explicit list, or when there is
+    /// none the variants' default — king capture under Duck Chess,
+    /// checkmate or king capture under Atomic, losing every piece under
+    /// Antichess, checkmate otherwise, plus the hill under King of the
+    /// Hill and three checks under Three-check. An `NChecks` always
+    /// reads `NChecks(THREE_CHECK)` here; the board knows the real
+    /// count.
+    pub fn win_conditions(self) -> Vec<WinConditionId> {
+        let bits = self.win_bits();
+        [
+            WinConditionId::Checkmate,
+            WinConditionId::KingCapture,
+            WinConditionId::Extinction,
+            WinConditionId::KingOfTheHill,
+            WinConditionId::NChecks(THREE_CHECK),
+            WinConditionId::LoseAllPieces,
+        ]
+        .into_iter()
+        .filter(|w| bits & win_bit(*w) != 0)
+        .collect()
+    }
+
+    /// Is `win` among `win_conditions`? An `NChecks` matches whatever
+    /// its count.
+    pub fn has_win(self, win: WinConditionId) -> bool {
+        self.win_bits() & win_bit(win) != 0
+    }
+
+    fn win_bits(self) -> u32 {
+        if self.win != 0 {
+            return self.win;
+        }
+        let mut bits = self.base_win_bits();
+        if self.has(VariantId::KingOfTheHill) {
+            bits |= win_bit(WinConditionId::KingOfTheHill);
+        }
+        if self.has(VariantId::ThreeCheck) {
+            bits |= win_bit(WinConditionId::NChecks(THREE_CHECK));
+        }
+        bits
+    }
+
+    /// The default of the variants that replace checkmate.
+    fn base_win_bits(self) -> u32 {
+        if self.has(VariantId::DuckChess) {
+            win_bit(WinConditionId::KingCapture)
+        } else if self.has(VariantId::Atomic) {
+            win_bit(WinConditionId::Checkmate) | win_bit(WinConditionId::KingCapture)
+        } else if self.has(VariantId::Antichess) {
+            win_bit(WinConditionId::LoseAllPieces)
+        } else {
+            win_bit(WinConditionId::Checkmate)
+        }
+    }
+
+    /// Does this game have check? Only when it can be won by
+    /// checkmate: otherwise a king may walk into attack and be taken,
+    /// so there's no `KingSafetyFilter`, castling ignores attacked
+    /// squares and `status()` never reports `Check`.
+    pub fn enforces_check(self) -> bool {
+        self.has_win(WinConditionId::Checkmate)
+    }
+
+    /// The registries for this ruleset, built on first use.
+    pub fn rules(self) -> &'static Rules {
+        // The standard ruleset is by far the most queried; keep it off
+        // the shared map's lock.
+        if self == Self::STANDARD {
+            static STANDARD: OnceLock<Rules> = OnceLock::new();
+            return STANDARD.get_or_init(|| Rules::build(self));
+        }
+        static CACHE: OnceLock<RwLock<HashMap<RuleSet, &'static Rules>>> = OnceLock::new();
+        let cache = CACHE.get_or_init(Default::default);
+        if let Some(rules) = cache.read().unwrap_or_else(|e| e.into_inner()).get(&self) {
+            return rules;
+        }
+        let mut cache = cache.write().unwrap_or_else(|e| e.into_inner());
+        // Leaked once per distinct ruleset — bounded, since each is
+        // just a subset of `VariantId` and one of `WinConditionId`.
+        cache
+            .entry(self)
+            .or_insert_with(|| Box::leak(Box::new(Rules::build(self))))
+    }
+}
+
+fn variant_bit(variant: VariantId) -> u32 {
+    match variant {
+        VariantId::DuckChess => 1 << 0,
+        VariantId::Atomic => 1 << 1,
+        VariantId::Crazyhouse => 1 << 2,
+        VariantId::Antichess => 1 << 3,
+        VariantId::KingOfTheHill => 1 << 4,
+        VariantId::ThreeCheck => 1 << 5,
+    }
+}
+
+fn win_bit(win: WinConditionId) -> u32 {
+    match win {
+        WinConditionId::Checkmate => 1 << 0,
+        WinConditionId::KingCapture => 1 << 1,
+        WinConditionId::Extinction => 1 << 2,
+        WinConditionId::KingOfTheHill => 1 << 3,
+        WinConditionId::NChecks(_) => 1 << 4,
+        WinConditionId::LoseAllPieces => 1 << 5,
+    }
+}
+
+/// The registries one ruleset plays by.
+pub struct Rules {
+    pub movement: MovementStack,
+    pub capture: CaptureStack,
+    pub env: EnvReactionRegistry,
+    pub win: WinConditionRegistry,
+}
+
+impl Rules {
+    fn build(ruleset: RuleSet) -> Self {
+        Rules {
+            movement: build_stack(ruleset),
+            capture: build_capture_stack(ruleset),
+            env: build_registry(ruleset),
+            win: build_win_conditions(ruleset),
+        }
+    }
+}
+
+impl Board {
+    /// The ruleset this position plays by.
+    pub fn ruleset(&self) -> RuleSet {
+        RuleSet::for_flags(&self.flags)
+    }
+
+    /// The registries for `ruleset()`.
+    pub fn rules(&self) -> &'static Rules {
+        self.ruleset().rules()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::GameStatus;
+    use crate::board::fen::fen_to_board;
+    use crate::board::win::WinReason;
+    use crate::pieces::Color;
+
+    #[test]
+    fn ruleset_follows_variants_flag() {
+        let standard = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let duck =
+            fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 variants=duck_chess").unwrap();
+        assert_eq!(standard.ruleset(), RuleSet::STANDARD);
+        assert!(duck.ruleset().has(VariantId::DuckChess));
+        assert!(std::ptr::eq(standard.rules(), RuleSet::STANDARD.rules()));
+        assert!(std::ptr::eq(duck.rules(), duck.ruleset().rules()), "cached");
+        assert!(!std::ptr::eq(standard.rules(), duck.rules()));
+    }
+
+    #[test]
+    fn duck_chess_stack_swaps_king_safety_for_the_duck() {
+        let standard: Vec<_> = RuleSet::STANDARD.rules().movement.modifier_ids().collect();
+        let duck: Vec<_> = RuleSet::STANDARD
+            .with(VariantId::DuckChess)
+            .rules()
+            .movement
+            .modifier_ids()
+            .collect();
+        assert!(standard.contains(&"king_safety"));
+        assert!(!standard.contains(&"variant.duck_phase"));
+        assert!(!duck.contains(&"king_safety"));
+        assert_eq!(duck.first(), Some(&"variant.duck_phase"));
+    }
+
+    #[test]
+    fn antichess_drops_check_and_compels_captures() {
+        let anti = RuleSet::STANDARD.with(VariantId::Antichess);
+        assert_eq!(anti.win_conditions(), vec![WinConditionId::LoseAllPieces]);
+        assert!(!anti.enforces_check());
+        let stack: Vec<_> = anti.rules().movement.modifier_ids().collect();
+        assert!(!stack.contains(&"king_safety"));
+        assert_eq!(stack.last(), Some(&"variant.antichess_captures"));
+        let wins: Vec<_> = anti.rules().win.condition_ids().collect();
+        assert_eq!(wins, vec!["win.lose_all_pieces", "variant.stalemate_win"]);
+    }
+
+    #[test]
+    fn crazyhouse_registers_drops_and_pockets() {
+        let zh = RuleSet::STANDARD.with(VariantId::Crazyhouse).rules();
+        assert!(zh.movement.modifier_ids().any(|id| id == "variant.crazyhouse_drops"));
+        assert!(zh.capture.modifier_ids().any(|id| id == "variant.crazyhouse_pocket"));
+        let standard = RuleSet::STANDARD.rules();
+        assert!(!standard.capture.modifier_ids().any(|id| id == "variant.crazyhouse_pocket"));
+    }
+
+    /// The check count lives on the board, not in the ruleset: every
+    /// N-check game shares one cached `Rules`, and each still wins at
+    /// its own count.
+    #[test]
+    fn check_counts_share_one_rules() {
+        let game = |win: &str, checks: &str| {
+            fen_to_board(&format!(
+                "4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 win={win} checks={checks}"
+            ))
+            .unwrap()
+        };
+        let three = game("3check", "2+0");
+        let five = game("5check", "3+0");
+        assert!(std::ptr::eq(three.rules(), five.rules()));
+        assert_eq!(three.check_limit(), 3);
+        assert_eq!(five.check_limit(), 5);
+        assert_eq!(five.status(), GameStatus::Ongoing);
+        assert_eq!(
+            game("5check", "5+0").status(),
+            GameStatus::Won {
+                winner: Color::White,
+                reason: WinReason::NChecks,
+            }
+        );
+    }
+
+    #[test]
+    fn win_conditions_default_per_variant_and_follow_win_flag() {
+        let standard = RuleSet::STANDARD;
+        let duck = standard.with(VariantId::DuckChess);
+        assert_eq!(standard.win_conditions(), vec![WinConditionId::Checkmate]);
+        assert_eq!(duck.win_conditions(), vec![WinConditionId::KingCapture]);
+        assert!(standard.enforces_check());
+        assert!(!duck.enforces_check());
+        assert_eq!(
+            standard.with(VariantId::Atomic).win_conditions(),
+            vec![WinConditionId::Checkmate, WinConditionId::KingCapture]
+        );
+
+        let three_check =
+            fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 win=3check,checkmate").unwrap();
+        assert_eq!(
+            three_check.ruleset().win_conditions(),
+            vec![WinConditionId::Checkmate, WinConditionId::NChecks(3)]
+        );
+        let ids: Vec<_> = three_check.rules().win.condition_ids().collect();
+        assert_eq!(ids, vec!["win.n_checks", "win.checkmate"]);
+        let env: Vec<_> = three_check.rules().env.handler_ids().collect();
+        assert!(env.contains(&"env.check_counter"));
+        let standard_env: Vec<_> = RuleSet::STANDARD.rules().env.handler_ids().collect();
+        assert!(!standard_env.contains(&"env.check_counter"));
+
+        let koth = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1 tr=full p=0 win=koth").unwrap();
+        assert!(!koth.ruleset().enforces_check());
+        let stack: Vec<_> = koth.rules().movement.modifier_ids().collect();
+        assert!(!stack.contains(&"king_safety"));
+    }
+}
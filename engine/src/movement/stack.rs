@@ -21,7 +21,11 @@
 // callers in.
 #![allow(dead_code)]
 
+pub mod antichess;
+pub mod atomic;
 pub mod capture;
+pub mod crazyhouse;
+pub mod duck;
 pub mod king_safety;
 pub mod piece_attacks;
 pub mod piece_moves;
@@ -30,27 +34,38 @@ pub mod tornado;
 pub mod train_modifiers;
 
 use std::cell::Cell;
-use std::sync::OnceLock;
 
-use crate::board::{Board, Coord, GameMove};
+use crate::board::{Board, Coord, GameMove, VariantId};
+use crate::movement::bitboard::{self, Position};
+use crate::movement::rules::RuleSet;
 use crate::pieces::Color;
 use crate::pieces::piecetype::PieceType;
 
 thread_local! {
     /// Audit Round-A/A-DoS: monotonic per-thread counter bumped once
-    /// at the top of each `resolve_legal_moves` call (the *only* path
-    /// that runs the priority-305 `TornadoCompulsionFilter`). The
+    /// at the top of each `resolve_legal_moves` call, or once for a
+    /// whole side-wide sweep (`resolve_all_legal_moves`,
+    /// `any_legal_move`) — the *only* paths that run the priority-305
+    /// `TornadoCompulsionFilter`. The
     /// filter memoizes its board-invariant probe
     /// (`any_tornado` + `side_can_reach_tornado`) keyed by this epoch,
     /// so the O(pieces×moves×king-safety) reachability probe runs once
     /// per legal-move query instead of once per *candidate* — closing
     /// the super-linear amplification a crafted tornado FEN could
-    /// exploit. Bumped ONLY here: `resolve_moves`/`resolve_moves_capped`
+    /// exploit. Bumped ONLY by those: `resolve_moves`/`resolve_moves_capped`
     /// don't run the 305 filter, and the capped probe inside the filter
     /// must NOT bump it (or the memo would never hit). Thread-local ⇒
     /// concurrency-safe (no cross-request sharing). Wrap after 2^64
     /// queries is unreachable in practice.
     static RESOLVE_LEGAL_EPOCH: Cell<u64> = const { Cell::new(0) };
+
+    /// Is a legal-move resolve running on this thread right now? Set
+    /// for the duration of the three epoch-opening calls by
+    /// `LegalEpochGuard`. Lets `KingSafetyFilter` tell a probe inside
+    /// one (same board as the epoch) from a bare `resolve_moves_capped`
+    /// call made outside any, where the current epoch belongs to some
+    /// earlier board.
+    static LEGAL_EPOCH_ACTIVE: Cell<bool> = const { Cell::new(false) };
 }
 
 /// Current resolve-legal epoch (read by `TornadoCompulsionFilter`'s
@@ -59,6 +74,36 @@ pub(crate) fn resolve_legal_epoch() -> u64 {
     RESOLVE_LEGAL_EPOCH.with(|e| e.get())
 }
 
+/// The current resolve-legal epoch while a legal-move resolve is
+/// running, `None` outside one.
+pub(crate) fn active_legal_epoch() -> Option<u64> {
+    LEGAL_EPOCH_ACTIVE
+        .with(|a| a.get())
+        .then(resolve_legal_epoch)
+}
+
+/// Opens a fresh epoch and marks it active until dropped, restoring
+/// the previous mark — so an early return or a panic can't leave it
+/// set.
+struct LegalEpochGuard {
+    was_active: bool,
+}
+
+impl LegalEpochGuard {
+    fn open() -> Self {
+        RESOLVE_LEGAL_EPOCH.with(|e| e.set(e.get().wrapping_add(1)));
+        LegalEpochGuard {
+            was_active: LEGAL_EPOCH_ACTIVE.with(|a| a.replace(true)),
+        }
+    }
+}
+
+impl Drop for LegalEpochGuard {
+    fn drop(&mut self) {
+        LEGAL_EPOCH_ACTIVE.with(|a| a.set(self.was_active));
+    }
+}
+
 /// Discriminator for `MovementEvent` variants. Powers the `touches()`
 /// fast-path: a modifier that doesn't touch a given event kind is
 /// skipped without a virtual call.
@@ -240,10 +285,15 @@ pub trait MovementModifier: Send + Sync {
     fn apply(&self, board: &Board, event: &MovementEvent) -> MovementEffect;
 }
 
-/// The registry itself. Built at engine init via `default_stack()`;
-/// custom stacks can be constructed manually for tests.
+/// The registry itself. Built once per ruleset by `build_stack` (see
+/// `movement::rules`); custom stacks can be constructed manually for
+/// tests.
 pub struct MovementStack {
     modifiers: Vec<Box<dyn MovementModifier>>,
+    /// Answer legal-move queries from `bitboard::Position` when the
+    /// board allows. Set by `build_stack` for rulesets the fast path
+    /// covers; hand-built stacks always run their modifiers.
+    pub(crate) bitboard: bool,
 }
 
 impl Default for MovementStack {
@@ -254,7 +304,10 @@ impl Default for MovementStack {
 
 impl MovementStack {
     pub fn new() -> Self {
-        Self { modifiers: Vec::new() }
+        Self {
+            modifiers: Vec::new(),
+            bitboard: false,
+        }
     }
 
     /// Register a modifier. The registry is kept sorted by priority
@@ -375,7 +428,62 @@ impl MovementStack {
         // RESOLVE_LEGAL_EPOCH). The board is immutable for the duration
         // of this `resolve`, so the tornado filter's board-invariant
         // probe is computed once and reused across every candidate.
-        RESOLVE_LEGAL_EPOCH.with(|e| e.set(e.get().wrapping_add(1)));
+        if let Some(pos) = self.fast_path(board) {
+            let mut moves = Vec::new();
+            pos.legal_moves_from(from, board.flags.promotion_choices(), &mut moves);
+            return moves;
+        }
+        let _epoch = LegalEpochGuard::open();
+        self.resolve_legal_in_epoch(board, from)
+    }
+
+    /// `resolve_legal_moves` for every square of `sources`, in order,
+    /// under one epoch: the position-wide facts the 300+ band memoises
+    /// — the king-safety filter's check state and pins, the tornado and
+    /// Antichess probes — are worked out once for the whole side rather
+    /// than once per square. Same moves, in the same order, as calling
+    /// `resolve_legal_moves` square by square.
+    pub fn resolve_all_legal_moves(&self, board: &Board, sources: &[Coord]) -> Vec<GameMove> {
+        if let Some(pos) = self.fast_path(board) {
+            let mut moves = Vec::new();
+            for from in sources {
+                pos.legal_moves_from(from, board.flags.promotion_choices(), &mut moves);
+            }
+            return moves;
+        }
+        let _epoch = LegalEpochGuard::open();
+        sources
+            .iter()
+            .flat_map(|from| self.resolve_legal_in_epoch(board, from))
+            .collect()
+    }
+
+    /// Does any square of `sources` have a legal move? One epoch, like
+    /// `resolve_all_legal_moves`, but stops at the first square that
+    /// has one.
+    pub fn any_legal_move(&self, board: &Board, sources: &[Coord]) -> bool {
+        if let Some(pos) = self.fast_path(board) {
+            return sources.iter().any(|from| pos.has_legal_move_from(from));
+        }
+        let _epoch = LegalEpochGuard::open();
+        sources
+            .iter()
+            .any(|from| !self.resolve_legal_in_epoch(board, from).is_empty())
+    }
+
+    /// `board` as bitboards, when this stack and the board both allow
+    /// the fast path (see `bitboard`).
+    fn fast_path(&self, board: &Board) -> Option<Position> {
+        if self.bitboard {
+            Position::from_board(board)
+        } else {
+            None
+        }
+    }
+
+    /// The full-stack resolve behind the three calls above, in whatever
+    /// epoch is current.
+    fn resolve_legal_in_epoch(&self, board: &Board, from: &Coord) -> Vec<GameMove> {
         let seed = vec![MovementEvent::MoveQuery { from: from.clone() }];
         let events = self.resolve(board, seed, None, None);
         events
@@ -445,42 +553,72 @@ impl MovementStack {
     }
 }
 
-/// Process-wide default stack, lazily initialised on first access.
-/// Production code paths call this; tests that want custom behaviour
+/// The stack for a board with no variant (`RuleSet::STANDARD`).
+/// Production code paths go through `Board::rules()`, which picks the
+/// stack for the board's own ruleset; tests that want custom behaviour
 /// construct their own `MovementStack`. The full list of registered
-/// modifiers lives on `build_default_stack` below.
+/// modifiers lives on `build_stack` below.
 pub fn default_stack() -> &'static MovementStack {
-    static STACK: OnceLock<MovementStack> = OnceLock::new();
-    STACK.get_or_init(build_default_stack)
+    &RuleSet::STANDARD.rules().movement
 }
 
-/// Constructor for the default stack. Each implementation step adds
-/// its modifiers here.
+/// Constructor for the stack `rules` plays by; `RuleSet::rules` caches
+/// the result. Each implementation step adds its modifiers here.
 ///
 /// Currently registered:
+/// - Plan 11, Duck Chess only: `DuckPhaseModifier` (priority 20) —
+///   turns every move query into duck candidates during the duck
+///   half-move.
 /// - Step 8 (move emission): `PieceMovesModifier` (priority 30).
+/// - Crazyhouse only: `DropModifier` (35) — drops from the pocket of
+///   the side to move onto the queried square.
 /// - Step 4 (threat path): twelve `PieceAttacksModifier` instances
 ///   (priority 40) plus `NeutralCarrierPassengerThreatModifier`
 ///   (priority 60).
+/// - Atomic only: `AtomicCaptureFilter` (70) — drops king captures
+///   and captures that would blow up the taker's own king.
 /// - Step 5 (square filters): `SquareConditionFilter` (110),
 ///   `WalkabilityFilter` (120), `SwitchTileAugment` (130).
 /// - Steps 6-7 (train geometry): `TrainHeadCrushModifier` (210),
 ///   `TrainCartCaptureFilter` (211), `TwoTrainCollisionFilter`
 ///   (212).
 /// - Step 9: `KingSafetyFilter` (300) — skipped by `resolve_moves`
-///   (capped at 299); applied by `resolve_legal_moves`.
+///   (capped at 299); applied by `resolve_legal_moves`. Left out of
+///   games without check (`RuleSet::enforces_check`).
 /// - Plan 13: `TornadoCompulsionFilter` (305) — destination
 ///   compulsion + trap; runs after king-safety so it operates over
 ///   the king-safe set. Skipped by `resolve_moves` and by the
 ///   tornado probe's capped resolve.
-fn build_default_stack() -> MovementStack {
+/// - Antichess only: `AntichessCaptureFilter` (310) — drops the side
+///   to move's non-captures while it has a capture.
+///
+/// Rulesets `bitboard::rules_allow` accepts also get the bitboard fast
+/// path for legal moves on boards it covers.
+pub(crate) fn build_stack(rules: RuleSet) -> MovementStack {
     let mut s = MovementStack::new();
+    // Plain standard chess on 8×8 skips the modifiers for legal-move
+    // queries (see `bitboard`).
+    s.bitboard = bitboard::rules_allow(rules);
+    // Plan 11: the Duck Chess duck half-move (priority 20), ahead of
+    // the piece moves it replaces.
+    if rules.has(VariantId::DuckChess) {
+        s.register(Box::new(duck::DuckPhaseModifier));
+    }
     // Step 8: piece-intrinsic move emission (priority 30).
     s.register(Box::new(piece_moves::PieceMovesModifier));
+    // Crazyhouse: drops onto empty squares (priority 35).
+    if rules.has(VariantId::Crazyhouse) {
+        s.register(Box::new(crazyhouse::DropModifier));
+    }
     // Step 4: piece-intrinsic threat emission.
     for m in piece_attacks::all_piece_attack_modifiers() {
         s.register(m);
     }
+    // Atomic: no king captures, no captures that blow up the taker's
+    // own king (priority 70).
+    if rules.has(VariantId::Atomic) {
+        s.register(Box::new(atomic::AtomicCaptureFilter));
+    }
     // Step 5: square-driven filters and augments.
     s.register(Box::new(square_filters::SquareConditionFilter));
     s.register(Box::new(square_filters::WalkabilityFilter));
@@ -492,11 +630,20 @@ fn build_default_stack() -> MovementStack {
     s.register(Box::new(train_modifiers::TwoTrainCollisionFilter));
     // Step 9: king-safety filter (priority 300). Skipped by
     // `resolve_moves` (capped at 299); applied by `resolve_legal_moves`.
-    s.register(Box::new(king_safety::KingSafetyFilter));
+    // Games without check (Duck Chess, king capture) leave it out:
+    // kings may walk into attack.
+    if rules.enforces_check() {
+        s.register(Box::new(king_safety::KingSafetyFilter));
+    }
     // Plan 13: tornado destination-compulsion (priority 305). After
     // king-safety so the probe and the final set are both over
     // king-safe moves.
     s.register(Box::new(tornado::TornadoCompulsionFilter));
+    // Antichess: captures are compulsory (priority 310). After the
+    // tornado so its probe sees only the moves a tornado leaves.
+    if rules.has(VariantId::Antichess) {
+        s.register(Box::new(antichess::AntichessCaptureFilter));
+    }
     s
 }
 
@@ -507,7 +654,7 @@ fn build_default_stack() -> MovementStack {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::board::BoardFlags;
+    use crate::board::{BoardFlags, DuckPhase};
     use crate::board::TrainTickRate;
 
     fn empty_board() -> Board {
@@ -518,20 +665,32 @@ mod tests {
                     .collect()
             })
             .collect();
-        Board {
+        Board::new(
             grid,
-            flags: BoardFlags {
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     /// Simple test modifier that emits a fixed effect for every event
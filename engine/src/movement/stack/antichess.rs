@@ -0,0 +1,130 @@
+//! Antichess (`variants=antichess`): captures are compulsory.
+//!
+//! `AntichessCaptureFilter` sits at priority 310, after the tornado
+//! compulsion (305). When the side to move has any capture among its
+//! moves, every candidate of that side that isn't a capture is dropped.
+//! The rest of the variant needs no modifier: the ruleset's win
+//! condition is `LoseAllPieces` and it has no check, so there's no
+//! `KingSafetyFilter` and the king is captured like any other piece
+//! (see `RuleSet::win_conditions`, `board::win`). A side left without a
+//! legal move wins too (`StalemateWin`).
+//!
+//! A capture is whatever `capture_targets` reports for the move — the
+//! same reading the capture stack uses — so a passenger stepping out
+//! of a carrier onto an enemy counts, and en passant does. The pieces
+//! that never capture (the Bus, whose boarding isn't a capture, the
+//! Skibidi's phase shift, the Stormcaller's tornado, a Crazyhouse drop)
+//! never satisfy the compulsion, but are bound by it: while a capture
+//! is on, they can't move. Train run-overs happen on the train tick,
+//! not as anyone's move, so they neither arm nor satisfy it; a piece
+//! lost to one still counts towards losing them all.
+//!
+//! **Recursion guard.** Same shape as the tornado filter's: "can this
+//! side capture" is probed with `resolve_moves_capped` at
+//! [`PROBE_CAP`] (= `ANTICHESS_PRIORITY - 1`), which runs the tornado
+//! compulsion — a tornado can rule out every capture — but never this
+//! filter. The probe's result is board-invariant, so it's memoised per
+//! `resolve_legal_moves` epoch like `tornado::compelled_facts`.
+
+use std::cell::Cell;
+
+use crate::board::make_move::capture_targets;
+use crate::board::{Board, Coord, GameMove, MoveType, VariantId};
+use crate::movement::stack::{
+    EventKindMask, MovementEffect, MovementEvent, MovementModifier, resolve_legal_epoch,
+};
+use crate::pieces::Color;
+
+thread_local! {
+    /// `(epoch, side, side_can_capture)` for the current
+    /// `resolve_legal_moves` call. See `tornado::PROBE_MEMO` for why an
+    /// epoch match means the same board.
+    static PROBE_MEMO: Cell<Option<(u64, Color, bool)>> = const { Cell::new(None) };
+}
+
+/// This filter's stack priority. After the tornado compulsion (305),
+/// so the probe sees only the moves a tornado leaves.
+pub const ANTICHESS_PRIORITY: u32 = 310;
+
+/// Cap for the capture probe: one below this filter's priority, so the
+/// probe never re-enters it.
+pub const PROBE_CAP: u32 = ANTICHESS_PRIORITY - 1;
+
+/// The colour of whoever makes `game_move` from `mover`: the dropped
+/// piece's for a drop, else the effective mover's (a passenger's, for a
+/// move out of a carrier). `None` for an empty square.
+fn mover_color(board: &Board, mover: &Coord, game_move: &GameMove) -> Option<Color> {
+    if let MoveType::Drop { piece, .. } = &game_move.move_type {
+        return Some(piece.get_color());
+    }
+    let piece = board.get_square_at(mover)?.piece.as_ref()?;
+    Some(board.effective_mover_color(piece, game_move).0)
+}
+
+fn is_capture(board: &Board, game_move: &GameMove) -> bool {
+    !capture_targets(board, game_move).is_empty()
+}
+
+/// Does `side` have a capture among its moves? Memoised per epoch.
+fn side_can_capture(board: &Board, side: Color) -> bool {
+    let epoch = resolve_legal_epoch();
+    if let Some((e, s, can)) = PROBE_MEMO.with(|c| c.get())
+        && e == epoch
+        && s == side
+    {
+        return can;
+    }
+    let stack = &board.rules().movement;
+    let can = board.move_sources(side).iter().any(|from| {
+        stack
+            .resolve_moves_capped(board, from, PROBE_CAP)
+            .iter()
+            .any(|m| mover_color(board, from, m) == Some(side) && is_capture(board, m))
+    });
+    PROBE_MEMO.with(|c| c.set(Some((epoch, side, can))));
+    can
+}
+
+pub struct AntichessCaptureFilter;
+
+impl MovementModifier for AntichessCaptureFilter {
+    fn id(&self) -> &'static str {
+        "variant.antichess_captures"
+    }
+    fn priority(&self) -> u32 {
+        ANTICHESS_PRIORITY
+    }
+    fn touches(&self) -> EventKindMask {
+        EventKindMask::CANDIDATE
+    }
+    fn apply(&self, board: &Board, event: &MovementEvent) -> MovementEffect {
+        let MovementEvent::Candidate { mover, game_move } = event else {
+            return MovementEffect::Keep;
+        };
+        let side_to_move = board.flags.side_to_move;
+        // Only the side to move is compelled; the other side's
+        // candidates aren't legal anyway.
+        if mover_color(board, mover, game_move) != Some(side_to_move)
+            || is_capture(board, game_move)
+        {
+            return MovementEffect::Keep;
+        }
+        if side_can_capture(board, side_to_move) {
+            MovementEffect::Drop
+        } else {
+            MovementEffect::Keep
+        }
+    }
+}
+
+impl Board {
+    /// Is `game_move` ruled out only by the Antichess capture
+    /// compulsion? The `validate_move` gate: the compulsion sits above
+    /// the 299 cap of the raw `get_moves` membership check, so a move
+    /// that passed that check and is missing from `legal_moves` was
+    /// dropped here (or by the tornado compulsion, checked first).
+    pub(crate) fn breaks_capture_compulsion(&self, game_move: &GameMove) -> bool {
+        self.ruleset().has(VariantId::Antichess)
+            && !self.legal_moves(&game_move.from).contains(game_move)
+    }
+}
@@ -0,0 +1,157 @@
+//! Atomic chess (`variants=atomic`).
+//!
+//! Every capture is an explosion: the captor dies along with its
+//! victim, and so does every piece other than a pawn on the eight
+//! squares around the capture square. Two modifiers carry the variant,
+//! both registered only by its ruleset:
+//!
+//! - `AtomicExplosionCapture` (capture stack, priority 200) emits the
+//!   blast as a `BoardOp::Compose` of `RemovePiece`s.
+//! - `AtomicCaptureFilter` (movement stack, priority 70) drops the
+//!   captures a king would make — kings can't capture — and every
+//!   threat whose capture would blow up the attacker's own king. The
+//!   second is what lets two kings stand side by side: neither can be
+//!   taken without the taker losing its own king, so neither is in
+//!   check.
+//!
+//! Carriers follow the blast: a Bus caught in it goes up with all its
+//! passengers, pawns included. Train carts are rolling stock and don't
+//! — a cart in the blast keeps its place on the track and loses only
+//! its non-pawn passengers, and a train that runs a piece over sets the
+//! blast off but rolls on. A Kidnapping Goblin's hostage dies with it,
+//! so the ruleset leaves out `GoblinDropVictimCapture`.
+//!
+//! Atomic is won by checkmate or by blowing up the enemy king (see
+//! `RuleSet::win_conditions`); a move that blows up the mover's own
+//! king is illegal (`Board::leaves_king_in_check`).
+
+use crate::board::make_move::capture_targets;
+use crate::board::{Board, Coord};
+use crate::movement::stack::capture::{
+    BoardOp, CaptureModifier, ResolutionEffect, ResolutionEvent,
+};
+use crate::movement::stack::{EventKindMask, MovementEffect, MovementEvent, MovementModifier};
+use crate::pieces::piecetype::PieceType;
+
+/// The squares around `center` a capture there blasts, clipped to the
+/// board.
+pub fn blast_squares(board: &Board, center: &Coord) -> Vec<Coord> {
+    let mut out = Vec::with_capacity(8);
+    for dr in -1i16..=1 {
+        for df in -1i16..=1 {
+            if dr == 0 && df == 0 {
+                continue;
+            }
+            let rank = center.rank as i16 + dr;
+            let file = center.file as i16 + df;
+            if (0..board.height() as i16).contains(&rank)
+                && (0..board.width() as i16).contains(&file)
+            {
+                out.push(Coord {
+                    file: file as u8,
+                    rank: rank as u8,
+                });
+            }
+        }
+    }
+    out
+}
+
+pub struct AtomicExplosionCapture;
+
+impl CaptureModifier for AtomicExplosionCapture {
+    fn id(&self) -> &'static str {
+        "variant.atomic_explosion"
+    }
+    fn priority(&self) -> u32 {
+        200
+    }
+    fn apply(&self, board: &Board, event: &ResolutionEvent) -> ResolutionEffect {
+        let ResolutionEvent::Capture {
+            captor_coord,
+            captor,
+            ..
+        } = event;
+        let mut ops = Vec::new();
+        if !captor.is_train_cart() {
+            ops.push(BoardOp::RemovePiece {
+                at: captor_coord.clone(),
+            });
+        }
+        for at in blast_squares(board, captor_coord) {
+            let Some(piece) = board.get_square_at(&at).and_then(|s| s.piece.as_ref()) else {
+                continue;
+            };
+            if matches!(piece, PieceType::Pawn(_)) {
+                continue;
+            }
+            if !piece.is_train_cart() {
+                ops.push(BoardOp::RemovePiece { at });
+                continue;
+            }
+            let passengers = piece.passengers().unwrap_or_default();
+            let survivors: Vec<PieceType> = passengers
+                .iter()
+                .filter(|p| matches!(p, PieceType::Pawn(_)))
+                .cloned()
+                .collect();
+            if survivors.len() != passengers.len() {
+                ops.push(BoardOp::SetPassengerList {
+                    at,
+                    passengers: survivors,
+                });
+            }
+        }
+        match ops.len() {
+            0 => ResolutionEffect::Keep,
+            1 => ResolutionEffect::Mutate(ops.remove(0)),
+            _ => ResolutionEffect::Mutate(BoardOp::Compose(ops)),
+        }
+    }
+}
+
+pub struct AtomicCaptureFilter;
+
+impl MovementModifier for AtomicCaptureFilter {
+    fn id(&self) -> &'static str {
+        "variant.atomic_captures"
+    }
+    fn priority(&self) -> u32 {
+        // After every piece-intrinsic emitter, including the neutral
+        // carriers' passenger threats (60).
+        70
+    }
+    fn touches(&self) -> EventKindMask {
+        EventKindMask::CANDIDATE.union(EventKindMask::THREAT)
+    }
+    fn apply(&self, board: &Board, event: &MovementEvent) -> MovementEffect {
+        match event {
+            MovementEvent::Candidate { game_move, .. } => {
+                let king_captures = capture_targets(board, game_move)
+                    .iter()
+                    .any(|c| matches!(c.captor, PieceType::King(_)));
+                if king_captures {
+                    MovementEffect::Drop
+                } else {
+                    MovementEffect::Keep
+                }
+            }
+            MovementEvent::Threat {
+                attacker_piece,
+                target,
+                ..
+            } => {
+                if matches!(attacker_piece, PieceType::King(_)) {
+                    return MovementEffect::Drop;
+                }
+                let own_king = board.find_king(attacker_piece.get_color());
+                if own_king.is_some_and(|k| blast_squares(board, target).contains(&k)) {
+                    MovementEffect::Drop
+                } else {
+                    MovementEffect::Keep
+                }
+            }
+            _ => MovementEffect::Keep,
+        }
+    }
+}
@@ -19,15 +19,15 @@
 
 // Reservation hooks: `len`, `is_empty`, `modifier_ids`, and some
 // `BoardOp` variants land in step 10 with the Goblin handler as the
-// sole consumer. Drop the allow once Bomb / Antipode / Jackhammer
+// first consumer. Drop the allow once Bomb / Antipode / Jackhammer
 // land and exercise the rest of the surface.
 #![allow(dead_code)]
 
-use std::sync::OnceLock;
-
-use crate::board::{Board, Coord, MoveType};
-use crate::board::square::SquareCondition;
-use crate::pieces::piecetype::PieceType;
+use crate::board::{Board, Coord, MoveType, VariantId};
+use crate::board::square::{Square, SquareCondition};
+use crate::movement::rules::RuleSet;
+use crate::movement::stack::{atomic, crazyhouse};
+use crate::pieces::{Color, piecetype::PieceType};
 
 /// Events the capture pipeline operates on. Distinct from
 /// `MovementEvent` — they fire during apply, not during generation —
@@ -126,6 +126,26 @@ pub enum BoardOp {
         at: Coord,
         passengers: Vec<PieceType>,
     },
+    /// Bank `count` bonus moves for the side that is moving (see
+    /// `BoardFlags::extra_moves`). Anything that lets the mover "move
+    /// again" emits this rather than touching the turn itself.
+    GrantExtraMoves {
+        count: u8,
+    },
+    /// Crazyhouse: put `piece` into `color`'s pocket (see
+    /// `BoardFlags::white_pocket`). Takes the piece as it should be
+    /// dropped later; `PocketCapture` converts it first.
+    AddToPocket {
+        color: Color,
+        piece: PieceType,
+    },
+    /// Overwrite the whole square — piece, square type and conditions.
+    /// Emitted by `MoveUndo` (see `board::undo`) to put back a square's
+    /// pre-move contents; capture handlers have no use for it.
+    RestoreSquare {
+        at: Coord,
+        square: Square,
+    },
     Compose(Vec<BoardOp>),
 }
 
@@ -203,6 +223,26 @@ impl BoardOp {
                 };
                 *list = passengers.clone();
             }
+            BoardOp::GrantExtraMoves { count } => {
+                board.flags.extra_moves = board.flags.extra_moves.saturating_add(*count);
+            }
+            BoardOp::AddToPocket { color, piece } => {
+                crate::board::crazyhouse::add_to_pocket(board, *color, piece.clone());
+            }
+            BoardOp::RestoreSquare { at, square } => {
+                // Honours the castle-revoke contract like the other
+                // piece-writing arms. `unmake_move` restores the flags
+                // wholesale after its ops, so an undo never keeps the
+                // revocation.
+                let Some(sq) = board.get_square_mut(at) else {
+                    tracing::warn!(?at, "BoardOp::RestoreSquare out of bounds");
+                    return;
+                };
+                let displaced = std::mem::replace(sq, square.clone()).piece;
+                if let Some(p) = &displaced {
+                    board.maybe_clear_castle_on_rook_capture(at, p);
+                }
+            }
             BoardOp::Compose(ops) => {
                 for op in ops {
                     op.apply(board);
@@ -220,8 +260,8 @@ pub trait CaptureModifier: Send + Sync {
     fn apply(&self, board: &Board, event: &ResolutionEvent) -> ResolutionEffect;
 }
 
-/// Registry, mirroring `MovementStack`. Built at engine init via
-/// `default_capture_stack()`.
+/// Registry, mirroring `MovementStack`. Built once per ruleset by
+/// `build_capture_stack` (see `movement::rules`).
 pub struct CaptureStack {
     modifiers: Vec<Box<dyn CaptureModifier>>,
 }
@@ -269,23 +309,35 @@ impl CaptureStack {
     }
 }
 
-/// Process-wide default capture stack. Currently registers the
-/// Goblin drop-victim handler (plan 04).
+/// The capture stack for a board with no variant
+/// (`RuleSet::STANDARD`). Production code goes through
+/// `Board::rules()`.
 pub fn default_capture_stack() -> &'static CaptureStack {
-    static STACK: OnceLock<CaptureStack> = OnceLock::new();
-    STACK.get_or_init(build_default_capture_stack)
+    &RuleSet::STANDARD.rules().capture
 }
 
-fn build_default_capture_stack() -> CaptureStack {
+/// Constructor for the capture stack `rules` plays by: the Goblin
+/// drop-victim handler (plan 04), or under Atomic the explosion in its
+/// place — a hostage dies with its Goblin there. Crazyhouse adds
+/// `PocketCapture`.
+pub(crate) fn build_capture_stack(rules: RuleSet) -> CaptureStack {
     let mut s = CaptureStack::new();
-    s.register(Box::new(GoblinDropVictimCapture));
+    if rules.has(VariantId::Atomic) {
+        s.register(Box::new(atomic::AtomicExplosionCapture));
+    } else {
+        s.register(Box::new(GoblinDropVictimCapture));
+    }
+    if rules.has(VariantId::Crazyhouse) {
+        s.register(Box::new(crazyhouse::PocketCapture));
+    }
     s
 }
 
 /// Plan 04: when a Goblin in `Kidnapping` state is captured by an
 /// enemy, the kidnapped piece is dropped onto the goblin's old
 /// square (the captor's *origin* square, which is now empty post-
-/// relocation in standard MoveTo captures).
+/// relocation in standard MoveTo captures), and the taker's side
+/// moves again (`BoardOp::GrantExtraMoves`).
 ///
 /// **Where the victim lands** — at `captor_origin`. The captor moved
 /// FROM `captor_origin` TO `victim_coord`. So `captor_origin` is now
@@ -297,7 +349,13 @@ fn build_default_capture_stack() -> CaptureStack {
 /// the freed piece into the passenger list would break carrier-color
 /// and capacity invariants). We skip the drop in this case; the
 /// kidnap victim is silently lost, matching the plan-09 Q7 "silent
-/// passenger removal" precedent.
+/// passenger removal" precedent. The bonus move is still granted.
+///
+/// **Train run-overs grant nothing.** A Neutral captor has no side to
+/// give the move to; the victim is dropped (or lost) as usual.
+///
+/// **Nor does Duck Chess.** A second piece move would skip the duck,
+/// so the kidnap there is just the drop.
 pub struct GoblinDropVictimCapture;
 
 impl CaptureModifier for GoblinDropVictimCapture {
@@ -307,9 +365,10 @@ impl CaptureModifier for GoblinDropVictimCapture {
     fn priority(&self) -> u32 {
         100
     }
-    fn apply(&self, _board: &Board, event: &ResolutionEvent) -> ResolutionEffect {
+    fn apply(&self, board: &Board, event: &ResolutionEvent) -> ResolutionEffect {
         let ResolutionEvent::Capture {
             captor_origin,
+            captor,
             victim,
             ..
         } = event;
@@ -321,15 +380,24 @@ impl CaptureModifier for GoblinDropVictimCapture {
         let GoblinState::Kidnapping { piece } = &goblin.state else {
             return ResolutionEffect::Keep;
         };
+        let mut ops = Vec::new();
         // No outer-board origin → no drop site (PIC capture). Silent
         // loss is the documented precedent.
-        let Some(origin) = captor_origin else {
-            return ResolutionEffect::Keep;
-        };
-        let kidnapped: PieceType = piece.as_ref().clone();
-        ResolutionEffect::Mutate(BoardOp::PlacePiece {
-            at: origin.clone(),
-            piece: kidnapped,
-        })
+        if let Some(origin) = captor_origin {
+            ops.push(BoardOp::PlacePiece {
+                at: origin.clone(),
+                piece: piece.as_ref().clone(),
+            });
+        }
+        if captor.get_color() != Color::Neutral
+            && !board.flags.has_variant(VariantId::DuckChess)
+        {
+            ops.push(BoardOp::GrantExtraMoves { count: 1 });
+        }
+        match ops.len() {
+            0 => ResolutionEffect::Keep,
+            1 => ResolutionEffect::Mutate(ops.remove(0)),
+            _ => ResolutionEffect::Mutate(BoardOp::Compose(ops)),
+        }
     }
 }
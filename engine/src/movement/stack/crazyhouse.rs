@@ -0,0 +1,85 @@
+//! Crazyhouse (`variants=crazyhouse`) as two modifiers, both registered
+//! only by its ruleset:
+//!
+//! - `DropModifier` (movement stack, priority 35) answers a `MoveQuery`
+//!   on an empty square with a `Drop` candidate per piece the side to
+//!   move could set down there (`Board::drop_moves_at`). The candidates'
+//!   `mover` is the target square, which holds no piece, so the filters
+//!   that key on one pass them through; `KingSafetyFilter` reads the
+//!   dropped piece's colour instead.
+//! - `PocketCapture` (capture stack, priority 150) sends every captured
+//!   piece to the taker's pocket, in its `pocket_form`. A captured Bus
+//!   takes its passengers with it; each goes in separately. A
+//!   Kidnapping Goblin's hostage is dropped back on the board by
+//!   `GoblinDropVictimCapture` as usual, so only the Goblin is pocketed.
+//!   Train run-overs have no taker and pocket nothing.
+//!
+//! See `board::crazyhouse` for the rules themselves.
+
+use crate::board::Board;
+use crate::board::crazyhouse::pocket_form;
+use crate::movement::stack::capture::{
+    BoardOp, CaptureModifier, ResolutionEffect, ResolutionEvent,
+};
+use crate::movement::stack::{EventKindMask, MovementEffect, MovementEvent, MovementModifier};
+
+pub struct DropModifier;
+
+impl MovementModifier for DropModifier {
+    fn id(&self) -> &'static str {
+        "variant.crazyhouse_drops"
+    }
+    fn priority(&self) -> u32 {
+        // Right after the piece moves (30); an empty square has none.
+        35
+    }
+    fn touches(&self) -> EventKindMask {
+        EventKindMask::MOVE_QUERY
+    }
+    fn apply(&self, board: &Board, event: &MovementEvent) -> MovementEffect {
+        let MovementEvent::MoveQuery { from } = event else {
+            return MovementEffect::Keep;
+        };
+        let drops = board.drop_moves_at(from);
+        if drops.is_empty() {
+            return MovementEffect::Keep;
+        }
+        MovementEffect::Augment(
+            drops
+                .into_iter()
+                .map(|m| MovementEvent::Candidate {
+                    mover: from.clone(),
+                    game_move: m,
+                })
+                .collect(),
+        )
+    }
+}
+
+pub struct PocketCapture;
+
+impl CaptureModifier for PocketCapture {
+    fn id(&self) -> &'static str {
+        "variant.crazyhouse_pocket"
+    }
+    fn priority(&self) -> u32 {
+        150
+    }
+    fn apply(&self, _board: &Board, event: &ResolutionEvent) -> ResolutionEffect {
+        let ResolutionEvent::Capture { captor, victim, .. } = event;
+        let owner = captor.get_color();
+        let mut ops: Vec<BoardOp> = std::iter::once(victim)
+            .chain(victim.passengers().into_iter().flatten())
+            .filter_map(|p| pocket_form(p, owner))
+            .map(|piece| BoardOp::AddToPocket {
+                color: owner,
+                piece,
+            })
+            .collect();
+        match ops.len() {
+            0 => ResolutionEffect::Keep,
+            1 => ResolutionEffect::Mutate(ops.remove(0)),
+            _ => ResolutionEffect::Mutate(BoardOp::Compose(ops)),
+        }
+    }
+}
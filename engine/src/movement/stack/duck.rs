@@ -0,0 +1,52 @@
+//! Plan 11 — the Duck Chess duck half-move as a modifier.
+//!
+//! `DuckPhaseModifier` sits at priority 20, ahead of
+//! `PieceMovesModifier` (30). During the duck half-move it replaces
+//! every `MoveQuery` with the duck's candidates (`Board::
+//! duck_moves_from`), so the piece at the query square never gets to
+//! propose a move and the `MOVE_QUERY` augments downstream never see
+//! the query. Outside the duck half-move it keeps the query untouched;
+//! games that aren't Duck Chess don't register it at all (see
+//! `build_stack`).
+//!
+//! The duck candidates carry no piece, so the later filters that key
+//! on one (`KingSafetyFilter`, `TornadoCompulsionFilter`) keep them as
+//! they are (Duck Chess has no `KingSafetyFilter` anyway); the square
+//! filters skip them explicitly.
+
+use crate::board::Board;
+use crate::movement::stack::{
+    EventKindMask, MovementEffect, MovementEvent, MovementModifier,
+};
+
+pub struct DuckPhaseModifier;
+
+impl MovementModifier for DuckPhaseModifier {
+    fn id(&self) -> &'static str {
+        "variant.duck_phase"
+    }
+    fn priority(&self) -> u32 {
+        20
+    }
+    fn touches(&self) -> EventKindMask {
+        EventKindMask::MOVE_QUERY
+    }
+    fn apply(&self, board: &Board, event: &MovementEvent) -> MovementEffect {
+        let MovementEvent::MoveQuery { from } = event else {
+            return MovementEffect::Keep;
+        };
+        if !board.in_duck_placement() {
+            return MovementEffect::Keep;
+        }
+        MovementEffect::Replace(
+            board
+                .duck_moves_from(from)
+                .into_iter()
+                .map(|m| MovementEvent::Candidate {
+                    mover: from.clone(),
+                    game_move: m,
+                })
+                .collect(),
+        )
+    }
+}
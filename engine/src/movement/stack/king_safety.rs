@@ -5,29 +5,213 @@
 //! `Board::legal_moves` uses the latter; `Board::get_moves` and the
 //! geometric-check step of `Board::validate_move` use the former.
 //!
-//! **Plan 11 hook:** the filter reads
-//! `BoardFlags.has_variant(VariantId::DuckChess)` (when plan 11
-//! lands) and short-circuits to `Keep` — Duck Chess has no concept
-//! of check. The variant infrastructure isn't in the engine yet,
-//! so today the check is a TODO. Once plan 11 ships, that's a
-//! single-line read.
+//! **Plan 11:** Duck Chess has no concept of check, so its ruleset
+//! leaves this filter out of the stack (see `build_stack`), as does
+//! any game that can't be won by checkmate.
+//!
+//! The probe asks `Board::leaves_king_in_check` rather than
+//! `is_in_check`, so under Atomic a capture that blows up the mover's
+//! own king is dropped and one that blows up only the enemy king is
+//! kept.
 //!
 //! **Critical invariant:** the hypothetical board's threat resolution
 //! must not recurse into `resolve_moves`, or we'd infinite-loop.
 //!
-//! The implementation route: clone-and-apply via
-//! `apply_move_for_validation`, then `is_in_check` → `is_attacked_by`
-//! → `resolve_threats` on the **full default stack**. That stack
+//! The implementation route: `Board::probe_move` (apply the
+//! validation phases to a reusable scratch board, revert after), then `is_in_check` → `is_attacked_by`
+//! → `resolve_threats` on the board's **full ruleset stack**. That stack
 //! includes this filter, but recursion is prevented by the
 //! `touches() = EventKindMask::CANDIDATE` mask below — the dispatcher
 //! skips this modifier for `Threat` events. Loop-freedom rests on
 //! that mask. Do NOT remove the `touches` impl without rewiring the
 //! hypothetical to a stripped-down threat path.
+//!
+//! **Pins.** On a plain board — standard pieces on standard floor, no
+//! square conditions, no duck, and no variant that changes what a
+//! capture does — the probe is skipped for the moves that can't expose
+//! the king: an ordinary move or standard promotion by a non-king piece
+//! that isn't pinned, while the king isn't in check. The check state
+//! and pins (`SafetyFacts`) are worked out once per mover colour per
+//! `resolve_legal_moves` epoch, so `Board::all_legal_moves` pays for
+//! them once per position; a capped resolve run outside any epoch
+//! always probes. Everything else — king moves, castling, en
+//! passant, any move while in check, and every move on a board with a
+//! fairy piece or tile — still goes through the probe, so the result
+//! is the probe's either way.
+
+use std::cell::RefCell;
 
-use crate::board::Board;
+use crate::board::square::SquareType;
+use crate::board::win::WinConditionId;
+use crate::board::{Board, Coord, MoveType, PromotionTarget, VariantId};
 use crate::movement::stack::{
-    EventKindMask, MovementEffect, MovementEvent, MovementModifier,
+    EventKindMask, MovementEffect, MovementEvent, MovementModifier, active_legal_epoch,
 };
+use crate::pieces::Color;
+use crate::pieces::piecetype::PieceType;
+
+thread_local! {
+    /// `(epoch, colour, facts)` for the current `resolve_legal_moves`
+    /// epoch. See `tornado::PROBE_MEMO` for why an epoch match means
+    /// the same board. `None` facts: not a plain board, probe
+    /// everything.
+    static FACTS_MEMO: RefCell<Option<(u64, Color, Option<SafetyFacts>)>> =
+        const { RefCell::new(None) };
+}
+
+/// What a plain board says about `color`'s king before any move.
+struct SafetyFacts {
+    in_check: bool,
+    king: Coord,
+    /// `color`'s pieces that stand between the king and an enemy
+    /// slider with nothing else in the way.
+    pinned: Vec<Coord>,
+}
+
+impl SafetyFacts {
+    /// Can the move from `mover` be kept without a probe?
+    fn move_is_safe(&self, mover: &Coord, move_type: &MoveType) -> bool {
+        let plain_move = match move_type {
+            MoveType::MoveTo(_) => true,
+            MoveType::Promotion { into, .. } => PromotionTarget::STANDARD.contains(into),
+            _ => false,
+        };
+        plain_move && !self.in_check && *mover != self.king && !self.pinned.contains(mover)
+    }
+}
+
+pub(crate) fn is_standard_piece(piece: &PieceType) -> bool {
+    matches!(
+        piece,
+        PieceType::Pawn(_)
+            | PieceType::Rook(_)
+            | PieceType::Knight(_)
+            | PieceType::Bishop(_)
+            | PieceType::Queen(_)
+            | PieceType::King(_)
+    )
+}
+
+/// Is `board` plain enough for pins to tell the whole story? Standard
+/// floor and pieces only, and none of the variants whose captures do
+/// more than remove the victim. A game also won by king capture is
+/// left to the probe: there, taking the enemy king is legal even from
+/// a pin (see `Board::leaves_king_in_check`).
+fn is_plain(board: &Board) -> bool {
+    let rules = board.ruleset();
+    if [
+        VariantId::DuckChess,
+        VariantId::Atomic,
+        VariantId::Crazyhouse,
+        VariantId::Antichess,
+    ]
+    .into_iter()
+    .any(|v| rules.has(v))
+        || rules.has_win(WinConditionId::KingCapture)
+    {
+        return false;
+    }
+    board.grid.iter().flatten().all(|sq| {
+        matches!(sq.square_type, SquareType::Standard | SquareType::Hill)
+            && sq.conditions.is_empty()
+            && !sq.duck
+            && sq.piece.as_ref().is_none_or(is_standard_piece)
+    })
+}
+
+/// `color`'s check state and pins, or `None` when the board isn't
+/// plain or `color` hasn't exactly one king.
+fn safety_facts(board: &Board, color: Color) -> Option<SafetyFacts> {
+    if !is_plain(board) {
+        return None;
+    }
+    let mut kings = board
+        .iter_pieces()
+        .filter(|(_, p)| matches!(p, PieceType::King(k) if k.color == color))
+        .map(|(c, _)| c);
+    let king = kings.next()?;
+    if kings.next().is_some() {
+        return None;
+    }
+    let mut pinned = Vec::new();
+    for (df, dr) in [
+        (0, 1),
+        (0, -1),
+        (1, 0),
+        (-1, 0),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+    ] {
+        let diagonal = df != 0 && dr != 0;
+        let mut shield: Option<Coord> = None;
+        let (mut file, mut rank) = (king.file as i32, king.rank as i32);
+        loop {
+            file += df;
+            rank += dr;
+            if file < 0 || rank < 0 {
+                break;
+            }
+            let at = Coord {
+                file: file as u8,
+                rank: rank as u8,
+            };
+            let Some(square) = board.get_square_at(&at) else {
+                break;
+            };
+            let Some(piece) = square.piece.as_ref() else {
+                continue;
+            };
+            if piece.get_color() == color {
+                if shield.is_some() {
+                    break;
+                }
+                shield = Some(at);
+                continue;
+            }
+            let slides_here = match piece {
+                PieceType::Queen(_) => true,
+                PieceType::Rook(_) => !diagonal,
+                PieceType::Bishop(_) => diagonal,
+                _ => false,
+            };
+            if slides_here && let Some(shield) = shield {
+                pinned.push(shield);
+            }
+            break;
+        }
+    }
+    Some(SafetyFacts {
+        in_check: board.is_in_check(color),
+        king,
+        pinned,
+    })
+}
+
+/// Can the candidate be kept without a probe? Only inside a legal-move
+/// resolve, whose board is the one the memoised facts were worked out
+/// for; a capped resolve run on its own always probes.
+fn skips_probe(board: &Board, color: Color, mover: &Coord, move_type: &MoveType) -> bool {
+    let Some(epoch) = active_legal_epoch() else {
+        return false;
+    };
+    let hit = FACTS_MEMO.with(|m| {
+        m.borrow()
+            .as_ref()
+            .filter(|(e, c, _)| *e == epoch && *c == color)
+            .map(|(_, _, f)| f.as_ref().is_some_and(|f| f.move_is_safe(mover, move_type)))
+    });
+    if let Some(skip) = hit {
+        return skip;
+    }
+    let facts = safety_facts(board, color);
+    let skip = facts
+        .as_ref()
+        .is_some_and(|f| f.move_is_safe(mover, move_type));
+    FACTS_MEMO.with(|m| *m.borrow_mut() = Some((epoch, color, facts)));
+    skip
+}
 
 pub struct KingSafetyFilter;
 
@@ -46,39 +230,31 @@ impl MovementModifier for KingSafetyFilter {
             return MovementEffect::Keep;
         };
 
-        // Plan 11 (Duck Chess) hook. When `BoardFlags.variants`
-        // contains `VariantId::DuckChess`, king-safety is disabled
-        // entirely (Duck Chess has no concept of check). The
-        // variants infrastructure isn't shipped yet; once it lands,
-        // replace this TODO with:
-        //   if board.flags.has_variant(VariantId::DuckChess) {
-        //       return MovementEffect::Keep;
-        //   }
-        // For now this is a no-op.
-
-        let Some(source_piece) = board.get_square_at(mover).and_then(|s| s.piece.as_ref())
-        else {
-            // No piece at source — the move couldn't apply anyway.
-            // Let the downstream apply-time error surface naturally.
-            return MovementEffect::Keep;
+        let mover_color = if let MoveType::Drop { piece, .. } = &game_move.move_type {
+            // A Crazyhouse drop has nothing on its square yet; it's the
+            // dropped piece's side whose king must be safe.
+            piece.get_color()
+        } else {
+            let Some(source_piece) = board.get_square_at(mover).and_then(|s| s.piece.as_ref())
+            else {
+                // No piece at source — the move couldn't apply anyway.
+                // Let the downstream apply-time error surface naturally.
+                return MovementEffect::Keep;
+            };
+            board.effective_mover_color(source_piece, game_move).0
         };
 
-        let (mover_color, _) = board.effective_mover_color(source_piece, game_move);
-
-        let mut hypothetical = board.clone();
-        match hypothetical.apply_move_for_validation(game_move.clone()) {
-            Ok(()) => {
-                if hypothetical.is_in_check(mover_color) {
-                    MovementEffect::Drop
-                } else {
-                    MovementEffect::Keep
-                }
-            }
-            // The hypothetical apply failed — the move can't legally
-            // execute. Drop so `legal_moves` doesn't emit a move
-            // `make_move` would reject. (This mirrors the legacy
-            // `legal_moves` filter's `Err(_) => false`.)
-            Err(_) => MovementEffect::Drop,
+        if skips_probe(board, mover_color, mover, &game_move.move_type) {
+            return MovementEffect::Keep;
         }
+
+        // The hypothetical apply failing means the move can't legally
+        // execute. Drop so `legal_moves` doesn't emit a move
+        // `make_move` would reject. (This mirrors the legacy
+        // `legal_moves` filter's `Err(_) => false`.)
+        board.probe_move(game_move, |hypothetical| match hypothetical {
+            Ok(h) if !board.leaves_king_in_check(h, mover_color) => MovementEffect::Keep,
+            _ => MovementEffect::Drop,
+        })
     }
 }
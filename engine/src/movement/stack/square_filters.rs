@@ -39,9 +39,14 @@ impl MovementModifier for SquareConditionFilter {
         EventKindMask::CANDIDATE
     }
     fn apply(&self, board: &Board, event: &MovementEvent) -> MovementEffect {
-        let MovementEvent::Candidate { mover, .. } = event else {
+        let MovementEvent::Candidate { mover, game_move } = event else {
             return MovementEffect::Keep;
         };
+        // Plan 11: the duck isn't a piece. Brainrot or frost on its
+        // square doesn't pin it.
+        if game_move.move_type.is_duck_move() {
+            return MovementEffect::Keep;
+        }
         let Some(sq) = board.get_square_at(mover) else {
             return MovementEffect::Drop;
         };
@@ -75,6 +80,11 @@ impl MovementModifier for SquareConditionFilter {
 /// only to move types that surface a destination coord. Castle is
 /// excluded — its path safety is checked at move-gen time per
 /// `king.rs::castle_moves`.
+///
+/// **Duck Chess (plan 11):** a destination holding the duck is dropped
+/// like unwalkable terrain. Duck moves skip the filter altogether —
+/// `Board::duck_moves_from` only targets squares that can take the
+/// duck, and the duck is never stranded by a gate closing under it.
 pub struct WalkabilityFilter;
 
 impl WalkabilityFilter {
@@ -84,6 +94,7 @@ impl WalkabilityFilter {
             MoveType::Promotion { target, .. } => Some(target),
             MoveType::EnPassant { target, .. } => Some(target),
             MoveType::MoveIntoCarrier(c) => Some(c),
+            MoveType::Drop { target, .. } => Some(target),
             MoveType::PieceInCarrier { move_type, .. } => match move_type.as_ref() {
                 MoveType::MoveTo(c) => Some(c),
                 MoveType::MoveIntoCarrier(c) => Some(c),
@@ -94,7 +105,10 @@ impl WalkabilityFilter {
             | MoveType::ThrowSwitch { .. }
             // Plan 13: placing a tornado doesn't relocate the placer,
             // so there's no landing square to walkability-check.
-            | MoveType::PlaceTornado { .. } => None,
+            | MoveType::PlaceTornado { .. }
+            // Plan 11: checked by the duck's own generator.
+            | MoveType::PlaceDuck { .. }
+            | MoveType::MoveDuck { .. } => None,
         }
     }
 }
@@ -113,6 +127,9 @@ impl MovementModifier for WalkabilityFilter {
         let MovementEvent::Candidate { mover, game_move } = event else {
             return MovementEffect::Keep;
         };
+        if game_move.move_type.is_duck_move() {
+            return MovementEffect::Keep;
+        }
         // Source-walkability check. Applies uniformly across all
         // MoveType arms — a stranded piece can't even ThrowSwitch /
         // PhaseShift / PIC. Castle's source is the king's tile (back
@@ -125,11 +142,11 @@ impl MovementModifier for WalkabilityFilter {
         let Some(dest) = Self::destination(game_move) else {
             return MovementEffect::Keep;
         };
-        // Out-of-bounds dests also fall through to Drop via the
-        // helper's `unwrap_or(false)`; the piece-intrinsic move
-        // modifier shouldn't propose them, but the filter is the
-        // safety net.
-        if board.is_walkable_at(dest) {
+        // Out-of-bounds dests also fall through to Drop (no square);
+        // the piece-intrinsic move modifier shouldn't propose them,
+        // but the filter is the safety net. `Square::is_walkable`
+        // also turns away a landing on the duck.
+        if board.get_square_at(dest).is_some_and(|sq| sq.is_walkable()) {
             MovementEffect::Keep
         } else {
             MovementEffect::Drop
@@ -187,7 +204,7 @@ impl MovementModifier for SwitchTileAugment {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::board::{BoardFlags, Coord, TrainTickRate};
+    use crate::board::{BoardFlags, Coord, DuckPhase, TrainTickRate};
     use crate::board::square::Square;
     use crate::movement::stack::MovementStack;
     use crate::pieces::Color;
@@ -197,20 +214,32 @@ mod tests {
         let grid = (0..8)
             .map(|_| (0..8).map(|_| Square::new()).collect())
             .collect();
-        Board {
+        Board::new(
             grid,
-            flags: BoardFlags {
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     /// A `Candidate` whose source square has `Frozen` is dropped.
@@ -172,6 +172,7 @@ fn move_destination(game_move: &GameMove) -> Option<&Coord> {
         MoveType::Promotion { target, .. } => Some(target),
         MoveType::EnPassant { target, .. } => Some(target),
         MoveType::MoveIntoCarrier(c) => Some(c),
+        MoveType::Drop { target, .. } => Some(target),
         MoveType::PieceInCarrier { move_type, .. } => match move_type.as_ref() {
             MoveType::MoveTo(c) => Some(c),
             MoveType::MoveIntoCarrier(c) => Some(c),
@@ -182,7 +183,10 @@ fn move_destination(game_move: &GameMove) -> Option<&Coord> {
         | MoveType::ThrowSwitch { .. }
         // PlaceTornado does not relocate the placer — it can never be
         // the move that "lands on" the tornado for compulsion.
-        | MoveType::PlaceTornado { .. } => None,
+        | MoveType::PlaceTornado { .. }
+        // Nor does the duck; it isn't a piece.
+        | MoveType::PlaceDuck { .. }
+        | MoveType::MoveDuck { .. } => None,
     }
 }
 
@@ -207,7 +211,7 @@ fn move_destination(game_move: &GameMove) -> Option<&Coord> {
 /// without this skip a multi-tornado position would let a trapped
 /// piece's phantom reachability falsely arm the compulsion).
 fn side_can_reach_tornado(board: &Board, side: Color) -> bool {
-    let stack = crate::movement::stack::default_stack();
+    let stack = &board.rules().movement;
     for (coord, piece) in board.iter_pieces() {
         if piece.get_color() != side {
             continue;
@@ -317,10 +321,10 @@ impl MovementModifier for TornadoCompulsionFilter {
         // Concept 1's "never produces a self-checking move" guarantee
         // depends on KingSafetyFilter (300) having filtered the set this
         // runs over (305) and the probe's capped set (PROBE_CAP=304 still
-        // includes 300). A variant that disables king-safety
-        // (king_safety.rs has a documented unshipped Duck-Chess
-        // short-circuit) voids that guarantee for the compulsion too —
-        // such a variant must independently disable/redefine the
+        // includes 300). Duck Chess switches king-safety off, but it
+        // has no check for the guarantee to protect, so the compulsion
+        // runs unchanged there. A variant that disables king-safety yet
+        // keeps check must independently disable/redefine the
         // compulsion. See plan 13 "Check interaction is free" bullet.
         if side_can_reach {
             let lands_on_tornado = move_destination(game_move)
@@ -339,27 +343,39 @@ impl MovementModifier for TornadoCompulsionFilter {
 mod tests {
     use super::*;
     use crate::board::square::Square;
-    use crate::board::{BoardFlags, TrainTickRate};
+    use crate::board::{BoardFlags, DuckPhase, TrainTickRate};
     use crate::pieces::Color;
 
     fn board8() -> Board {
         let grid = (0..8)
             .map(|_| (0..8).map(|_| Square::new()).collect())
             .collect();
-        Board {
+        Board::new(
             grid,
-            flags: BoardFlags {
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     fn c(file: u8, rank: u8) -> Coord {
@@ -717,16 +733,16 @@ mod tests {
             .add_square_condition(SquareCondition::Tornado { remaining: 2 });
 
         assert_eq!(
-            b.grid[4][4].conditions,
-            vec![SquareCondition::Tornado { remaining: 2 }]
+            b.grid[4][4].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 2 }]
         );
 
         // Ply 1 (White rook shuffles): tick 2 → 1.
         b.make_move(move_to(c(2, 7), c(3, 7)))
             .expect("white rook move is legal (compulsion not armed)");
         assert_eq!(
-            b.grid[4][4].conditions,
-            vec![SquareCondition::Tornado { remaining: 1 }],
+            b.grid[4][4].conditions.as_slice(),
+            [SquareCondition::Tornado { remaining: 1 }],
             "one real make_move ply must tick the tornado once"
         );
 
@@ -1103,4 +1119,27 @@ mod tests {
         );
         assert_eq!(m2.len(), moves.len());
     }
+
+    /// `all_legal_moves` sweeps every piece of the side under one
+    /// epoch, so the probe computes once for the whole side, not once
+    /// per piece.
+    #[test]
+    fn probe_computed_once_per_side_wide_sweep() {
+        let mut b = board8();
+        b.grid[0][0] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        b.grid[7][7] = Square::new().set_piece(PieceType::new_rook(Color::White));
+        b.grid[3][3] = Square::new()
+            .add_square_condition(SquareCondition::Tornado { remaining: 3 });
+        b.grid[6][5] = Square::new().set_piece(PieceType::new_king(Color::Black));
+
+        let before = probe_compute_count();
+        let all = b.all_legal_moves(Color::White);
+        assert_eq!(probe_compute_count() - before, 1);
+
+        let per_square: Vec<_> = [c(0, 0), c(7, 7)]
+            .iter()
+            .flat_map(|sq| b.legal_moves(sq))
+            .collect();
+        assert_eq!(all, per_square);
+    }
 }
@@ -0,0 +1,724 @@
+//! PGN import / export.
+//!
+//! Movetext is SAN, fairy extensions included (see `board::san`). A
+//! game that doesn't start from the standard position carries
+//! `[SetUp "1"]` and `[FEN "..."]` tags holding the engine's extended
+//! FEN, so trains, signals, square conditions, `tr=` and `lm=` all
+//! survive the trip.
+//!
+//! Comments, NAGs and variations are kept as a tree — a `PgnLine` of
+//! `PgnMove`s, each carrying the alternatives to itself — so a file
+//! reads back to the same `PgnGame` it was written from. `!`/`?`
+//! glyphs become their NAGs ($1–$6) on read and `;` comments become
+//! brace comments; neither form is written back.
+//!
+//! Reading replays every move, variations included, through
+//! `Board::san_to_move` and `Board::make_move`, and stops at the first
+//! one that doesn't resolve with `PgnError::IllegalMove`.
+
+use crate::{
+    board::{
+        Board, GameMove, GameStatus, MoveError,
+        fen::{FenError, board_to_fen, fen_to_board},
+    },
+    game::Game,
+    pieces::Color,
+};
+
+/// The standard starting position. Games that start here get no
+/// `SetUp` / `FEN` tags.
+pub const STANDARD_START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -";
+
+/// Longest movetext line the writer produces (PGN export format).
+const LINE_WIDTH: usize = 80;
+
+/// Move-suffix glyphs and the NAGs they stand for.
+const GLYPH_NAGS: [(&str, u8); 6] = [
+    ("!!", 3),
+    ("??", 4),
+    ("!?", 5),
+    ("?!", 6),
+    ("!", 1),
+    ("?", 2),
+];
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum PgnError {
+    /// The input held no game — no tags and no movetext.
+    NoGame,
+    /// A `[...]` tag pair that isn't `[Name "value"]`.
+    BadTag(String),
+    /// A `{` with no closing `}`.
+    UnterminatedComment,
+    /// A `(` with no matching `)`, or a stray `)`.
+    UnbalancedVariation,
+    /// A NAG, variation or other token where the movetext can't take
+    /// one (e.g. `$1` or `(` before the first move of a line).
+    UnexpectedToken(String),
+    /// The `FEN` tag didn't parse.
+    BadFen(FenError),
+    /// The first move that couldn't be played, identified the way the
+    /// movetext numbers it (`move_number`, plus `color` for White's or
+    /// Black's half).
+    IllegalMove {
+        move_number: u32,
+        color: Color,
+        san: String,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PgnError::NoGame => write!(f, "PGN contains no game."),
+            PgnError::BadTag(tag) => write!(f, "Malformed PGN tag: {tag}"),
+            PgnError::UnterminatedComment => write!(f, "PGN comment is never closed."),
+            PgnError::UnbalancedVariation => {
+                write!(f, "PGN variation parentheses don't balance.")
+            }
+            PgnError::UnexpectedToken(t) => write!(f, "Unexpected '{t}' in PGN movetext."),
+            PgnError::BadFen(e) => write!(f, "PGN FEN tag is invalid: {e}"),
+            PgnError::IllegalMove {
+                move_number,
+                color,
+                san,
+                reason,
+            } => {
+                let dots = if *color == Color::Black { "..." } else { "." };
+                write!(f, "Illegal move {move_number}{dots} {san}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// One game of a PGN file.
+#[derive(PartialEq, Debug, Clone)]
+pub struct PgnGame {
+    /// Tag pairs in file order. `SetUp` and `FEN` are not kept here:
+    /// they describe `start` and are written from it.
+    pub tags: Vec<(String, String)>,
+    pub start: Board,
+    pub line: PgnLine,
+    /// Game termination marker: `1-0`, `0-1`, `1/2-1/2` or `*`.
+    pub result: String,
+}
+
+/// A sequence of moves — the main line or a variation.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct PgnLine {
+    /// Comments before the first move.
+    pub comments: Vec<String>,
+    pub moves: Vec<PgnMove>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PgnMove {
+    pub game_move: GameMove,
+    pub nags: Vec<u8>,
+    /// Comments after the move.
+    pub comments: Vec<String>,
+    /// Alternatives to this move, each starting from the position
+    /// this move was played from.
+    pub variations: Vec<PgnLine>,
+}
+
+impl PgnMove {
+    fn new(game_move: GameMove) -> Self {
+        PgnMove {
+            game_move,
+            nags: Vec::new(),
+            comments: Vec::new(),
+            variations: Vec::new(),
+        }
+    }
+}
+
+/// Termination marker for a finished (or unfinished) position.
+fn result_for(status: &GameStatus) -> &'static str {
+    match status {
+        GameStatus::Checkmate {
+            winner: Color::White,
+        }
+        | GameStatus::BrainrotWin {
+            winner: Color::White,
+        }
+        | GameStatus::Won {
+            winner: Color::White,
+            ..
+        } => "1-0",
+        GameStatus::Checkmate {
+            winner: Color::Black,
+        }
+        | GameStatus::BrainrotWin {
+            winner: Color::Black,
+        }
+        | GameStatus::Won {
+            winner: Color::Black,
+            ..
+        } => "0-1",
+        GameStatus::Stalemate | GameStatus::Draw { .. } => "1/2-1/2",
+        _ => "*",
+    }
+}
+
+impl PgnGame {
+    /// Main line of `game` (moves up to its current ply), with a Seven
+    /// Tag Roster of placeholders and the result taken from the game's
+    /// status.
+    pub fn from_game(game: &Game) -> Self {
+        let result = result_for(&game.status()).to_string();
+        let tags = [
+            ("Event", "?"),
+            ("Site", "?"),
+            ("Date", "????.??.??"),
+            ("Round", "?"),
+            ("White", "?"),
+            ("Black", "?"),
+            ("Result", result.as_str()),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        PgnGame {
+            tags,
+            start: game.start().clone(),
+            line: PgnLine {
+                comments: Vec::new(),
+                moves: game
+                    .moves()
+                    .iter()
+                    .map(|p| PgnMove::new(p.game_move.clone()))
+                    .collect(),
+            },
+            result,
+        }
+    }
+
+    /// Replay the main line into a `Game`.
+    pub fn to_game(&self) -> Result<Game, MoveError> {
+        let mut game = Game::new(self.start.clone());
+        for mv in &self.line.moves {
+            game.push_move(mv.game_move.clone())?;
+        }
+        Ok(game)
+    }
+
+    /// Value of the first tag called `name`.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// ---------------------------------------------------------------------
+// Writing
+// ---------------------------------------------------------------------
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Export `game` as PGN text. Fails only if a move in `game` isn't
+/// legal where it stands, which can't happen for a `PgnGame` that came
+/// from `pgn_to_games` or `PgnGame::from_game`.
+pub fn game_to_pgn(game: &PgnGame) -> Result<String, MoveError> {
+    let mut out = String::new();
+    for (name, value) in &game.tags {
+        out.push_str(&format!("[{name} \"{}\"]\n", escape_tag_value(value)));
+    }
+    let standard = fen_to_board(STANDARD_START_FEN).expect("standard FEN parses");
+    if game.start != standard {
+        out.push_str("[SetUp \"1\"]\n");
+        out.push_str(&format!(
+            "[FEN \"{}\"]\n",
+            escape_tag_value(&board_to_fen(&game.start))
+        ));
+    }
+    out.push('\n');
+
+    let mut tokens = Vec::new();
+    write_line(&game.start, &game.line, &mut tokens)?;
+    tokens.push(game.result.clone());
+
+    // Parentheses hug their variation: `(1... c5 2. Nf3)`.
+    let mut width = 0;
+    let mut prev_open = false;
+    for token in tokens {
+        let hug = prev_open || token == ")";
+        if width > 0 && width + usize::from(!hug) + token.len() > LINE_WIDTH {
+            out.push('\n');
+            width = 0;
+        } else if width > 0 && !hug {
+            out.push(' ');
+            width += 1;
+        }
+        width += token.len();
+        prev_open = token == "(";
+        out.push_str(&token);
+    }
+    out.push('\n');
+    Ok(out)
+}
+
+fn write_line(start: &Board, line: &PgnLine, tokens: &mut Vec<String>) -> Result<(), MoveError> {
+    for comment in &line.comments {
+        tokens.push(format!("{{{comment}}}"));
+    }
+    let mut board = start.clone();
+    // Black's moves get an explicit `N...` at the start of a line and
+    // after anything that interrupts the move pairs.
+    let mut need_number = true;
+    for mv in &line.moves {
+        // The number rides in the move's token so wrapping never
+        // separates the two.
+        let number = board.flags.fullmove_number;
+        let san = board.move_to_san(&mv.game_move)?;
+        tokens.push(match board.flags.side_to_move {
+            Color::Black if need_number => format!("{number}... {san}"),
+            Color::Black => san,
+            _ => format!("{number}. {san}"),
+        });
+        need_number = false;
+        for nag in &mv.nags {
+            tokens.push(format!("${nag}"));
+        }
+        for comment in &mv.comments {
+            tokens.push(format!("{{{comment}}}"));
+            need_number = true;
+        }
+        for variation in &mv.variations {
+            tokens.push("(".to_string());
+            write_line(&board, variation, tokens)?;
+            tokens.push(")".to_string());
+            need_number = true;
+        }
+        board.make_move(mv.game_move.clone())?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------
+// Reading
+// ---------------------------------------------------------------------
+
+#[derive(PartialEq, Debug, Clone)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    Open,
+    Close,
+    Result(String),
+    San(String),
+}
+
+fn is_result(symbol: &str) -> bool {
+    matches!(symbol, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
+
+fn parse_tag(body: &str) -> Result<Token, PgnError> {
+    let bad = || PgnError::BadTag(format!("[{body}]"));
+    let body = body.trim();
+    let (name, rest) = body.split_once(char::is_whitespace).ok_or_else(bad)?;
+    let rest = rest.trim();
+    let inner = rest
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .ok_or_else(bad)?;
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next().ok_or_else(bad)?),
+            '"' => return Err(bad()),
+            c => value.push(c),
+        }
+    }
+    Ok(Token::Tag(name.to_string(), value))
+}
+
+/// Split a symbol token into its tokens: an optional move-number
+/// prefix (dropped), the SAN, and any `!`/`?` glyph suffix as a NAG.
+fn push_symbol(symbol: &str, tokens: &mut Vec<Token>) {
+    if is_result(symbol) {
+        tokens.push(Token::Result(symbol.to_string()));
+        return;
+    }
+    let digits = symbol.len() - symbol.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let mut rest = symbol;
+    if digits > 0 && symbol[digits..].starts_with('.') {
+        rest = symbol[digits..].trim_start_matches('.');
+    }
+    if rest.is_empty() {
+        return;
+    }
+    let san = rest.trim_end_matches(['!', '?']);
+    let glyph = &rest[san.len()..];
+    tokens.push(Token::San(san.to_string()));
+    if let Some((_, nag)) = GLYPH_NAGS.iter().find(|(g, _)| *g == glyph) {
+        tokens.push(Token::Nag(*nag));
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, PgnError> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    let take_until = |from: usize, end: char| -> Option<usize> {
+        chars[from..].iter().position(|&c| c == end).map(|p| from + p)
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            // Escape line: ignored entirely.
+            '%' if i == 0 || chars[i - 1] == '\n' => {
+                i = take_until(i, '\n').unwrap_or(chars.len());
+            }
+            '[' => {
+                // `]` inside a quoted value doesn't close the tag.
+                let mut j = i + 1;
+                let mut quoted = false;
+                while j < chars.len() && (quoted || chars[j] != ']') {
+                    match chars[j] {
+                        '\\' if quoted => j += 1,
+                        '"' => quoted = !quoted,
+                        _ => {}
+                    }
+                    j += 1;
+                }
+                let body: String = chars[i + 1..j.min(chars.len())].iter().collect();
+                if j >= chars.len() {
+                    return Err(PgnError::BadTag(format!("[{body}")));
+                }
+                tokens.push(parse_tag(&body)?);
+                i = j + 1;
+            }
+            '{' => {
+                let end = take_until(i, '}').ok_or(PgnError::UnterminatedComment)?;
+                let body: String = chars[i + 1..end].iter().collect();
+                tokens.push(Token::Comment(body.trim().to_string()));
+                i = end + 1;
+            }
+            ';' => {
+                let end = take_until(i, '\n').unwrap_or(chars.len());
+                let body: String = chars[i + 1..end].iter().collect();
+                tokens.push(Token::Comment(body.trim().to_string()));
+                i = end;
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            '$' => {
+                let digits: String = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                let nag = digits
+                    .parse()
+                    .map_err(|_| PgnError::UnexpectedToken(format!("${digits}")))?;
+                tokens.push(Token::Nag(nag));
+                i += 1 + digits.len();
+            }
+            _ => {
+                let symbol: String = chars[i..]
+                    .iter()
+                    .take_while(|c| !c.is_whitespace() && !"{}()[];$".contains(**c))
+                    .collect();
+                i += symbol.chars().count();
+                push_symbol(&symbol, &mut tokens);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Reader {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        if self.peek().is_none() {
+            return Ok(None);
+        }
+        let mut tags = Vec::new();
+        let mut fen = None;
+        while let Some(Token::Tag(..)) = self.peek() {
+            let Some(Token::Tag(name, value)) = self.next() else {
+                unreachable!()
+            };
+            match name.as_str() {
+                "FEN" => fen = Some(value),
+                "SetUp" => {}
+                _ => tags.push((name, value)),
+            }
+        }
+        let start = fen_to_board(fen.as_deref().unwrap_or(STANDARD_START_FEN))
+            .map_err(PgnError::BadFen)?;
+        let line = self.read_line(start.clone(), false)?;
+        let result = match self.peek() {
+            Some(Token::Result(r)) => {
+                let r = r.clone();
+                self.pos += 1;
+                r
+            }
+            _ => "*".to_string(),
+        };
+        Ok(Some(PgnGame {
+            tags,
+            start,
+            line,
+            result,
+        }))
+    }
+
+    /// Read moves from `board` until the line ends: a result or the
+    /// next game's tags for the main line, the closing `)` (consumed)
+    /// for a variation.
+    fn read_line(&mut self, mut board: Board, nested: bool) -> Result<PgnLine, PgnError> {
+        let mut line = PgnLine::default();
+        // Position the last move was played from — where its
+        // variations start.
+        let mut before: Option<Board> = None;
+        loop {
+            let Some(token) = self.peek().cloned() else {
+                return if nested {
+                    Err(PgnError::UnbalancedVariation)
+                } else {
+                    Ok(line)
+                };
+            };
+            match token {
+                Token::Tag(..) | Token::Result(_) if !nested => return Ok(line),
+                Token::Tag(..) | Token::Result(_) => return Err(PgnError::UnbalancedVariation),
+                Token::Close if nested => {
+                    self.pos += 1;
+                    return Ok(line);
+                }
+                Token::Close => return Err(PgnError::UnbalancedVariation),
+                Token::Comment(text) => {
+                    self.pos += 1;
+                    match line.moves.last_mut() {
+                        Some(mv) => mv.comments.push(text),
+                        None => line.comments.push(text),
+                    }
+                }
+                Token::Nag(nag) => {
+                    self.pos += 1;
+                    let mv = line
+                        .moves
+                        .last_mut()
+                        .ok_or_else(|| PgnError::UnexpectedToken(format!("${nag}")))?;
+                    mv.nags.push(nag);
+                }
+                Token::Open => {
+                    self.pos += 1;
+                    let from = before
+                        .clone()
+                        .ok_or_else(|| PgnError::UnexpectedToken("(".to_string()))?;
+                    let variation = self.read_line(from, true)?;
+                    line.moves
+                        .last_mut()
+                        .expect("`before` is set once a move is read")
+                        .variations
+                        .push(variation);
+                }
+                Token::San(san) => {
+                    self.pos += 1;
+                    let (move_number, color) =
+                        (board.flags.fullmove_number, board.flags.side_to_move);
+                    let illegal = |reason: String| PgnError::IllegalMove {
+                        move_number,
+                        color,
+                        san: san.clone(),
+                        reason,
+                    };
+                    let mv = board.san_to_move(&san).map_err(|e| illegal(e.to_string()))?;
+                    let prev = board.clone();
+                    board
+                        .make_move(mv.clone())
+                        .map_err(|e| illegal(e.to_string()))?;
+                    before = Some(prev);
+                    line.moves.push(PgnMove::new(mv));
+                }
+            }
+        }
+    }
+}
+
+/// Parse every game in `text`.
+pub fn pgn_to_games(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut reader = Reader {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    let mut games = Vec::new();
+    while let Some(game) = reader.read_game()? {
+        games.push(game);
+    }
+    Ok(games)
+}
+
+/// Parse the first game in `text`.
+pub fn pgn_to_game(text: &str) -> Result<PgnGame, PgnError> {
+    pgn_to_games(text)?
+        .into_iter()
+        .next()
+        .ok_or(PgnError::NoGame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Coord, square::Square};
+    use crate::pieces::{fairy::bus::Bus, piecetype::PieceType};
+
+    fn standard() -> Board {
+        fen_to_board(STANDARD_START_FEN).unwrap()
+    }
+
+    #[test]
+    fn from_game_writes_seven_tag_roster_and_result() {
+        let mut game = Game::new(standard());
+        for san in ["f3", "e5", "g4", "Qh4"] {
+            let mv = game.board().san_to_move(san).unwrap();
+            game.push_move(mv).unwrap();
+        }
+        let text = game_to_pgn(&PgnGame::from_game(&game)).unwrap();
+        assert!(text.starts_with("[Event \"?\"]\n"));
+        assert!(text.contains("[Result \"0-1\"]\n"));
+        assert!(!text.contains("[FEN"), "standard start needs no FEN tag");
+        assert!(text.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"), "{text}");
+
+        let back = pgn_to_game(&text).unwrap();
+        assert_eq!(back.to_game().unwrap().board(), game.board());
+    }
+
+    #[test]
+    fn comments_nags_and_variations_round_trip() {
+        let text = "[Event \"Casual \\\"blitz\\\"\"]\n\n\
+                    {Opening} 1. e4 $1 {best by test} e5 (1... c5!? 2. Nf3 (2. c3) d6) \
+                    2. Nf3 ; the usual\n Nc6?! 1/2-1/2\n";
+        let game = pgn_to_game(text).unwrap();
+        assert_eq!(game.tag("Event"), Some("Casual \"blitz\""));
+        assert_eq!(game.result, "1/2-1/2");
+        assert_eq!(game.line.comments, ["Opening"]);
+        let moves = &game.line.moves;
+        assert_eq!(moves.len(), 4);
+        assert_eq!(moves[0].nags, [1]);
+        assert_eq!(moves[0].comments, ["best by test"]);
+        assert_eq!(moves[1].variations.len(), 1);
+        let sicilian = &moves[1].variations[0];
+        assert_eq!(sicilian.moves[0].nags, [5]);
+        assert_eq!(sicilian.moves[1].variations[0].moves.len(), 1);
+        assert_eq!(moves[2].comments, ["the usual"]);
+        assert_eq!(moves[3].nags, [6]);
+
+        let written = game_to_pgn(&game).unwrap();
+        assert!(
+            written.contains("(1... c5 $5 2. Nf3 (2. c3) 2... d6)"),
+            "{written}"
+        );
+        assert!(written.contains("2. Nf3 {the usual} 2... Nc6 $6"), "{written}");
+        assert_eq!(pgn_to_game(&written).unwrap(), game);
+    }
+
+    #[test]
+    fn fairy_start_round_trips_through_fen_tag() {
+        let mut start = fen_to_board("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+        start.grid[5][2] = Square::new().set_piece(PieceType::Bus(Bus {
+            color: Color::White,
+            pieces: vec![PieceType::new_knight(Color::White)].into(),
+        }));
+        let mut game = Game::new(start);
+        let exit = game.board().san_to_move("c3/0:Nd5").unwrap();
+        game.push_move(exit).unwrap();
+        let pgn = PgnGame::from_game(&game);
+        let text = game_to_pgn(&pgn).unwrap();
+        assert!(text.contains("[SetUp \"1\"]\n[FEN \""), "{text}");
+        assert!(text.contains("1. c3/0:Nd5 *"), "{text}");
+        let back = pgn_to_game(&text).unwrap();
+        assert_eq!(back, pgn);
+        assert_eq!(
+            back.to_game().unwrap().board().grid[3][3]
+                .piece
+                .as_ref()
+                .map(|p| p.symbol().into_owned()),
+            Some("N".to_string())
+        );
+    }
+
+    #[test]
+    fn black_to_move_start_numbers_with_ellipsis() {
+        let start = fen_to_board("4k3/p7/8/8/8/8/P7/4K3 b - - 0 12").unwrap();
+        let mut game = Game::new(start);
+        for san in ["Kd7", "Kd2"] {
+            let mv = game.board().san_to_move(san).unwrap();
+            game.push_move(mv).unwrap();
+        }
+        let text = game_to_pgn(&PgnGame::from_game(&game)).unwrap();
+        assert!(text.contains("\n12... Kd7 13. Kd2 *\n"), "{text}");
+    }
+
+    #[test]
+    fn first_illegal_move_is_reported() {
+        let err = pgn_to_game("1. e4 e5 2. Ke3 Nc6 *").unwrap_err();
+        assert!(matches!(
+            err,
+            PgnError::IllegalMove { move_number: 2, color: Color::White, ref san, .. } if san == "Ke3"
+        ));
+        let err = pgn_to_game("1. e4 (1... e5) *").unwrap_err();
+        assert!(matches!(
+            err,
+            PgnError::IllegalMove { move_number: 1, color: Color::White, ref san, .. } if san == "e5"
+        ));
+    }
+
+    #[test]
+    fn structural_errors() {
+        assert_eq!(pgn_to_game(""), Err(PgnError::NoGame));
+        assert_eq!(pgn_to_game("1. e4 {oops"), Err(PgnError::UnterminatedComment));
+        assert_eq!(pgn_to_game("1. e4 (1. d4"), Err(PgnError::UnbalancedVariation));
+        assert_eq!(pgn_to_game("1. e4 e5)"), Err(PgnError::UnbalancedVariation));
+        assert!(matches!(pgn_to_game("[Event]"), Err(PgnError::BadTag(_))));
+        assert!(matches!(
+            pgn_to_game("[FEN \"\"]\n*"),
+            Err(PgnError::BadFen(FenError::EmptyInput))
+        ));
+    }
+
+    #[test]
+    fn reads_several_games() {
+        let text = "[Event \"one\"]\n\n1. e4 *\n\n[Event \"two\"]\n\n1. d4 d5 1-0\n";
+        let games = pgn_to_games(text).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].tag("Event"), Some("two"));
+        assert_eq!(games[1].line.moves.len(), 2);
+        assert_eq!(
+            games[0].line.moves[0].game_move.from,
+            Coord { file: 4, rank: 6 }
+        );
+    }
+}
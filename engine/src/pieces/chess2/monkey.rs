@@ -11,12 +11,14 @@
 /// . . . . . . . .
 ///
 /// This should be done recursively, to allow for multiple jumps in a single move
+use std::borrow::Cow;
+
 use crate::{
     board::{Board, Coord, GameMove, MoveType},
     pieces::{Color, Piece},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Monkey {
     pub color: Color,
 }
@@ -81,11 +83,11 @@ impl Piece for Monkey {
         self.find_jump_moves(board, from, from, &mut visited, &mut moves);
         moves
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'M'.to_string(),
-            Color::Black => 'm'.to_string(),
-            Color::Neutral => 'M'.to_string(),
+            Color::White => Cow::Borrowed("M"),
+            Color::Black => Cow::Borrowed("m"),
+            Color::Neutral => Cow::Borrowed("M"),
         }
     }
 
@@ -215,7 +217,7 @@ impl Monkey {
             // Plan 08: the Monkey can't land on non-walkable terrain (closed
             // Gate / Turret / Vent) — neither as a jump landing nor as a
             // capture target.
-            if !jump_square.square_type.is_walkable() {
+            if !jump_square.is_walkable() {
                 continue;
             }
 
@@ -7,6 +7,8 @@
 /// The Bus moves like a standard rook - horizontally or vertically until there is a piece blocking the path.
 /// The Bus cannot take pieces.
 /// To exit the Bus, a piece simply moves out of the square the Bus occupies, following its usual movement rules.
+use std::borrow::Cow;
+
 use tracing::{trace, warn};
 
 use crate::{
@@ -14,20 +16,20 @@ use crate::{
         Board, Coord, GameMove, MoveType,
         fen::{find_matching_paren, split_top_level},
     },
-    pieces::{Color, Piece, piecetype::PieceType},
+    pieces::{Color, Piece, fairy::passengers::Passengers, piecetype::PieceType},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Bus {
     pub color: Color,
-    pub pieces: Vec<PieceType>,
+    pub pieces: Passengers,
 }
 
 impl Bus {
     pub fn new(color: Color) -> Self {
         Bus {
             color,
-            pieces: vec![],
+            pieces: Passengers::default(),
         }
     }
 
@@ -54,7 +56,7 @@ impl Bus {
             trace!("Bus with no bracketed contents");
             return Some(PieceType::Bus(Bus {
                 color,
-                pieces: vec![],
+                pieces: Passengers::default(),
             }));
         };
 
@@ -148,7 +150,10 @@ impl Bus {
             }
         }
 
-        Some(PieceType::Bus(Bus { color, pieces }))
+        Some(PieceType::Bus(Bus {
+            color,
+            pieces: pieces.into(),
+        }))
     }
 }
 
@@ -169,7 +174,7 @@ impl Piece for Bus {
         Some(&self.pieces)
     }
     fn passengers_mut(&mut self) -> Option<&mut Vec<PieceType>> {
-        Some(&mut self.pieces)
+        Some(self.pieces.to_mut())
     }
     fn initial_moves(&self, board: &Board, from: &Coord) -> Vec<GameMove> {
         trace!("bus initial_moves");
@@ -201,7 +206,7 @@ impl Piece for Bus {
                     // Plan 08: non-walkable terrain blocks the Bus's slide
                     // the same way a piece does — and we don't emit a move
                     // onto it.
-                    Some(sq) if !sq.square_type.is_walkable() => break,
+                    Some(sq) if !sq.is_walkable() => break,
                     Some(sq) if sq.piece.is_none() => {
                         moves.push(GameMove {
                             from: from.clone(),
@@ -256,27 +261,29 @@ impl Piece for Bus {
 
         moves
     }
-    fn symbol(&self) -> String {
-        let mut sym = match self.color {
-            Color::White => "BUS".to_string(),
-            Color::Black => "bus".to_string(),
-            Color::Neutral => "BUS".to_string(),
+    fn symbol(&self) -> Cow<'static, str> {
+        let base = match self.color {
+            Color::White => "BUS",
+            Color::Black => "bus",
+            Color::Neutral => "BUS",
         };
-
-        if !self.pieces.is_empty() {
-            let pieces_map = self
-                .pieces
-                .iter()
-                .map(|piece| piece.symbol())
-                .collect::<Vec<String>>()
-                .join(",");
-            trace!(pieces_map, "bus symbol pieces");
-            sym.push_str("(P=(");
-            sym.push_str(&pieces_map);
-            sym.push_str("))");
+        if self.pieces.is_empty() {
+            return Cow::Borrowed(base);
         }
 
-        sym
+        let mut sym = base.to_string();
+        let pieces_map = self
+            .pieces
+            .iter()
+            .map(|piece| piece.symbol())
+            .collect::<Vec<_>>()
+            .join(",");
+        trace!(pieces_map, "bus symbol pieces");
+        sym.push_str("(P=(");
+        sym.push_str(&pieces_map);
+        sym.push_str("))");
+
+        Cow::Owned(sym)
     }
     fn clone_box(&self) -> Box<dyn Piece> {
         Box::new(self.clone())
@@ -2,6 +2,8 @@
 //! locomotive at the head of the chain; its next-tick tile is the
 //! current tile of the cart immediately in front of it (chain_index − 1).
 
+use std::borrow::Cow;
+
 use tracing::{trace, warn};
 
 use crate::{
@@ -11,17 +13,20 @@ use crate::{
     },
     pieces::{
         Color, Piece,
-        fairy::locomotive::{passenger_moves, target_is_any_train_cart},
+        fairy::{
+            locomotive::{passenger_moves, target_is_any_train_cart},
+            passengers::Passengers,
+        },
         piecetype::PieceType,
     },
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Carriage {
     pub train_id: u32,
     /// 1..255; 0 is the locomotive at the head of the same `train_id`.
     pub chain_index: u8,
-    pub passengers: Vec<PieceType>,
+    pub passengers: Passengers,
 }
 
 impl Carriage {
@@ -29,7 +34,7 @@ impl Carriage {
         Carriage {
             train_id,
             chain_index,
-            passengers: vec![],
+            passengers: Passengers::default(),
         }
     }
 
@@ -104,7 +109,7 @@ impl Carriage {
         Some(PieceType::Carriage(Carriage {
             train_id,
             chain_index,
-            passengers,
+            passengers: passengers.into(),
         }))
     }
 }
@@ -126,7 +131,7 @@ impl Piece for Carriage {
         Some(&self.passengers)
     }
     fn passengers_mut(&mut self) -> Option<&mut Vec<PieceType>> {
-        Some(&mut self.passengers)
+        Some(self.passengers.to_mut())
     }
     /// Carriages, like locomotives, don't emit player-driven moves of
     /// their own — only the passengers do.
@@ -179,7 +184,7 @@ impl Piece for Carriage {
     fn would_capture_at(&self, board: &Board, _from: &Coord, target: &Coord) -> bool {
         !target_is_any_train_cart(board, target)
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         let mut s = format!("CART(ID={},I={}", self.train_id, self.chain_index);
         if !self.passengers.is_empty() {
             let p = self
@@ -191,7 +196,7 @@ impl Piece for Carriage {
             s.push_str(&format!(",P=({p})"));
         }
         s.push(')');
-        s
+        Cow::Owned(s)
     }
     fn clone_box(&self) -> Box<dyn Piece> {
         Box::new(self.clone())
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 use tracing::{trace, warn};
 
@@ -23,20 +23,21 @@ use tracing::{trace, warn};
 /// Both match the plan-09 Q7 silent-passenger-removal precedent and
 /// are pinned by tests in `engine/src/board/tests.rs`.
 ///
-/// The "taking piece can move again" half of the spec (plan-04
-/// mechanic 2) is NOT implemented in this refactor. Tracked as a
-/// follow-up; needs `extra_moves: u8` on `BoardFlags` + gated side-
-/// flip in `apply_environment_reactions`.
+/// The "taking piece can move again" half (plan-04 mechanic 2) is a
+/// bonus move for the taker's side — `BoardFlags::extra_moves`, granted
+/// by the same capture handler. It's forfeited when the capture gives
+/// check, since a second move could then take the king.
 use crate::{
     board::{
         Board, Coord, GameMove, MoveType,
         fen::{find_matching_paren, split_top_level},
+        make_move::PreMove,
     },
     movement::glider::{OMNI_DIRS, generate_glider_moves},
     pieces::{Color, Piece, piecetype::PieceType},
 };
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum GoblinState {
     Free, // hasn't kidnapped any piece
     Kidnapping {
@@ -44,7 +45,7 @@ pub enum GoblinState {
     },
 }
 
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Goblin {
     pub color: Color,
     pub state: GoblinState,
@@ -282,7 +283,7 @@ impl Piece for Goblin {
         matches!(self.state, GoblinState::Free)
     }
 
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         let prefix = match self.color {
             Color::White => "G",
             Color::Black => "g",
@@ -292,21 +293,21 @@ impl Piece for Goblin {
         match &self.state {
             GoblinState::Free => {
                 // Free state only encodes home square
-                format!(
+                Cow::Owned(format!(
                     "{}(H={}-{})",
                     prefix, self.home_square.file, self.home_square.rank
-                )
+                ))
             }
 
             GoblinState::Kidnapping { piece } => {
                 // Include home square AND kidnapped piece symbol
-                format!(
+                Cow::Owned(format!(
                     "{}(H={}-{},P={})",
                     prefix,
                     self.home_square.file,
                     self.home_square.rank,
                     piece.symbol()
-                )
+                ))
             }
         }
     }
@@ -317,7 +318,7 @@ impl Piece for Goblin {
 
     fn post_move_effects(
         &self,
-        board_before: &Board,
+        before: &PreMove,
         board_after: &mut Board,
         game_move: &GameMove,
     ) {
@@ -341,7 +342,7 @@ impl Piece for Goblin {
         };
         match &self.state {
             GoblinState::Free => {
-                if let Some(square) = board_before.get_square_at(&to) {
+                if let Some(square) = before.square_at(&to) {
                     if let Some(captured_piece) = &square.piece {
                         if captured_piece.get_color() != self.color {
                             // A kidnapped king (direct or hidden as a
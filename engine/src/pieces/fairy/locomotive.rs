@@ -2,6 +2,8 @@
 //! the track tiles, dragging its `Carriage`s behind it. Locomotives are
 //! neutral — never the side-to-move — and carry passengers like a Bus.
 
+use std::borrow::Cow;
+
 use tracing::{trace, warn};
 
 use crate::{
@@ -10,13 +12,13 @@ use crate::{
         fen::{find_matching_paren, split_top_level},
         square::TrackDir,
     },
-    pieces::{Color, Piece, piecetype::PieceType},
+    pieces::{Color, Piece, fairy::passengers::Passengers, piecetype::PieceType},
 };
 
 /// Direction a train walks along its track tiles. `Forward` follows each
 /// `Track`'s stored `direction`; `Reverse` follows the opposite. There is
 /// no in-game way to set this in v1 — it's editor-time only.
-#[derive(Clone, PartialEq, Debug, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy, serde::Serialize, serde::Deserialize)]
 pub enum TrainHeading {
     Forward,
     Reverse,
@@ -39,11 +41,11 @@ impl TrainHeading {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Locomotive {
     pub train_id: u32,
     pub heading: TrainHeading,
-    pub passengers: Vec<PieceType>,
+    pub passengers: Passengers,
     /// Cardinal direction this loco *entered* its current tile through.
     /// `None` means "no last move yet" — the next tick is the first
     /// since the loco was placed (or since the FEN was loaded), and the
@@ -59,7 +61,7 @@ impl Locomotive {
         Locomotive {
             train_id,
             heading,
-            passengers: vec![],
+            passengers: Passengers::default(),
             last_dir: None,
         }
     }
@@ -133,7 +135,7 @@ impl Locomotive {
         Some(PieceType::Locomotive(Locomotive {
             train_id,
             heading,
-            passengers,
+            passengers: passengers.into(),
             last_dir,
         }))
     }
@@ -156,7 +158,7 @@ impl Piece for Locomotive {
         Some(&self.passengers)
     }
     fn passengers_mut(&mut self) -> Option<&mut Vec<PieceType>> {
-        Some(&mut self.passengers)
+        Some(self.passengers.to_mut())
     }
     /// Locomotives don't emit player-driven moves of their own. Movement
     /// happens automatically during `Board::advance_trains`. Passengers,
@@ -194,7 +196,7 @@ impl Piece for Locomotive {
     fn would_capture_at(&self, board: &Board, _from: &Coord, target: &Coord) -> bool {
         !target_is_any_train_cart(board, target)
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         let mut s = format!("LOCO(ID={},H={}", self.train_id, self.heading.as_str());
         if let Some(d) = self.last_dir {
             s.push_str(&format!(",L={}", d.as_str()));
@@ -209,7 +211,7 @@ impl Piece for Locomotive {
             s.push_str(&format!(",P=({p})"));
         }
         s.push(')');
-        s
+        Cow::Owned(s)
     }
     fn clone_box(&self) -> Box<dyn Piece> {
         Box::new(self.clone())
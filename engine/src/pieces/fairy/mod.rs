@@ -2,5 +2,6 @@ pub mod bus;
 pub mod carriage;
 pub mod goblin;
 pub mod locomotive;
+pub mod passengers;
 pub mod skibidi;
 pub mod stormcaller;
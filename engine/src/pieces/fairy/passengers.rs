@@ -0,0 +1,92 @@
+//! A carrier's passenger list.
+//!
+//! Bus, Locomotive and Carriage each hold the pieces riding inside
+//! them. A passenger is a `PieceType`, which can itself be a carrier,
+//! so the list can't be stored inline in the piece — some indirection
+//! is unavoidable. `Passengers` makes that indirection cheap to copy:
+//! the list is shared behind an `Arc` and copied on the first write
+//! (`to_mut`), so cloning a loaded carrier — and with it the board it
+//! stands on — doesn't allocate. An empty list holds no `Arc` at all.
+//!
+//! It reads as a slice (`Deref`), builds from a `Vec`, and serialises
+//! exactly as the `Vec<PieceType>` it replaced.
+
+use std::fmt::{Debug, Formatter, Result};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::pieces::piecetype::PieceType;
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(from = "Vec<PieceType>", into = "Vec<PieceType>")]
+pub struct Passengers(Option<Arc<Vec<PieceType>>>);
+
+impl Passengers {
+    pub fn as_slice(&self) -> &[PieceType] {
+        self.0.as_deref().map_or(&[], Vec::as_slice)
+    }
+
+    /// The list to edit, copied first if another carrier shares it.
+    pub fn to_mut(&mut self) -> &mut Vec<PieceType> {
+        Arc::make_mut(self.0.get_or_insert_with(Default::default))
+    }
+}
+
+impl Deref for Passengers {
+    type Target = [PieceType];
+
+    fn deref(&self) -> &[PieceType] {
+        self.as_slice()
+    }
+}
+
+impl<'a> IntoIterator for &'a Passengers {
+    type Item = &'a PieceType;
+    type IntoIter = std::slice::Iter<'a, PieceType>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl From<Vec<PieceType>> for Passengers {
+    fn from(pieces: Vec<PieceType>) -> Self {
+        Passengers((!pieces.is_empty()).then(|| Arc::new(pieces)))
+    }
+}
+
+impl From<Passengers> for Vec<PieceType> {
+    fn from(passengers: Passengers) -> Self {
+        passengers
+            .0
+            .map(Arc::unwrap_or_clone)
+            .unwrap_or_default()
+    }
+}
+
+impl FromIterator<PieceType> for Passengers {
+    fn from_iter<I: IntoIterator<Item = PieceType>>(iter: I) -> Self {
+        Vec::from_iter(iter).into()
+    }
+}
+
+impl PartialEq for Passengers {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for Passengers {}
+
+impl Hash for Passengers {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+impl Debug for Passengers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
@@ -13,14 +13,16 @@
 ///     If your Skibidi your enemy cannot make a move due to your Brainrot,
 ///         you win by Brainrot instead of stalemate being declared.
 ///     If your Skibidi is captured while your opponent's Skibidi is in phase 4, there is nothing you can do.
+use std::borrow::Cow;
+
 use tracing::{trace, warn};
 
 use crate::{
-    board::{Board, Coord, GameMove, MoveType},
+    board::{Board, Coord, GameMove, MoveType, make_move::PreMove},
     pieces::{Color, Piece, piecetype::PieceType},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Skibidi {
     pub color: Color,
     pub phase: u8, // 1 to 4
@@ -135,7 +137,7 @@ impl Piece for Skibidi {
             };
             // Plan 08: non-walkable destinations (closed Gate / Turret / Vent)
             // are off-limits regardless of what's on them.
-            if !square.square_type.is_walkable() {
+            if !square.is_walkable() {
                 continue;
             }
             // Spec: cannot take other pieces, but can take other Skibidis.
@@ -170,17 +172,17 @@ impl Piece for Skibidi {
 
         moves
     }
-    fn symbol(&self) -> String {
-        let mut sym = match self.color {
-            Color::White => 'S'.to_string(),
-            Color::Black => 's'.to_string(),
-            Color::Neutral => 'S'.to_string(),
+    fn symbol(&self) -> Cow<'static, str> {
+        let sym = match self.color {
+            Color::White => "S",
+            Color::Black => "s",
+            Color::Neutral => "S",
         };
         if self.phase > 1 {
-            sym.push_str(&format!("(PHASE={phase})", phase = self.phase));
+            return Cow::Owned(format!("{sym}(PHASE={phase})", phase = self.phase));
         }
 
-        sym
+        Cow::Borrowed(sym)
     }
     fn clone_box(&self) -> Box<dyn Piece> {
         Box::new(self.clone())
@@ -256,7 +258,7 @@ impl Piece for Skibidi {
 
     fn post_move_effects(
         &self,
-        _board_before: &Board,
+        _before: &PreMove,
         board_after: &mut Board,
         game_move: &GameMove,
     ) {
@@ -345,7 +347,11 @@ impl Piece for Skibidi {
             | MoveType::ThrowSwitch { .. }
             // A Skibidi never makes a PlaceTornado move (Stormcaller-
             // only) — no-op here.
-            | MoveType::PlaceTornado { .. } => {}
+            | MoveType::PlaceTornado { .. }
+            // Duck moves carry no piece, and a drop doesn't move one.
+            | MoveType::PlaceDuck { .. }
+            | MoveType::MoveDuck { .. }
+            | MoveType::Drop { .. } => {}
         }
     }
 }
@@ -12,6 +12,8 @@
 //! produce them). More placers can follow later without touching the
 //! condition or the compulsion filter.
 
+use std::borrow::Cow;
+
 use crate::{
     board::{Board, Coord, GameMove, MoveType},
     pieces::{Color, Piece, piecetype::PieceType},
@@ -37,7 +39,7 @@ const KING_STEPS: [(isize, isize); 8] = [
     (1, -1),
 ];
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Stormcaller {
     pub color: Color,
 }
@@ -92,7 +94,7 @@ impl Piece for Stormcaller {
             // Reposition: step onto an EMPTY, walkable adjacent
             // square. Stormcaller cannot capture (a placer, not a
             // fighter) — occupied squares are not movement targets.
-            if sq.square_type.is_walkable() && sq.piece.is_none() {
+            if sq.is_walkable() && sq.piece.is_none() {
                 moves.push(GameMove {
                     from: from.clone(),
                     move_type: MoveType::MoveTo(coord.clone()),
@@ -109,13 +111,13 @@ impl Piece for Stormcaller {
         }
         moves
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => "W".to_string(),
-            Color::Black => "w".to_string(),
+            Color::White => Cow::Borrowed("W"),
+            Color::Black => Cow::Borrowed("w"),
             // Mirror Skibidi: a Neutral instance still renders the
             // uppercase letter.
-            Color::Neutral => "W".to_string(),
+            Color::Neutral => Cow::Borrowed("W"),
         }
     }
     fn clone_box(&self) -> Box<dyn Piece> {
@@ -140,26 +142,38 @@ impl Piece for Stormcaller {
 mod tests {
     use super::*;
     use crate::board::square::{Square, SquareCondition};
-    use crate::board::{BoardFlags, TrainTickRate};
+    use crate::board::{BoardFlags, DuckPhase, TrainTickRate};
 
     fn board8() -> Board {
         let grid = (0..8)
             .map(|_| (0..8).map(|_| Square::new()).collect())
             .collect();
-        Board {
+        Board::new(
             grid,
-            flags: BoardFlags {
+            BoardFlags {
                 side_to_move: Color::White,
                 white_can_castle_kingside: false,
                 white_can_castle_queenside: false,
                 black_can_castle_kingside: false,
                 black_can_castle_queenside: false,
+                castle_rook_files: Default::default(),
+                promotion_targets: Vec::new(),
                 en_passant_target: None,
                 train_tick_rate: TrainTickRate::EveryFullTurn,
                 ply_count: 0,
                 last_move: None,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                extra_moves: 0,
+                variants: Vec::new(),
+                duck_phase: DuckPhase::PieceMove,
+                win_conditions: Vec::new(),
+                white_checks: 0,
+                black_checks: 0,
+                white_pocket: Vec::new(),
+                black_pocket: Vec::new(),
             },
-        }
+        )
     }
 
     fn cc(file: u8, rank: u8) -> Coord {
@@ -248,8 +262,8 @@ mod tests {
         .expect("PlaceTornado onto an in-range empty square is legal");
 
         assert_eq!(
-            b.grid[3][4].conditions,
-            vec![SquareCondition::Tornado {
+            b.grid[3][4].conditions.as_slice(),
+            [SquareCondition::Tornado {
                 remaining: TORNADO_DURATION - 1
             }],
             "placed at full duration, decremented once by the same-turn tick"
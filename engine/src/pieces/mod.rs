@@ -1,9 +1,10 @@
 use std::{
     any::Any,
+    borrow::Cow,
     fmt::{Debug, Formatter, Result},
 };
 
-use crate::board::{Board, Coord, GameMove, MoveType};
+use crate::board::{Board, Coord, GameMove, MoveType, make_move::PreMove};
 use crate::pieces::piecetype::PieceType;
 
 pub mod chess2;
@@ -18,7 +19,10 @@ pub trait Piece {
     fn color(&self) -> Color;
     fn set_color(&mut self, color: Color);
     fn initial_moves(&self, board: &Board, from: &Coord) -> Vec<GameMove>;
-    fn symbol(&self) -> String;
+    /// FEN token for this piece. Plain pieces hand back a static
+    /// string; only pieces with state to encode (Goblin, Locomotive,
+    /// Carriage, a loaded Bus, a phased Skibidi) build one.
+    fn symbol(&self) -> Cow<'static, str>;
 
     fn can_carry_piece(&self) -> bool {
         false
@@ -27,7 +31,7 @@ pub trait Piece {
     /// Read-only view of this carrier's passenger list, or `None` if
     /// the piece isn't a carrier. Default-`None` so non-carriers don't
     /// need to override. Carriers (Bus, Locomotive, Carriage) override
-    /// to expose their internal `Vec<PieceType>`. Used by the move
+    /// to expose their `Passengers`. Used by the move
     /// filter, `find_king` descent, and the PieceInCarrier dispatch.
     fn passengers(&self) -> Option<&[PieceType]> {
         None
@@ -36,6 +40,10 @@ pub trait Piece {
     /// Mutable handle on this carrier's passenger list, or `None` for
     /// non-carriers. Used by `make_move` when a passenger enters /
     /// exits / is captured by an enemy boarding.
+    ///
+    /// The list is shared copy-on-write (see `fairy::passengers`), so
+    /// this copies it first when another clone of the carrier still
+    /// holds it.
     fn passengers_mut(&mut self) -> Option<&mut Vec<PieceType>> {
         None
     }
@@ -78,11 +86,12 @@ pub trait Piece {
     /// converted (ex-victim) piece, since "the goblin dies" per spec.
     ///
     /// `board_after` is the post-relocation board; the moved piece
-    /// sits at the move's destination, and `board_before` is its
-    /// pre-move snapshot.
+    /// sits at the move's destination, and `before` is what the apply
+    /// read off the board before touching it: the squares the move
+    /// names and the castling rook squares (see `PreMove`).
     fn post_move_effects(
         &self,
-        _board_before: &Board,
+        _before: &PreMove,
         _board_after: &mut Board,
         _game_move: &GameMove,
     ) {
@@ -163,7 +172,7 @@ impl Clone for Box<dyn Piece> {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub enum Color {
     White,
     Black,
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 use crate::{
     board::GameMove,
@@ -38,7 +38,7 @@ macro_rules! dispatch {
     };
 }
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub enum PieceType {
     Pawn(Pawn),
     Rook(Rook),
@@ -92,7 +92,7 @@ impl PieceType {
         dispatch!(self, p => p.as_any_mut())
     }
 
-    pub fn symbol(&self) -> String {
+    pub fn symbol(&self) -> Cow<'static, str> {
         dispatch!(self, p => p.symbol())
     }
 
@@ -274,6 +274,11 @@ impl PieceType {
                 // logic below doesn't apply. The placer stays put and
                 // the target square gets a condition, not a piece.
                 MoveType::PlaceTornado { .. } => return true,
+                // No piece generates a duck move; the duck's come from
+                // `DuckPhaseModifier`, which never calls this filter.
+                MoveType::PlaceDuck { .. } | MoveType::MoveDuck { .. } => return false,
+                // Nor a drop; those come from `DropModifier`.
+                MoveType::Drop { .. } => return false,
                 MoveType::MoveIntoCarrier(_) => {
                     // No piece's `initial_moves` produces a top-level
                     // MoveIntoCarrier today — the filter is the sole
@@ -357,11 +362,11 @@ impl PieceType {
 
     pub fn post_move_effects(
         &self,
-        board_before: &crate::board::Board,
+        before: &crate::board::make_move::PreMove,
         board_after: &mut crate::board::Board,
         game_move: &GameMove,
     ) {
-        dispatch!(self, p => p.post_move_effects(board_before, board_after, game_move))
+        dispatch!(self, p => p.post_move_effects(before, board_after, game_move))
     }
 
     /// See `Piece::attacks` for semantics.
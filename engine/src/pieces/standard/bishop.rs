@@ -1,10 +1,12 @@
+use std::borrow::Cow;
+
 use crate::{
     board::{Board, Coord, GameMove},
     movement::glider::{DIAGONAL_DIRS, generate_glider_moves},
     pieces::{Color, Piece},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Bishop {
     pub color: Color,
 }
@@ -26,11 +28,11 @@ impl Piece for Bishop {
         }
         generate_glider_moves(board, from, &DIAGONAL_DIRS, usize::MAX)
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'B'.to_string(),
-            Color::Black => 'b'.to_string(),
-            Color::Neutral => 'B'.to_string(),
+            Color::White => Cow::Borrowed("B"),
+            Color::Black => Cow::Borrowed("b"),
+            Color::Neutral => Cow::Borrowed("B"),
         }
     }
 
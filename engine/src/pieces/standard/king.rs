@@ -1,10 +1,15 @@
+use std::borrow::Cow;
+
 use crate::{
-    board::{Board, CastleSide, Coord, GameMove, MoveType},
+    board::{
+        Board, CastleSide, Coord, GameMove, MoveType, chess960::castle_target_files,
+        make_move::PreMove,
+    },
     movement::glider::{OMNI_DIRS, generate_glider_moves},
     pieces::{Color, Piece, piecetype::PieceType},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct King {
     pub color: Color,
 }
@@ -19,40 +24,30 @@ impl King {
         }
     }
 
-    /// Generate castle candidates that satisfy every standard-chess
-    /// precondition: the relevant castle right, king on its starting square,
-    /// rook of the right colour on its starting square, empty path, and the
-    /// king's start + intermediate + destination squares not under attack.
+    /// Generate castle candidates under Chess960 rules, which take in
+    /// standard chess (see `board::chess960`): the relevant castle right,
+    /// the king on its back rank with the right's rook on its side of
+    /// it, every square between either piece and its destination empty
+    /// bar the two pieces themselves, and the king's start, path and
+    /// destination not under attack.
+    ///
+    /// A side that doesn't fit on the board — queenside needs the d-file
+    /// — isn't generated; otherwise any width works.
     ///
-    /// Castling is only generated when the board is at least 8 wide: king
-    /// targets sit at files 2 (queenside) and 6 (kingside), and the
-    /// kingside rook is at `width - 1`. Narrower boards can't host the
-    /// move geometrically.
+    /// Duck Chess has no check, so there the attack conditions drop out:
+    /// a king may castle out of, through, or into attack.
     fn castle_moves(&self, board: &Board, from: &Coord) -> Vec<GameMove> {
         let mut moves = Vec::new();
-        if board.width() < 8 {
-            return moves;
-        }
         let back_rank = self.back_rank(board);
-        if from.file != 4 || from.rank != back_rank {
+        if from.rank != back_rank || self.color == Color::Neutral {
             return moves;
         }
-        if board.is_in_check(self.color) {
+        let ignores_check = !board.ruleset().enforces_check();
+        if !ignores_check && board.is_in_check(self.color) {
             return moves;
         }
         let opp = self.color.opposite();
-
-        let (can_ks, can_qs) = match self.color {
-            Color::White => (
-                board.flags.white_can_castle_kingside,
-                board.flags.white_can_castle_queenside,
-            ),
-            Color::Black => (
-                board.flags.black_can_castle_kingside,
-                board.flags.black_can_castle_queenside,
-            ),
-            Color::Neutral => return moves,
-        };
+        let safe = |board: &Board, sq: &Coord| ignores_check || !board.is_attacked_by(sq, opp);
 
         // The rook must sit on a walkable square — otherwise a stranded
         // rook (corner Gate closed by a signal, or FEN setup) gets
@@ -86,69 +81,44 @@ impl King {
         let empty = |board: &Board, sq: &Coord| -> bool {
             matches!(
                 board.get_square_at(sq),
-                Some(s) if s.piece.is_none() && s.square_type.is_walkable()
+                Some(s) if s.piece.is_none() && s.is_walkable()
             )
         };
+        let on_rank = |file: u8| Coord {
+            file,
+            rank: back_rank,
+        };
+        let span = |a: u8, b: u8| a.min(b)..=a.max(b);
 
-        if can_ks {
-            let p5 = Coord {
-                file: 5,
-                rank: back_rank,
-            };
-            let p6 = Coord {
-                file: 6,
-                rank: back_rank,
+        for side in [CastleSide::Kingside, CastleSide::Queenside] {
+            let Some(rook) = board.castle_rook_square(self.color, side) else {
+                continue;
             };
-            // Kingside rook always sits on the right-edge file, which is
-            // `width - 1` (== 7 for a standard 8-wide board).
-            let p_rook = Coord {
-                file: board.width().saturating_sub(1),
-                rank: back_rank,
+            let (king_file, rook_file) = castle_target_files(side, board.width());
+            let on_its_side = match side {
+                CastleSide::Kingside => rook.file > from.file,
+                CastleSide::Queenside => rook.file < from.file,
             };
-            if empty(board, &p5)
-                && empty(board, &p6)
-                && rook_is_friendly(board, &p_rook)
-                && !board.is_attacked_by(&p5, opp)
-                && !board.is_attacked_by(&p6, opp)
+            if !on_its_side
+                || king_file >= board.width()
+                || rook_file >= board.width()
+                || !rook_is_friendly(board, &rook)
             {
-                moves.push(GameMove {
-                    from: from.clone(),
-                    move_type: MoveType::Castle {
-                        side: CastleSide::Kingside,
-                    },
-                });
+                continue;
             }
-        }
-
-        if can_qs {
-            let p1 = Coord {
-                file: 1,
-                rank: back_rank,
-            };
-            let p2 = Coord {
-                file: 2,
-                rank: back_rank,
-            };
-            let p3 = Coord {
-                file: 3,
-                rank: back_rank,
-            };
-            let p0 = Coord {
-                file: 0,
-                rank: back_rank,
-            };
-            if empty(board, &p1)
-                && empty(board, &p2)
-                && empty(board, &p3)
-                && rook_is_friendly(board, &p0)
-                && !board.is_attacked_by(&p3, opp)
-                && !board.is_attacked_by(&p2, opp)
-            {
+            // The king and rook may stand on each other's paths; only
+            // the other squares have to be clear.
+            let clear = span(from.file, king_file)
+                .chain(span(rook.file, rook_file))
+                .filter(|&f| f != from.file && f != rook.file)
+                .all(|f| empty(board, &on_rank(f)));
+            let king_path_safe = span(from.file, king_file)
+                .filter(|&f| f != from.file)
+                .all(|f| safe(board, &on_rank(f)));
+            if clear && king_path_safe {
                 moves.push(GameMove {
                     from: from.clone(),
-                    move_type: MoveType::Castle {
-                        side: CastleSide::Queenside,
-                    },
+                    move_type: MoveType::Castle { side },
                 });
             }
         }
@@ -206,11 +176,11 @@ impl Piece for King {
         }
         out
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'K'.to_string(),
-            Color::Black => 'k'.to_string(),
-            Color::Neutral => 'K'.to_string(),
+            Color::White => Cow::Borrowed("K"),
+            Color::Black => Cow::Borrowed("k"),
+            Color::Neutral => Cow::Borrowed("K"),
         }
     }
 
@@ -220,23 +190,18 @@ impl Piece for King {
 
     fn post_move_effects(
         &self,
-        _board_before: &Board,
+        _before: &PreMove,
         board_after: &mut Board,
         _game_move: &GameMove,
     ) {
         // Once the king has moved, castling on either side is no longer
         // available for that colour — covers ordinary moves and castles.
-        match self.color {
-            Color::White => {
-                board_after.flags.white_can_castle_kingside = false;
-                board_after.flags.white_can_castle_queenside = false;
-            }
-            Color::Black => {
-                board_after.flags.black_can_castle_kingside = false;
-                board_after.flags.black_can_castle_queenside = false;
-            }
-            Color::Neutral => {}
-        }
+        board_after
+            .flags
+            .clear_castle(self.color, CastleSide::Kingside);
+        board_after
+            .flags
+            .clear_castle(self.color, CastleSide::Queenside);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -1,9 +1,11 @@
+use std::borrow::Cow;
+
 use crate::{
     board::{Board, Coord, GameMove, MoveType},
     pieces::{Color, Piece},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Knight {
     pub color: Color,
 }
@@ -52,7 +54,7 @@ impl Piece for Knight {
             // The general filter handles same-color targets later.
             if !board
                 .get_square_at(&coord)
-                .map(|s| s.square_type.is_walkable())
+                .map(|s| s.is_walkable())
                 .unwrap_or(false)
             {
                 continue;
@@ -64,11 +66,11 @@ impl Piece for Knight {
         }
         moves
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'N'.to_string(),
-            Color::Black => 'n'.to_string(),
-            Color::Neutral => 'N'.to_string(),
+            Color::White => Cow::Borrowed("N"),
+            Color::Black => Cow::Borrowed("n"),
+            Color::Neutral => Cow::Borrowed("N"),
         }
     }
 
@@ -1,11 +1,13 @@
+use std::borrow::Cow;
+
 use tracing::trace;
 
 use crate::{
-    board::{Board, Coord, GameMove, MoveType, PromotionTarget},
+    board::{Board, Coord, GameMove, MoveType, make_move::PreMove},
     pieces::{Color, Piece},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Pawn {
     pub color: Color,
 }
@@ -34,9 +36,9 @@ impl Pawn {
         }
     }
 
-    /// Push either a `MoveTo` or the four `Promotion` variants — same target,
-    /// four piece choices — depending on whether the destination is the
-    /// promotion rank.
+    /// Push either a `MoveTo` or one `Promotion` per piece the game
+    /// lets a pawn become (`BoardFlags::promotion_choices`), depending on
+    /// whether the destination is the promotion rank.
     fn push_advance_or_promotion(
         &self,
         target: Coord,
@@ -45,17 +47,12 @@ impl Pawn {
         out: &mut Vec<GameMove>,
     ) {
         if target.rank == self.promotion_rank(board) {
-            for into in [
-                PromotionTarget::Queen,
-                PromotionTarget::Rook,
-                PromotionTarget::Bishop,
-                PromotionTarget::Knight,
-            ] {
+            for into in board.flags.promotion_choices() {
                 out.push(GameMove {
                     from: from.clone(),
                     move_type: MoveType::Promotion {
                         target: target.clone(),
-                        into,
+                        into: into.clone(),
                     },
                 });
             }
@@ -100,7 +97,7 @@ impl Piece for Pawn {
                 trace!(?square, ?forward_coord, "forward square");
                 // Plan 08: a closed Gate (or Turret/Vent) is non-walkable;
                 // the pawn can't push onto it even though `piece.is_none()`.
-                if square.piece.is_none() && square.square_type.is_walkable() {
+                if square.piece.is_none() && square.is_walkable() {
                     trace!("forward square empty + walkable, pushing move");
                     self.push_advance_or_promotion(forward_coord.clone(), from, board, &mut moves);
 
@@ -117,7 +114,7 @@ impl Piece for Pawn {
                         };
                         if let Some(two_square) = board.get_square_at(&two_forward_coord) {
                             if two_square.piece.is_none()
-                                && two_square.square_type.is_walkable()
+                                && two_square.is_walkable()
                             {
                                 let game_move = GameMove {
                                     from: from.clone(),
@@ -148,7 +145,7 @@ impl Piece for Pawn {
                 // square. (A piece couldn't legally be sitting on a closed
                 // Gate in the first place, but be defensive against
                 // hand-crafted FENs.)
-                if !square.square_type.is_walkable() {
+                if !square.is_walkable() {
                     continue;
                 }
                 // Ordinary diagonal capture: enemy piece sitting on the square.
@@ -187,11 +184,11 @@ impl Piece for Pawn {
 
         moves
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'P'.to_string(),
-            Color::Black => 'p'.to_string(),
-            Color::Neutral => 'P'.to_string(),
+            Color::White => Cow::Borrowed("P"),
+            Color::Black => Cow::Borrowed("p"),
+            Color::Neutral => Cow::Borrowed("P"),
         }
     }
 
@@ -224,7 +221,7 @@ impl Piece for Pawn {
 
     fn post_move_effects(
         &self,
-        _board_before: &Board,
+        _before: &PreMove,
         board_after: &mut Board,
         game_move: &GameMove,
     ) {
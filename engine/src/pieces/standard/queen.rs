@@ -1,10 +1,12 @@
+use std::borrow::Cow;
+
 use crate::{
     board::{Board, Coord, GameMove},
     movement::glider::{OMNI_DIRS, generate_glider_moves},
     pieces::{Color, Piece},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Queen {
     pub color: Color,
 }
@@ -25,11 +27,11 @@ impl Piece for Queen {
         }
         generate_glider_moves(board, from, &OMNI_DIRS, usize::MAX)
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'Q'.to_string(),
-            Color::Black => 'q'.to_string(),
-            Color::Neutral => 'Q'.to_string(),
+            Color::White => Cow::Borrowed("Q"),
+            Color::Black => Cow::Borrowed("q"),
+            Color::Neutral => Cow::Borrowed("Q"),
         }
     }
 
@@ -1,10 +1,12 @@
+use std::borrow::Cow;
+
 use crate::{
-    board::{Board, Coord, GameMove},
+    board::{Board, CastleSide, Coord, GameMove, make_move::PreMove},
     movement::glider::{STRAIGHT_DIRS, generate_glider_moves},
     pieces::{Color, Piece},
 };
 
-#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Rook {
     pub color: Color,
 }
@@ -25,11 +27,11 @@ impl Piece for Rook {
         }
         generate_glider_moves(board, from, &STRAIGHT_DIRS, usize::MAX)
     }
-    fn symbol(&self) -> String {
+    fn symbol(&self) -> Cow<'static, str> {
         match self.color {
-            Color::White => 'R'.to_string(),
-            Color::Black => 'r'.to_string(),
-            Color::Neutral => 'R'.to_string(),
+            Color::White => Cow::Borrowed("R"),
+            Color::Black => Cow::Borrowed("r"),
+            Color::Neutral => Cow::Borrowed("R"),
         }
     }
 
@@ -39,41 +41,19 @@ impl Piece for Rook {
 
     fn post_move_effects(
         &self,
-        board_before: &Board,
+        before: &PreMove,
         board_after: &mut Board,
         game_move: &GameMove,
     ) {
-        // A rook leaving its starting corner permanently disables castling on
-        // that side. Rooks that promoted onto the board started life on a
-        // non-corner square, so the starting-square test correctly leaves the
-        // flags alone for them.
-        //
-        // Right-edge file is `width - 1`, not necessarily 7 — needed for
-        // non-8-wide boards. We read the width from `board_before` because
-        // dimensions are immutable across a move.
-        let from = &game_move.from;
-        let right_edge = board_before.width().saturating_sub(1);
-        let white_back = board_before.height().saturating_sub(1);
-        match self.color {
-            Color::White => {
-                if from.rank == white_back {
-                    if from.file == 0 {
-                        board_after.flags.white_can_castle_queenside = false;
-                    } else if from.file == right_edge {
-                        board_after.flags.white_can_castle_kingside = false;
-                    }
-                }
-            }
-            Color::Black => {
-                if from.rank == 0 {
-                    if from.file == 0 {
-                        board_after.flags.black_can_castle_queenside = false;
-                    } else if from.file == right_edge {
-                        board_after.flags.black_can_castle_kingside = false;
-                    }
-                }
+        // A rook leaving the square its castling right names (see
+        // `board::chess960`) — the corner, in standard chess —
+        // permanently disables castling on that side. Rooks that promoted
+        // onto the board never stand on one, so the flags stay alone for
+        // them.
+        for side in [CastleSide::Kingside, CastleSide::Queenside] {
+            if before.castle_rook_square(self.color, side).as_ref() == Some(&game_move.from) {
+                board_after.flags.clear_castle(self.color, side);
             }
-            Color::Neutral => {}
         }
     }
 
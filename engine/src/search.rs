@@ -0,0 +1,733 @@
+//! Computer play: pick a move for the side to move.
+//!
+//! Iterative-deepening negamax with alpha-beta pruning, a quiescence
+//! search over captures at the horizon, and a time / node / depth
+//! budget (`Limits`). The tree is walked with `all_legal_moves`,
+//! `make_move_unchecked` and `unmake_move` on a private copy of the
+//! board, so the search plays by exactly the rules the game does —
+//! trains advance, tornadoes compel, brainrot freezes, carriers board
+//! and unload — without knowing any of them. Game ends come from the
+//! ruleset's win conditions and `Board::status`, so a King of the Hill
+//! walk, a third check or a Brainrot win scores like a checkmate.
+//!
+//! A turn doesn't always pass: a bonus move (`BoardFlags::extra_moves`)
+//! or the Duck Chess duck half-move leaves the same side to move. The
+//! search negates a child's score only when the side to move changed.
+//!
+//! Moves are tried best-first: the transposition table's move for the
+//! position, then captures by most valuable victim / least valuable
+//! attacker, then promotions, then the two most recent quiet moves that
+//! caused a cutoff at the same ply (killers). The table is keyed by
+//! `Board::position_hash`, which knows nothing of how the game got
+//! there: a score that leaned on a repetition or the fifty-move rule
+//! below it is kept out (only its move is stored), and an entry isn't
+//! trusted for a cutoff when the fifty-move draw could now fall within
+//! its depth.
+//!
+//! The evaluation is material plus a little centralisation and pawn
+//! advancement, from the side to move's point of view. Antichess and
+//! lose-all-pieces games count material the other way round; King of
+//! the Hill rewards a king near the hill, and N-check the checks given.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::board::win::{WinConditionId, hill_squares};
+use crate::board::{Board, Coord, GameMove, GameStatus, MoveType, VariantId};
+use crate::pieces::Color;
+use crate::pieces::piecetype::PieceType;
+
+/// Score of a win on the board, less the plies it takes to get there:
+/// `MATE - 3` is a win three plies away. Anything within `MAX_PLY` of
+/// `MATE` is a forced result.
+pub const MATE: i32 = 1_000_000;
+
+/// Deepest the main search iterates to when `Limits::depth` is unset.
+const MAX_DEPTH: u8 = 64;
+/// Deepest ply either search reaches; also bounds mate distances.
+const MAX_PLY: usize = 128;
+/// Plies of quiescence past the horizon before the static evaluation
+/// is taken regardless.
+const QUIESCENCE_PLIES: usize = 8;
+/// Transposition table entries kept before it is cleared.
+const TABLE_LIMIT: usize = 1 << 18;
+/// Nodes between clock reads.
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// When to stop searching. Unset fields don't limit; a search with no
+/// limit at all runs to `MAX_DEPTH`, so set at least one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Full-width plies, not counting quiescence.
+    pub depth: Option<u8>,
+    /// Positions visited, quiescence included.
+    pub nodes: Option<u64>,
+    /// Wall-clock time.
+    pub time: Option<Duration>,
+}
+
+impl Limits {
+    pub fn depth(depth: u8) -> Self {
+        Limits {
+            depth: Some(depth),
+            ..Limits::default()
+        }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        Limits {
+            nodes: Some(nodes),
+            ..Limits::default()
+        }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        Limits {
+            time: Some(time),
+            ..Limits::default()
+        }
+    }
+}
+
+/// What `best_move` found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// The move to play; `None` when the game is already over.
+    pub best: Option<GameMove>,
+    /// Centipawns for the side to move; see `MATE` for forced results.
+    pub score: i32,
+    /// The expected line, starting with `best`. May stop short of the
+    /// searched depth where the line ran into a table hit.
+    pub pv: Vec<GameMove>,
+    /// Positions visited.
+    pub nodes: u64,
+}
+
+/// Search `board` within `limits` and return the best move for the
+/// side to move. Only completed iterations count: when the budget
+/// runs out mid-iteration, the previous depth's answer stands, or the
+/// first move in search order if not even depth 1 finished.
+pub fn best_move(board: &Board, limits: Limits) -> SearchResult {
+    let mut board = board.clone();
+    let mut search = Search::new(&board, limits);
+    let mut result = SearchResult {
+        best: None,
+        score: search.evaluate(&board),
+        pv: Vec::new(),
+        nodes: 0,
+    };
+    for depth in 1..=limits.depth.unwrap_or(MAX_DEPTH) {
+        let mut pv = Vec::new();
+        let score = search.negamax(&mut board, depth, 0, -MATE, MATE, &mut pv);
+        if search.stopped {
+            break;
+        }
+        result.score = score;
+        result.best = pv.first().cloned();
+        result.pv = pv;
+        if result.best.is_none() || score.abs() > MATE - MAX_PLY as i32 {
+            break;
+        }
+    }
+    if result.best.is_none() {
+        let mut moves = board.all_legal_moves(board.flags.side_to_move);
+        search.order(&board, &mut moves, 0, None);
+        result.best = moves.into_iter().next();
+        result.pv = result.best.iter().cloned().collect();
+    }
+    result.nodes = search.nodes;
+    result
+}
+
+/// How far to search below a move.
+#[derive(Clone, Copy)]
+enum Next {
+    /// This many more full-width plies.
+    Full(u8),
+    /// Quiescence, this many plies past the horizon.
+    Quiesce(usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    /// The score is at least this (a beta cutoff).
+    Lower,
+    /// The score is at most this (nothing beat alpha).
+    Upper,
+}
+
+struct Entry {
+    /// 0 for an entry that only remembers its move.
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best: Option<GameMove>,
+}
+
+/// Evaluation terms that depend on the game rather than the position.
+struct Style {
+    /// Antichess or lose-all-pieces: material is a burden.
+    inverted: bool,
+    /// King of the Hill: the hill squares.
+    hill: Vec<Coord>,
+    /// An N-check game.
+    checks: bool,
+}
+
+struct Search {
+    limits: Limits,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+    /// Rule draws scored so far, so a node can tell whether one was
+    /// scored below it.
+    rule_draws: u64,
+    table: HashMap<u64, Entry>,
+    killers: Vec<[Option<GameMove>; 2]>,
+    style: Style,
+}
+
+impl Search {
+    fn new(board: &Board, limits: Limits) -> Self {
+        let rules = board.ruleset();
+        let wins = rules.win_conditions();
+        Search {
+            limits,
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+            rule_draws: 0,
+            table: HashMap::new(),
+            killers: vec![[None, None]; MAX_PLY + 1],
+            style: Style {
+                inverted: rules.has(VariantId::Antichess)
+                    || wins.contains(&WinConditionId::LoseAllPieces),
+                hill: if wins.contains(&WinConditionId::KingOfTheHill) {
+                    hill_squares(board)
+                } else {
+                    Vec::new()
+                },
+                checks: wins.iter().any(|w| matches!(w, WinConditionId::NChecks(_))),
+            },
+        }
+    }
+
+    /// Count a node; flag the search stopped once the budget is spent.
+    fn visit(&mut self) -> bool {
+        self.nodes += 1;
+        if self.limits.nodes.is_some_and(|n| self.nodes > n)
+            || (self.nodes.is_multiple_of(CLOCK_INTERVAL)
+                && self.limits.time.is_some_and(|t| self.start.elapsed() >= t))
+        {
+            self.stopped = true;
+        }
+        !self.stopped
+    }
+
+    /// Full-width search to `depth` plies. Scores are for the side to
+    /// move; `pv` receives the best line found below this node.
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        depth: u8,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<GameMove>,
+    ) -> i32 {
+        pv.clear();
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiesce(board, ply, 0, (alpha, beta));
+        }
+        if !self.visit() {
+            return 0;
+        }
+        if ply > 0 && is_rule_draw(board) {
+            self.rule_draws += 1;
+            return 0;
+        }
+        let mut moves = board.all_legal_moves(board.flags.side_to_move);
+        if let Some(score) = terminal_score(board, moves.is_empty(), ply) {
+            return score;
+        }
+
+        let key = board.position_hash();
+        let mut hash_move = None;
+        if let Some(entry) = self.table.get(&key) {
+            let score = from_table(entry.score, ply);
+            // The entry's search may not have run into the fifty-move
+            // draw that a higher clock now puts within its reach.
+            let fifty_in_reach = board.flags.halfmove_clock + u32::from(entry.depth)
+                >= crate::board::FIFTY_MOVE_PLIES;
+            if ply > 0
+                && entry.depth >= depth
+                && !fifty_in_reach
+                && match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                }
+            {
+                return score;
+            }
+            hash_move = entry.best.clone();
+        }
+
+        self.order(board, &mut moves, ply, hash_move.as_ref());
+        let original_alpha = alpha;
+        let rule_draws = self.rule_draws;
+        let mut best_score = -MATE;
+        let mut best = None;
+        let mut line = Vec::new();
+        for m in moves {
+            let Some(score) = self.play(
+                board,
+                &m,
+                Next::Full(depth - 1),
+                ply,
+                (alpha, beta),
+                &mut line,
+            ) else {
+                continue;
+            };
+            if self.stopped {
+                return 0;
+            }
+            if score > best_score {
+                best_score = score;
+                best = Some(m.clone());
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(m.clone());
+                    pv.append(&mut line);
+                }
+            }
+            if alpha >= beta {
+                if victim(board, &m).is_none() {
+                    let killers = &mut self.killers[ply];
+                    if killers[0].as_ref() != Some(&m) {
+                        killers[1] = killers[0].replace(m);
+                    }
+                }
+                break;
+            }
+        }
+
+        if self.table.len() >= TABLE_LIMIT {
+            self.table.clear();
+        }
+        let bound = if best_score >= beta {
+            Bound::Lower
+        } else if best_score > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        // A repetition or fifty-move draw below makes the score hold
+        // for this path only; keep just the move.
+        let depth = if self.rule_draws == rule_draws { depth } else { 0 };
+        self.table.insert(
+            key,
+            Entry {
+                depth,
+                score: to_table(best_score, ply),
+                bound,
+                best,
+            },
+        );
+        best_score
+    }
+
+    /// Captures (every move, when in check) until the position is
+    /// quiet, standing pat on the static evaluation otherwise.
+    fn quiesce(&mut self, board: &mut Board, ply: usize, qply: usize, window: (i32, i32)) -> i32 {
+        let (mut alpha, beta) = window;
+        if !self.visit() {
+            return 0;
+        }
+        let mover = board.flags.side_to_move;
+        let mut moves = board.all_legal_moves(mover);
+        if let Some(score) = terminal_score(board, moves.is_empty(), ply) {
+            return score;
+        }
+        let stand = self.evaluate(board);
+        if qply >= QUIESCENCE_PLIES || ply >= MAX_PLY {
+            return stand;
+        }
+        let in_check = board.ruleset().enforces_check() && board.is_in_check(mover);
+        let mut best_score = if in_check { -MATE } else { stand };
+        if !in_check {
+            if stand >= beta {
+                return stand;
+            }
+            alpha = alpha.max(stand);
+            moves.retain(|m| victim(board, m).is_some() || is_promotion(m));
+        }
+        self.order(board, &mut moves, ply, None);
+        let mut line = Vec::new();
+        for m in moves {
+            let Some(score) = self.play(
+                board,
+                &m,
+                Next::Quiesce(qply + 1),
+                ply,
+                (alpha, beta),
+                &mut line,
+            ) else {
+                continue;
+            };
+            if self.stopped {
+                return 0;
+            }
+            best_score = best_score.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best_score
+    }
+
+    /// Make `m`, score the position after it for the side that made
+    /// it within `(alpha, beta)`, and take it back. The window and the
+    /// score flip only when the turn passed. `None` when `m` doesn't
+    /// apply after all, which leaves the board as it was; the caller
+    /// skips the move.
+    fn play(
+        &mut self,
+        board: &mut Board,
+        m: &GameMove,
+        next: Next,
+        ply: usize,
+        (alpha, beta): (i32, i32),
+        line: &mut Vec<GameMove>,
+    ) -> Option<i32> {
+        let mover = board.flags.side_to_move;
+        let undo = board.make_move_unchecked(m.clone()).ok()?;
+        let same_side = board.flags.side_to_move == mover;
+        let (lo, hi) = if same_side {
+            (alpha, beta)
+        } else {
+            (-beta, -alpha)
+        };
+        let score = match next {
+            Next::Full(depth) => self.negamax(board, depth, ply + 1, lo, hi, line),
+            Next::Quiesce(qply) => {
+                line.clear();
+                self.quiesce(board, ply + 1, qply, (lo, hi))
+            }
+        };
+        board.unmake_move(undo);
+        Some(if same_side { score } else { -score })
+    }
+
+    /// Sort `moves` best-first: `hash_move`, captures by most valuable
+    /// victim then least valuable attacker, promotions, killers, the
+    /// rest in generation order.
+    fn order(
+        &self,
+        board: &Board,
+        moves: &mut [GameMove],
+        ply: usize,
+        hash_move: Option<&GameMove>,
+    ) {
+        let killers = &self.killers[ply.min(MAX_PLY)];
+        moves.sort_by_cached_key(|m| {
+            Reverse(if Some(m) == hash_move {
+                4_000_000
+            } else if let Some(v) = victim(board, m) {
+                let attacker = board
+                    .get_square_at(&m.from)
+                    .and_then(|s| s.piece.as_ref())
+                    .map_or(0, piece_value);
+                3_000_000 + 10 * piece_value(v) - attacker
+            } else if is_promotion(m) {
+                2_000_000
+            } else if killers.iter().any(|k| k.as_ref() == Some(m)) {
+                1_000_000
+            } else {
+                0
+            })
+        });
+    }
+
+    /// Static evaluation for the side to move.
+    fn evaluate(&self, board: &Board) -> i32 {
+        let (width, height) = (board.width() as i32, board.height() as i32);
+        let mut white = 0;
+        for (at, piece) in board.iter_pieces() {
+            white += self.piece_score(piece, &at, width, height);
+        }
+        for piece in &board.flags.white_pocket {
+            white += piece_value(piece);
+        }
+        for piece in &board.flags.black_pocket {
+            white -= piece_value(piece);
+        }
+        if self.style.inverted {
+            white = -white;
+        }
+        if self.style.checks {
+            white += CHECK_BONUS
+                * (i32::from(board.flags.checks_given(Color::White))
+                    - i32::from(board.flags.checks_given(Color::Black)));
+        }
+        match board.flags.side_to_move {
+            Color::Black => -white,
+            _ => white,
+        }
+    }
+
+    /// `piece` at `at`, passengers included, from White's side.
+    fn piece_score(&self, piece: &PieceType, at: &Coord, width: i32, height: i32) -> i32 {
+        let (file, rank) = (i32::from(at.file), i32::from(at.rank));
+        let mut score = piece_value(piece);
+        match piece {
+            PieceType::King(_) => {
+                if let Some(steps) = self
+                    .style
+                    .hill
+                    .iter()
+                    .map(|h| {
+                        (i32::from(h.file) - file)
+                            .abs()
+                            .max((i32::from(h.rank) - rank).abs())
+                    })
+                    .min()
+                {
+                    score += HILL_BONUS * (width.max(height) - steps);
+                }
+            }
+            PieceType::Locomotive(_) | PieceType::Carriage(_) | PieceType::Bus(_) => {}
+            _ => {
+                // Closeness to the centre line along each axis, 1 at
+                // the edge up to the board's size; the product favours
+                // central files as well as advanced ranks.
+                let along_file = width - (2 * file - (width - 1)).abs();
+                let along_rank = height - (2 * rank - (height - 1)).abs();
+                score += CENTRE_BONUS * along_file * along_rank / 4;
+            }
+        }
+        if let PieceType::Pawn(_) = piece {
+            let advanced = match piece.get_color() {
+                Color::White => height - 1 - rank,
+                _ => rank,
+            };
+            score += PAWN_STEP_BONUS * advanced;
+        }
+        let mut white = match piece.get_color() {
+            Color::White => score,
+            Color::Black => -score,
+            Color::Neutral => 0,
+        };
+        for passenger in piece.passengers().unwrap_or_default() {
+            white += self.piece_score(passenger, at, width, height);
+        }
+        white
+    }
+}
+
+const CENTRE_BONUS: i32 = 1;
+const PAWN_STEP_BONUS: i32 = 6;
+const HILL_BONUS: i32 = 30;
+const CHECK_BONUS: i32 = 150;
+
+/// Material value in centipawns. Carriers are worth little of their
+/// own; their passengers count separately.
+fn piece_value(piece: &PieceType) -> i32 {
+    match piece {
+        PieceType::Pawn(_) => 100,
+        PieceType::Knight(_) => 320,
+        PieceType::Bishop(_) => 330,
+        PieceType::Rook(_) => 500,
+        PieceType::Queen(_) => 900,
+        PieceType::King(_) => 0,
+        PieceType::Monkey(_) => 300,
+        PieceType::Goblin(_) => 300,
+        PieceType::Skibidi(_) => 350,
+        PieceType::Stormcaller(_) => 250,
+        PieceType::Locomotive(_) => 400,
+        PieceType::Bus(_) | PieceType::Carriage(_) => 100,
+    }
+}
+
+/// The enemy piece `m` takes, if it's a capture.
+fn victim<'b>(board: &'b Board, m: &GameMove) -> Option<&'b PieceType> {
+    fn target(move_type: &MoveType) -> Option<&Coord> {
+        match move_type {
+            MoveType::MoveTo(t) | MoveType::Promotion { target: t, .. } => Some(t),
+            MoveType::EnPassant { captured, .. } => Some(captured),
+            MoveType::PieceInCarrier { move_type, .. } => target(move_type),
+            _ => None,
+        }
+    }
+    let mover = board.flags.side_to_move;
+    board
+        .get_square_at(target(&m.move_type)?)?
+        .piece
+        .as_ref()
+        .filter(|p| p.get_color() != mover)
+}
+
+fn is_promotion(m: &GameMove) -> bool {
+    matches!(m.move_type, MoveType::Promotion { .. })
+}
+
+/// Drawn by the fifty-move rule, or a repeat of a position earlier in
+/// the game or the line: playing on can't do better than the first
+/// time round.
+fn is_rule_draw(board: &Board) -> bool {
+    board.flags.halfmove_clock >= crate::board::FIFTY_MOVE_PLIES || board.repetition_count() >= 2
+}
+
+/// The score of a finished game `ply` plies from the root, or `None`
+/// if it isn't finished. `stuck`: the side to move has no legal move.
+fn terminal_score(board: &Board, stuck: bool, ply: usize) -> Option<i32> {
+    let status = if stuck {
+        board.status()
+    } else {
+        board.rules().win.decide(board, false)?
+    };
+    let winner = match status {
+        GameStatus::Checkmate { winner }
+        | GameStatus::BrainrotWin { winner }
+        | GameStatus::Won { winner, .. } => winner,
+        GameStatus::Stalemate | GameStatus::Draw { .. } => return Some(0),
+        GameStatus::Ongoing | GameStatus::Check { .. } => return None,
+    };
+    let score = MATE - ply as i32;
+    Some(if winner == board.flags.side_to_move {
+        score
+    } else {
+        -score
+    })
+}
+
+/// Mate scores are stored relative to the node, not the root, so a
+/// table hit at another ply reads the right distance.
+fn to_table(score: i32, ply: usize) -> i32 {
+    if score > MATE - MAX_PLY as i32 {
+        score + ply as i32
+    } else if score < -MATE + MAX_PLY as i32 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn from_table(score: i32, ply: usize) -> i32 {
+    if score > MATE - MAX_PLY as i32 {
+        score - ply as i32
+    } else if score < -MATE + MAX_PLY as i32 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::fen::fen_to_board;
+
+    fn san(board: &Board, m: &Option<GameMove>) -> String {
+        board.move_to_san(m.as_ref().expect("a move")).unwrap()
+    }
+
+    /// Plays `line` out with `make_move`, which re-validates each move.
+    fn assert_line_is_legal(board: &Board, line: &[GameMove]) {
+        let mut board = board.clone();
+        for m in line {
+            board.make_move(m.clone()).unwrap();
+        }
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let board = fen_to_board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let result = best_move(&board, Limits::depth(3));
+        assert_eq!(san(&board, &result.best), "Ra8#");
+        assert_eq!(result.score, MATE - 1);
+    }
+
+    #[test]
+    fn takes_a_hanging_queen() {
+        let board = fen_to_board("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let result = best_move(&board, Limits::depth(2));
+        assert_eq!(san(&board, &result.best), "Rxd5");
+        assert!(result.score > 300, "{}", result.score);
+        assert_line_is_legal(&board, &result.pv);
+    }
+
+    #[test]
+    fn finished_game_has_no_move() {
+        let board = fen_to_board("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        let result = best_move(&board, Limits::depth(2));
+        assert_eq!(result.best, None);
+        assert_eq!(result.score, -MATE);
+    }
+
+    #[test]
+    fn node_budget_is_respected() {
+        let board =
+            fen_to_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let result = best_move(&board, Limits::nodes(300));
+        assert!(result.nodes <= 301, "{}", result.nodes);
+        let best = result.best.expect("a move even when cut short");
+        assert!(board.all_legal_moves(Color::White).contains(&best));
+    }
+
+    /// A score that rests on a repetition holds for the path that led
+    /// to it, so the table keeps only its move.
+    #[test]
+    fn repetition_scores_stay_out_of_the_table() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut repeated = fen_to_board(start).unwrap();
+        for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+            let m = repeated.san_to_move(san).unwrap();
+            repeated.make_move(m).unwrap();
+        }
+        let mut fresh = fen_to_board(start).unwrap();
+        for (board, depth) in [(&mut repeated, 0), (&mut fresh, 2)] {
+            let mut search = Search::new(board, Limits::depth(2));
+            search.negamax(board, 2, 0, -MATE, MATE, &mut Vec::new());
+            let entry = &search.table[&board.position_hash()];
+            assert_eq!(entry.depth, depth);
+            assert!(entry.best.is_some());
+        }
+    }
+
+    /// A variant win counts like a mate: the king walks onto the hill.
+    #[test]
+    fn walks_onto_the_hill() {
+        let board =
+            fen_to_board("4k3/8/8/8/8/4K3/8/8 w - - 0 1 tr=full p=0 win=checkmate,koth").unwrap();
+        let result = best_move(&board, Limits::depth(2));
+        let target = match &result.best.as_ref().unwrap().move_type {
+            MoveType::MoveTo(t) => t.clone(),
+            other => panic!("expected a king step, got {other:?}"),
+        };
+        assert!(hill_squares(&board).contains(&target), "{target:?}");
+        assert_eq!(result.score, MATE - 1);
+    }
+
+    /// Fairy pieces, and Duck Chess's extra half-move, go through the
+    /// same legal moves: the line found replays with `make_move`.
+    #[test]
+    fn fairy_lines_are_legal() {
+        for fen in [
+            "4k3/8/3s4/2p5/5P2/4S3/1M6/(P=BUS)3K2(P=G(H=7-7)) w - -",
+            "4k3/8/8/8/3(P=BUS(P=(K)))4/8/8/4r3 w - - 0 1",
+            "7k/8/8/8/R6r/8/8/K7 w - - 0 1 tr=full p=0 variants=duck_chess",
+        ] {
+            let board = fen_to_board(fen).unwrap();
+            let result = best_move(&board, Limits::depth(2));
+            assert!(result.best.is_some(), "{fen}");
+            assert_eq!(result.pv.first(), result.best.as_ref(), "{fen}");
+            assert_line_is_legal(&board, &result.pv);
+        }
+    }
+}
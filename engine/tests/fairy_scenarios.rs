@@ -55,21 +55,33 @@ fn skibidi_phase_shift_freezes_pieces_in_radius() {
 }
 
 fn empty_board() -> Board {
-    use engine::board::{BoardFlags, TrainTickRate, square::Square};
-    Board {
-        grid: vec![vec![Square::new(); 8]; 8],
-        flags: BoardFlags {
+    use engine::board::{BoardFlags, DuckPhase, TrainTickRate, square::Square};
+    Board::new(
+        vec![vec![Square::new(); 8]; 8].into(),
+        BoardFlags {
             side_to_move: Color::White,
             white_can_castle_kingside: false,
             white_can_castle_queenside: false,
             black_can_castle_kingside: false,
             black_can_castle_queenside: false,
+            castle_rook_files: Default::default(),
+            promotion_targets: Vec::new(),
             en_passant_target: None,
             train_tick_rate: TrainTickRate::EveryFullTurn,
             ply_count: 0,
             last_move: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            extra_moves: 0,
+            variants: Vec::new(),
+            duck_phase: DuckPhase::PieceMove,
+            win_conditions: Vec::new(),
+            white_checks: 0,
+            black_checks: 0,
+            white_pocket: Vec::new(),
+            black_pocket: Vec::new(),
         },
-    }
+    )
 }
 
 /// Bus carrying a passenger pawn: the passenger can exit via the
@@ -82,7 +94,7 @@ fn bus_passenger_can_exit_via_piece_in_carrier() {
     let mut board = empty_board();
     let bus_with_pawn = PieceType::Bus(engine::pieces::fairy::bus::Bus {
         color: Color::White,
-        pieces: vec![PieceType::new_pawn(Color::White)],
+        pieces: vec![PieceType::new_pawn(Color::White)].into(),
     });
     board.grid[3][3] = engine::board::square::Square::new().set_piece(bus_with_pawn);
 
@@ -17,21 +17,21 @@ use engine::board::{Board, fen::fen_to_board};
 /// Standard perft: recursively counts leaf positions reachable in
 /// `depth` plies of legal play from `board`. Returns 1 at depth 0 so
 /// it functions as a counting unit at the leaves.
-fn perft(board: &Board, depth: u32) -> u64 {
+///
+/// Walks the tree on a single board with `make_move` / `unmake_move`
+/// rather than cloning per node; the board is back to its starting
+/// state when this returns.
+fn perft(board: &mut Board, depth: u32) -> u64 {
     if depth == 0 {
         return 1;
     }
     let mut count = 0u64;
-    for (coord, piece) in board.all_pieces() {
-        if piece.get_color() != board.flags.side_to_move {
-            continue;
-        }
-        for m in board.legal_moves(&coord) {
-            let mut next = board.clone();
-            next.make_move(m)
-                .expect("legal_moves output must apply cleanly via make_move");
-            count += perft(&next, depth - 1);
-        }
+    for m in board.all_legal_moves(board.flags.side_to_move) {
+        let undo = board
+            .make_move(m)
+            .expect("all_legal_moves output must apply cleanly via make_move");
+        count += perft(board, depth - 1);
+        board.unmake_move(undo);
     }
     count
 }
@@ -45,17 +45,17 @@ fn standard_start() -> Board {
 
 #[test]
 fn perft_start_depth_1() {
-    assert_eq!(perft(&standard_start(), 1), 20);
+    assert_eq!(perft(&mut standard_start(), 1), 20);
 }
 
 #[test]
 fn perft_start_depth_2() {
-    assert_eq!(perft(&standard_start(), 2), 400);
+    assert_eq!(perft(&mut standard_start(), 2), 400);
 }
 
 #[test]
 fn perft_start_depth_3() {
-    assert_eq!(perft(&standard_start(), 3), 8902);
+    assert_eq!(perft(&mut standard_start(), 3), 8902);
 }
 
 /// Depth 4 = 197,281. Slow under the cloning legal_moves
@@ -63,7 +63,14 @@ fn perft_start_depth_3() {
 #[test]
 #[ignore]
 fn perft_start_depth_4() {
-    assert_eq!(perft(&standard_start(), 4), 197_281);
+    assert_eq!(perft(&mut standard_start(), 4), 197_281);
+}
+
+/// `Board::perft` counts a plain standard position on bitboards, so
+/// the depths the make/unmake walk leaves to `--ignored` run here.
+#[test]
+fn bitboard_perft_start_depth_4() {
+    assert_eq!(standard_start().perft(4), 197_281);
 }
 
 /// Kiwipete — the classic perft test position, packed with castling,
@@ -78,13 +85,13 @@ fn kiwipete() -> Board {
 
 #[test]
 fn perft_kiwipete_depth_1() {
-    assert_eq!(perft(&kiwipete(), 1), 48);
+    assert_eq!(perft(&mut kiwipete(), 1), 48);
 }
 
 #[test]
 #[ignore]
 fn perft_kiwipete_depth_2() {
-    assert_eq!(perft(&kiwipete(), 2), 2039);
+    assert_eq!(perft(&mut kiwipete(), 2), 2039);
 }
 
 /// "Position 3" — endgame-y perft anchor designed to exercise pawn
@@ -95,18 +102,70 @@ fn position_three() -> Board {
 
 #[test]
 fn perft_position_three_depth_1() {
-    assert_eq!(perft(&position_three(), 1), 14);
+    assert_eq!(perft(&mut position_three(), 1), 14);
 }
 
 #[test]
 fn perft_position_three_depth_2() {
-    assert_eq!(perft(&position_three(), 2), 191);
+    assert_eq!(perft(&mut position_three(), 2), 191);
+}
+
+/// The make/unmake walk must hand the board back untouched — grid,
+/// flags, and the cached position hash alike. Position three has
+/// checks, en passant and a quiet-move history to unwind.
+#[test]
+fn perft_unmake_restores_the_board() {
+    let mut board = position_three();
+    let start = board.clone();
+    let start_hash = board.position_hash();
+    perft(&mut board, 2);
+    assert_eq!(board, start);
+    assert_eq!(board.position_hash(), start_hash);
 }
 
 #[test]
 #[ignore]
 fn perft_position_three_depth_3() {
-    assert_eq!(perft(&position_three(), 3), 2812);
+    assert_eq!(perft(&mut position_three(), 3), 2812);
+}
+
+#[test]
+fn bitboard_perft_deeper_reference_counts() {
+    assert_eq!(kiwipete().perft(3), 97_862);
+    assert_eq!(position_three().perft(4), 43_238);
+}
+
+/// Chess960 positions from the published Fischer-random perft tables,
+/// castling rights in Shredder notation. Both kings start on the g-file
+/// with a rook beside them, so their castles overlap the king's and
+/// rook's own squares.
+fn chess960_one() -> Board {
+    fen_to_board("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9").unwrap()
+}
+
+fn chess960_two() -> Board {
+    fen_to_board("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9").unwrap()
+}
+
+#[test]
+fn perft_chess960_depth_2() {
+    assert_eq!(perft(&mut chess960_one(), 1), 21);
+    assert_eq!(perft(&mut chess960_one(), 2), 528);
+    assert_eq!(perft(&mut chess960_two(), 1), 21);
+    assert_eq!(perft(&mut chess960_two(), 2), 807);
+}
+
+#[test]
+#[ignore]
+fn perft_chess960_depth_3() {
+    assert_eq!(perft(&mut chess960_one(), 3), 12_189);
+    assert_eq!(perft(&mut chess960_two(), 3), 18_002);
+}
+
+#[test]
+fn bitboard_perft_chess960_depth_3() {
+    assert_eq!(chess960_one().perft(3), 12_189);
+    assert_eq!(chess960_two().perft(3), 18_002);
 }
 
 /// Fairy-piece smoke perft. No canonical reference number exists for
@@ -145,14 +204,21 @@ fn perft_fairy_setup_depth_1_smoke() {
     // smoke test — if move-gen for any piece changes, this trips.
     // Depth 1 captures move-gen breadth; depth 2 captures composition
     // (see the `#[ignore]`d depth-2 variant below).
-    let board = fairy_setup();
-    let count = perft(&board, 1);
+    let mut board = fairy_setup();
+    let count = perft(&mut board, 1);
     assert_eq!(
         count, FAIRY_PERFT_DEPTH_1,
         "fairy-setup depth-1 perft drifted — investigate which piece changed"
     );
 }
 
+/// A fairy position has no bitboard form; `Board::perft` walks it
+/// with make/unmake instead and lands on the same count.
+#[test]
+fn board_perft_falls_back_for_fairy_pieces() {
+    assert_eq!(fairy_setup().perft(1), FAIRY_PERFT_DEPTH_1);
+}
+
 /// Plan-05 audit (B6): the 40→47 constant bump is the only guard for
 /// the `symbol_to_piece` `M`/`m` arm. Anchor it structurally so a
 /// regression names the Monkey instead of a generic "perft drifted"
@@ -177,8 +243,8 @@ fn perft_fairy_setup_depth_2_smoke() {
     // Depth 2 — the recommended fairy-piece regression target. Slow
     // enough to gate behind `--ignored`. Locks in compositional
     // behaviour (move-gen → make_move → next-side move-gen).
-    let board = fairy_setup();
-    let count = perft(&board, 2);
+    let mut board = fairy_setup();
+    let count = perft(&mut board, 2);
     assert_eq!(
         count, FAIRY_PERFT_DEPTH_2,
         "fairy-setup depth-2 perft drifted — investigate which piece changed"
@@ -18,6 +18,9 @@
 //! 4. move-changes-state — `make_move` is never a no-op on a legal
 //!    move. The board after a move must differ from the board before.
 //!    Catches an entire class of "move silently dropped" bugs.
+//! 5. unmake round-trip — `unmake_move` with the record `make_move`
+//!    returned restores the pre-move board exactly, cached position
+//!    hash included (train ticks and tornado decay too).
 //!
 //! Note: `picks` is sized `1..40` so the inner loop runs at least
 //! once, and the per-iteration `iters_performed` counter is asserted
@@ -141,7 +144,7 @@ fn fuzz_fen() -> impl Strategy<Value = String> {
 
 proptest! {
     /// Drive 1-39 random legal moves from the start position and assert
-    /// all four properties at each step.
+    /// all five properties at each step.
     #[test]
     fn fen_roundtrip_and_invariants_under_random_play(
         picks in prop::collection::vec(any::<u32>(), 1..40)
@@ -163,9 +166,18 @@ proptest! {
 
             // Property 2: every legal move applies cleanly.
             let mut after = board.clone();
-            after.make_move(chosen)
+            let undo = after.make_move(chosen)
                 .expect("legal_moves output must apply via make_move");
 
+            // Property 5: unmake restores the pre-move board.
+            let mut rewound = after.clone();
+            rewound.unmake_move(undo);
+            prop_assert_eq!(&rewound, &board_before, "unmake_move mismatch");
+            prop_assert_eq!(
+                rewound.position_hash(),
+                board_before.position_hash()
+            );
+
             // Property 4: a legal move actually changes the board.
             prop_assert_ne!(
                 &after, &board_before,
@@ -197,7 +209,7 @@ proptest! {
         );
     }
 
-    /// Same four properties, but driving a board with an active train
+    /// Same five properties, but driving a board with an active train
     /// loop. Each player has only a king on a corner; almost every
     /// step ticks the train. Without the round-3–round-6 train work
     /// this property would surface train-related regressions (lost
@@ -235,9 +247,18 @@ proptest! {
             let board_before = board.clone();
 
             let mut after = board.clone();
-            after.make_move(chosen)
+            let undo = after.make_move(chosen)
                 .expect("legal_moves output must apply via make_move");
 
+            // Property 5: unmake restores the pre-move board.
+            let mut rewound = after.clone();
+            rewound.unmake_move(undo);
+            prop_assert_eq!(&rewound, &board_before, "unmake_move mismatch");
+            prop_assert_eq!(
+                rewound.position_hash(),
+                board_before.position_hash()
+            );
+
             prop_assert_ne!(
                 &after, &board_before,
                 "make_move on a legal move must not be a no-op"
@@ -362,7 +383,7 @@ proptest! {
         // Survival (no panic) is the assertion for any fuzz_fen() input.
     }
 
-    /// The four random-play invariants (Property 1–4) driven from a
+    /// The five random-play invariants (Property 1–5) driven from a
     /// Stormcaller position so `PlaceTornado` actually fires and the
     /// tornado compulsion/trap/tick/dissipation run through real
     /// `make_move`. Closes the Round-A gap that the random-play
@@ -386,9 +407,18 @@ proptest! {
             let board_before = board.clone();
 
             let mut after = board.clone();
-            after.make_move(chosen)
+            let undo = after.make_move(chosen)
                 .expect("a legal_moves move must apply via make_move (incl. PlaceTornado / compelled / trapped positions)");
 
+            // Property 5: unmake restores the pre-move board.
+            let mut rewound = after.clone();
+            rewound.unmake_move(undo);
+            prop_assert_eq!(&rewound, &board_before, "unmake_move mismatch");
+            prop_assert_eq!(
+                rewound.position_hash(),
+                board_before.position_hash()
+            );
+
             prop_assert_ne!(
                 &after, &board_before,
                 "make_move on a legal move must not be a no-op (PlaceTornado flips side + adds the condition)"
@@ -83,8 +83,8 @@ let activeBrushButton: HTMLButtonElement | null = null;
 /// rings its wired receivers; clicking other receivers toggles them.
 let inspectedSquare: Coord | null = null;
 
-const STARTING_FEN = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - tr=full p=0";
-const EMPTY_FEN = "8/8/8/8/8/8/8/8 w - - tr=full p=0";
+const STARTING_FEN = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 tr=full p=0";
+const EMPTY_FEN = "8/8/8/8/8/8/8/8 w - - 0 1 tr=full p=0";
 
 let board: Square[][] = parseFEN(EMPTY_FEN);
 let flags: BoardFlags = { ...DEFAULT_FLAGS };
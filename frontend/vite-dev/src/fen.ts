@@ -33,6 +33,11 @@ export type BoardFlags = {
   trainTickRate: TrainTickRate;
   /// Plan 09: monotonic ply counter, bumped at every successful move.
   plyCount: number;
+  /// Standard FEN halfmove clock (plies since the last pawn move or
+  /// capture). Drives the engine's fifty-move draw.
+  halfmoveClock: number;
+  /// Standard FEN fullmove number. Starts at 1, bumped after Black moves.
+  fullmoveNumber: number;
 };
 
 export const DEFAULT_FLAGS: BoardFlags = {
@@ -41,6 +46,8 @@ export const DEFAULT_FLAGS: BoardFlags = {
   enPassant: null,
   trainTickRate: { kind: "EveryFullTurn" },
   plyCount: 0,
+  halfmoveClock: 0,
+  fullmoveNumber: 1,
 };
 
 /// Split a full FEN ("<grid> <stm> <castling> <ep>") into its grid +
@@ -49,15 +56,22 @@ export const DEFAULT_FLAGS: BoardFlags = {
 /// fallback in the engine, so a bare grid round-trips identically.
 export function parseFENFlags(fen: string): BoardFlags {
   const parts = fen.trim().split(/\s+/);
-  // parts[0] is the grid; the rest are flag fields:
-  //   [1] stm, [2] castling, [3] ep, [4] train-tick rate, [5] ply count.
-  // Plan 09 appended trainTickRate + plyCount; both fall back to engine
-  // defaults when absent, so older grid-only FENs still round-trip.
+  // parts[0] is the grid; [1] stm, [2] castling, [3] ep are positional.
+  // The tail is classified by token shape, mirroring the engine's
+  // `fen_to_board`: bare integers are the standard halfmove clock then
+  // fullmove number, `tr=` / `p=` are the plan-09 fields. Anything
+  // absent falls back to engine defaults, so older FENs still round-trip.
   const stm = parts[1];
   const castle = parts[2];
   const ep = parts[3];
-  const tr = parts[4];
-  const p = parts[5];
+  let tr: string | undefined;
+  let p: string | undefined;
+  const counters: number[] = [];
+  for (const tok of parts.slice(4)) {
+    if (tok.startsWith("p=")) p = tok;
+    else if (/^\d+$/.test(tok)) counters.push(Number.parseInt(tok, 10));
+    else if (parseTrainTickRate(tok) !== null) tr = tok;
+  }
 
   const sideToMove: Side = stm === "b" ? "b" : "w";
 
@@ -75,8 +89,10 @@ export function parseFENFlags(fen: string): BoardFlags {
   const enPassant = ep === undefined || ep === "-" ? null : ep;
   const trainTickRate = parseTrainTickRate(tr) ?? { kind: "EveryFullTurn" };
   const plyCount = parsePlyCount(p) ?? 0;
+  const halfmoveClock = counters[0] ?? 0;
+  const fullmoveNumber = counters[1] !== undefined && counters[1] > 0 ? counters[1] : 1;
 
-  return { sideToMove, castling, enPassant, trainTickRate, plyCount };
+  return { sideToMove, castling, enPassant, trainTickRate, plyCount, halfmoveClock, fullmoveNumber };
 }
 
 function parseTrainTickRate(field: string | undefined): TrainTickRate | null {
@@ -130,7 +146,7 @@ export function serializeFullFEN(board: Square[][], flags: BoardFlags): string {
   const ep = flags.enPassant ?? "-";
   const tr = trainTickRateToFEN(flags.trainTickRate);
   const p = `p=${flags.plyCount}`;
-  return `${grid} ${stm} ${castling} ${ep} ${tr} ${p}`;
+  return `${grid} ${stm} ${castling} ${ep} ${flags.halfmoveClock} ${flags.fullmoveNumber} ${tr} ${p}`;
 }
 
 // For pretty optional rendering